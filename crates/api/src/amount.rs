@@ -0,0 +1,232 @@
+//! Bitcoin amount types with human-friendly parsing.
+//!
+//! Both [`Msat`] and [`Sat`] parse strings such as `1500000`, `1500000msat`, `21k sat`,
+//! `0.001btc` or `2.5 mBTC`. A bare number is read in the type's own unit. Parsing is exact:
+//! values that overflow `u64` or carry precision below the target unit are rejected.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+const MSAT_PER_SAT: u128 = 1_000;
+const MSAT_PER_MBTC: u128 = 100_000_000;
+const MSAT_PER_BTC: u128 = 100_000_000_000;
+
+/// Error returned when an amount string cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmountParseError(String);
+
+impl fmt::Display for AmountParseError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "invalid amount: {}", self.0)
+	}
+}
+
+impl std::error::Error for AmountParseError {}
+
+fn err(msg: impl Into<String>) -> AmountParseError {
+	AmountParseError(msg.into())
+}
+
+/// Removes `,` thousands separators from the integer part of `number`.
+///
+/// A comma is only accepted between groups of exactly three digits, so `1,500,000` is read as
+/// 1500000 while `0,001` or `1,5` (decimal commas) are rejected rather than misread.
+fn strip_thousands_separators(number: &str) -> Result<String, AmountParseError> {
+	if !number.contains(',') {
+		return Ok(number.to_string());
+	}
+	let (int_part, frac_part) =
+		number.split_once('.').map_or((number, None), |(i, f)| (i, Some(f)));
+	let mut groups = int_part.split(',');
+	let first = groups.next().unwrap_or_default();
+	let grouped = !frac_part.is_some_and(|f| f.contains(','))
+		&& (1..=3).contains(&first.len())
+		&& !first.starts_with('0')
+		&& groups.all(|g| g.len() == 3);
+	if !grouped {
+		return Err(err(format!(
+			"{number:?} uses ',' outside of thousands separators (use '.' for decimals)"
+		)));
+	}
+	let mut out = int_part.replace(',', "");
+	if let Some(frac) = frac_part {
+		out.push('.');
+		out.push_str(frac);
+	}
+	Ok(out)
+}
+
+/// Parses `s` into millisatoshis; a bare number is multiplied by `default_unit_msat`.
+fn parse_msat(s: &str, default_unit_msat: u128) -> Result<u128, AmountParseError> {
+	let s: String = s.trim().chars().filter(|c| *c != '_').collect();
+	let num_end = s.find(|c: char| !c.is_ascii_digit() && c != '.' && c != ',').unwrap_or(s.len());
+	let (number, rest) = s.split_at(num_end);
+	if number.is_empty() {
+		return Err(err(format!("{s:?} does not start with a number")));
+	}
+	let number = strip_thousands_separators(number)?;
+	let number = number.as_str();
+
+	let mut rest = rest.trim_start();
+	let mut multiplier = 1u128;
+	if let Some(r) = rest.strip_prefix('k').or_else(|| rest.strip_prefix('K')) {
+		multiplier = 1_000;
+		rest = r.trim_start();
+	}
+	let unit_msat = match rest.to_ascii_lowercase().as_str() {
+		"" => default_unit_msat,
+		"msat" | "msats" => 1,
+		"sat" | "sats" => MSAT_PER_SAT,
+		"mbtc" => MSAT_PER_MBTC,
+		"btc" => MSAT_PER_BTC,
+		other => {
+			return Err(err(format!("unknown unit {other:?} (expected msat, sat, mbtc or btc)")))
+		},
+	};
+
+	let (int_part, frac_part) = number.split_once('.').unwrap_or((number, ""));
+	if frac_part.contains('.') || (int_part.is_empty() && frac_part.is_empty()) {
+		return Err(err(format!("{number:?} is not a number")));
+	}
+	if frac_part.len() > 18 {
+		return Err(err("too many decimal places"));
+	}
+	let overflow = || err(format!("{s:?} is too large"));
+	let scale = 10u128.pow(frac_part.len() as u32);
+	let digits = format!("{int_part}{frac_part}");
+	let scaled: u128 =
+		if digits.is_empty() { 0 } else { digits.parse().map_err(|_| overflow())? };
+	let total = scaled
+		.checked_mul(unit_msat)
+		.and_then(|v| v.checked_mul(multiplier))
+		.ok_or_else(overflow)?;
+	if total % scale != 0 {
+		return Err(err(format!("{s:?} is more precise than 1 msat")));
+	}
+	let msat = total / scale;
+	if msat > u64::MAX as u128 {
+		return Err(overflow());
+	}
+	Ok(msat)
+}
+
+/// Amount in millisatoshis.
+#[derive(
+	Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct Msat(pub u64);
+
+impl Msat {
+	/// Amount in millisatoshis.
+	pub fn to_msat(self) -> u64 {
+		self.0
+	}
+}
+
+impl FromStr for Msat {
+	type Err = AmountParseError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		parse_msat(s, 1).map(|v| Msat(v as u64))
+	}
+}
+
+impl fmt::Display for Msat {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} msat", self.0)
+	}
+}
+
+/// Amount in satoshis.
+#[derive(
+	Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct Sat(pub u64);
+
+impl Sat {
+	/// Amount in satoshis.
+	pub fn to_sat(self) -> u64 {
+		self.0
+	}
+}
+
+impl FromStr for Sat {
+	type Err = AmountParseError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let msat = parse_msat(s, MSAT_PER_SAT)?;
+		if msat % MSAT_PER_SAT != 0 {
+			return Err(err(format!("{:?} is not a whole number of satoshis", s.trim())));
+		}
+		Ok(Sat((msat / MSAT_PER_SAT) as u64))
+	}
+}
+
+impl fmt::Display for Sat {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} sat", self.0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn units_and_prefixes() {
+		assert_eq!("21k sat".parse(), Ok(Msat(21_000_000)));
+		assert_eq!("0.001btc".parse(), Ok(Msat(100_000_000)));
+		assert_eq!("2.5 mBTC".parse(), Ok(Msat(250_000_000)));
+		assert_eq!("1500000msat".parse(), Ok(Msat(1_500_000)));
+		assert_eq!("1,500,000 MSATS".parse(), Ok(Msat(1_500_000)));
+		assert_eq!("1_500_000 msat".parse(), Ok(Msat(1_500_000)));
+		assert_eq!("12,345.5 sat".parse(), Ok(Msat(12_345_500)));
+		assert_eq!("0.001btc".parse(), Ok(Sat(100_000)));
+	}
+
+	#[test]
+	fn bare_numbers_use_the_default_unit() {
+		assert_eq!(" 1500 ".parse(), Ok(Msat(1_500)));
+		assert_eq!("1500".parse(), Ok(Sat(1_500)));
+		assert_eq!("1.5k".parse(), Ok(Sat(1_500)));
+	}
+
+	#[test]
+	fn overflow_is_rejected() {
+		assert_eq!(u64::MAX.to_string().parse(), Ok(Msat(u64::MAX)));
+		assert!("18446744073709551616".parse::<Msat>().is_err());
+		assert!("18446744073709551615 sat".parse::<Msat>().is_err());
+		assert!("18446744073709552 sat".parse::<Sat>().is_err());
+		assert!("1000000000000000000000000000000000000000btc".parse::<Msat>().is_err());
+	}
+
+	#[test]
+	fn precision_below_the_unit_is_rejected() {
+		assert!("0.5msat".parse::<Msat>().is_err());
+		assert!("0.000000000001btc".parse::<Msat>().is_err());
+		assert!("1500msat".parse::<Sat>().is_err());
+		assert_eq!("0.00000000001btc".parse(), Ok(Msat(1)));
+		assert!("0.1234567890123456789".parse::<Msat>().is_err(), "too many decimal places");
+	}
+
+	#[test]
+	fn commas_must_separate_thousands() {
+		for s in ["0,001btc", "1,5sat", "1,50", "1234,567", ",500", "1,", "1,000.0,1", "1_500,000"]
+		{
+			assert!(s.parse::<Msat>().is_err(), "{s:?} parsed");
+		}
+	}
+
+	#[test]
+	fn junk_is_rejected() {
+		for s in ["", "  ", "abc", "sat", ".", "1.2.3", "12 sats please", "1kk sat", "-5", "1e3"] {
+			assert!(s.parse::<Msat>().is_err(), "{s:?} parsed");
+		}
+	}
+}
@@ -0,0 +1,305 @@
+//! Recording and replaying the HTTP exchanges of [`RgbLdkClient`](crate::RgbLdkClient).
+//!
+//! A cassette is a JSON file ([`FILE_NAME`]) in a directory of its own. While recording, the
+//! client appends every request and the daemon's answer to it; while replaying, it answers each
+//! request from the cassette and never opens a connection. Values under the keys in
+//! [`REDACTED_FIELDS`] are replaced with [`REDACTED`] before anything is written, so a cassette
+//! can be attached to a bug report.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Name of the cassette file inside the `--record` / `--replay` directory.
+pub const FILE_NAME: &str = "cassette.json";
+
+/// Version of the cassette layout written by this crate.
+pub const FORMAT: u32 = 1;
+
+/// JSON keys whose string values never reach a cassette.
+pub const REDACTED_FIELDS: &[&str] =
+	&["preimage", "secret", "payment_secret", "password", "mnemonic", "authorization"];
+
+/// Placeholder written in place of a redacted value.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Recorded HTTP exchanges, in the order they happened.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+	/// Layout version ([`FORMAT`]).
+	pub format: u32,
+	/// `rgbldk-api` version of the client that recorded the cassette.
+	pub recorded_with: String,
+	/// Exchanges in request order.
+	pub interactions: Vec<Interaction>,
+}
+
+/// One request and what came back.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+	/// The request, as sent.
+	pub request: RecordedRequest,
+	/// The daemon's answer, or `None` when no answer arrived.
+	pub response: Option<RecordedResponse>,
+	/// Transport error, when no answer arrived.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+/// A request without the daemon's address.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+	/// HTTP method.
+	pub method: String,
+	/// Path and query, e.g. `/api/v1/channels`.
+	pub path: String,
+	/// JSON body, redacted.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub body: Option<Value>,
+}
+
+/// A response as the client read it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+	/// HTTP status code.
+	pub status: u16,
+	/// Body parsed as JSON, redacted.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub body: Option<Value>,
+	/// Body as text, when it was not valid JSON.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub raw: Option<String>,
+}
+
+impl RecordedResponse {
+	/// Records `status` and `bytes`, redacting the body if it is JSON.
+	pub fn new(status: u16, bytes: &[u8]) -> Self {
+		match serde_json::from_slice::<Value>(bytes) {
+			Ok(mut body) => {
+				redact(&mut body);
+				Self { status, body: Some(body), raw: None }
+			},
+			Err(_) => {
+				Self { status, body: None, raw: Some(String::from_utf8_lossy(bytes).into_owned()) }
+			},
+		}
+	}
+
+	/// The body as the daemon sent it (apart from redactions).
+	pub fn bytes(&self) -> Vec<u8> {
+		match (&self.body, &self.raw) {
+			(Some(body), _) => serde_json::to_vec(body).expect("JSON values serialize"),
+			(None, Some(raw)) => raw.clone().into_bytes(),
+			(None, None) => Vec::new(),
+		}
+	}
+}
+
+impl Default for Cassette {
+	fn default() -> Self {
+		Self {
+			format: FORMAT,
+			recorded_with: crate::CRATE_VERSION.to_string(),
+			interactions: Vec::new(),
+		}
+	}
+}
+
+impl Cassette {
+	/// Reads `dir`/[`FILE_NAME`].
+	pub fn load(dir: &Path) -> Result<Self, CassetteError> {
+		let path = dir.join(FILE_NAME);
+		let s = std::fs::read_to_string(&path)
+			.map_err(|source| CassetteError::Io { path: path.clone(), source })?;
+		let cassette: Cassette = serde_json::from_str(&s)
+			.map_err(|source| CassetteError::Format { path: path.clone(), source })?;
+		if cassette.format != FORMAT {
+			return Err(CassetteError::UnsupportedFormat { path, format: cassette.format });
+		}
+		let bad_status = cassette
+			.interactions
+			.iter()
+			.filter_map(|i| i.response.as_ref())
+			.find(|r| !(100..=999).contains(&r.status));
+		if let Some(r) = bad_status {
+			let msg = format!("invalid HTTP status {}", r.status);
+			let source = <serde_json::Error as serde::de::Error>::custom(msg);
+			return Err(CassetteError::Format { path, source });
+		}
+		Ok(cassette)
+	}
+
+	/// Writes the cassette to `dir`/[`FILE_NAME`], creating `dir` if needed.
+	pub fn save(&self, dir: &Path) -> Result<(), CassetteError> {
+		let path = dir.join(FILE_NAME);
+		let io = |source| CassetteError::Io { path: path.clone(), source };
+		std::fs::create_dir_all(dir).map_err(io)?;
+		let mut s = serde_json::to_string_pretty(self).expect("cassettes serialize");
+		s.push('\n');
+		std::fs::write(&path, s).map_err(io)
+	}
+}
+
+/// Replaces the string values under [`REDACTED_FIELDS`] keys, at any depth, with [`REDACTED`].
+pub fn redact(value: &mut Value) {
+	match value {
+		Value::Object(map) => {
+			for (key, v) in map.iter_mut() {
+				let secret = REDACTED_FIELDS.iter().any(|f| key.eq_ignore_ascii_case(f));
+				if secret && v.is_string() {
+					*v = Value::String(REDACTED.to_string());
+				} else {
+					redact(v);
+				}
+			}
+		},
+		Value::Array(items) => items.iter_mut().for_each(redact),
+		_ => {},
+	}
+}
+
+/// Whether a client records to or replays from a cassette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+	/// Requests go to the daemon and are appended to the cassette.
+	Record,
+	/// Requests are answered from the cassette.
+	Replay,
+}
+
+/// Error reading, writing or replaying a cassette.
+#[derive(Debug)]
+pub enum CassetteError {
+	/// The cassette file could not be read or written.
+	Io {
+		/// Cassette file.
+		path: PathBuf,
+		/// Underlying error.
+		source: std::io::Error,
+	},
+	/// The cassette file is not valid cassette JSON.
+	Format {
+		/// Cassette file.
+		path: PathBuf,
+		/// Underlying error.
+		source: serde_json::Error,
+	},
+	/// The cassette was written in a layout this crate does not read.
+	UnsupportedFormat {
+		/// Cassette file.
+		path: PathBuf,
+		/// Layout version found in the file.
+		format: u32,
+	},
+	/// No unused recorded exchange matches the request.
+	NoMatch {
+		/// Request method.
+		method: String,
+		/// Request path.
+		path: String,
+	},
+	/// The recorded request failed before the daemon answered.
+	Transport {
+		/// Request method.
+		method: String,
+		/// Request path.
+		path: String,
+		/// The recorded error.
+		message: String,
+	},
+}
+
+impl fmt::Display for CassetteError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			CassetteError::Io { path, source } => {
+				write!(f, "cassette {}: {source}", path.display())
+			},
+			CassetteError::Format { path, source } => {
+				write!(f, "cassette {} is not valid: {source}", path.display())
+			},
+			CassetteError::UnsupportedFormat { path, format } => write!(
+				f,
+				"cassette {} has format {format}; this build reads format {FORMAT}",
+				path.display()
+			),
+			CassetteError::NoMatch { method, path } => {
+				write!(f, "cassette has no recorded response for {method} {path}")
+			},
+			CassetteError::Transport { message, .. } => {
+				write!(f, "request failed: {message} (replayed)")
+			},
+		}
+	}
+}
+
+impl std::error::Error for CassetteError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			CassetteError::Io { source, .. } => Some(source),
+			CassetteError::Format { source, .. } => Some(source),
+			_ => None,
+		}
+	}
+}
+
+/// Cassette state shared by the clones of a client.
+#[derive(Debug)]
+pub(crate) struct Tape {
+	mode: Mode,
+	dir: PathBuf,
+	cassette: Mutex<Cassette>,
+	/// Replay: which interactions have been served.
+	used: Mutex<Vec<bool>>,
+}
+
+impl Tape {
+	/// Starts an empty cassette in `dir`, replacing an existing one.
+	pub(crate) fn record(dir: PathBuf) -> Result<Self, CassetteError> {
+		let cassette = Cassette::default();
+		cassette.save(&dir)?;
+		Ok(Self { mode: Mode::Record, dir, cassette: Mutex::new(cassette), used: Mutex::default() })
+	}
+
+	/// Loads the cassette in `dir` for replay.
+	pub(crate) fn replay(dir: PathBuf) -> Result<Self, CassetteError> {
+		let cassette = Cassette::load(&dir)?;
+		let used = vec![false; cassette.interactions.len()];
+		Ok(Self { mode: Mode::Replay, dir, cassette: Mutex::new(cassette), used: Mutex::new(used) })
+	}
+
+	pub(crate) fn mode(&self) -> Mode {
+		self.mode
+	}
+
+	/// Appends an exchange and rewrites the cassette, so it survives the process exiting early.
+	pub(crate) fn append(&self, interaction: Interaction) -> Result<(), CassetteError> {
+		let mut cassette = self.cassette.lock().expect("cassette lock");
+		cassette.interactions.push(interaction);
+		cassette.save(&self.dir)
+	}
+
+	/// The first unused exchange recorded for the same method, path and (redacted) body.
+	pub(crate) fn next(&self, request: &RecordedRequest) -> Result<Interaction, CassetteError> {
+		let cassette = self.cassette.lock().expect("cassette lock");
+		let mut used = self.used.lock().expect("cassette lock");
+		let found = cassette
+			.interactions
+			.iter()
+			.enumerate()
+			.find(|(i, recorded)| !used[*i] && recorded.request == *request);
+		match found {
+			Some((i, recorded)) => {
+				used[i] = true;
+				Ok(recorded.clone())
+			},
+			None => Err(CassetteError::NoMatch {
+				method: request.method.clone(),
+				path: request.path.clone(),
+			}),
+		}
+	}
+}
@@ -0,0 +1,598 @@
+//! Typed async client for the `/api/v1` HTTP surface.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub use reqwest::StatusCode;
+
+use crate::cassette::{self, CassetteError, Interaction, RecordedRequest, RecordedResponse, Tape};
+use crate::http::{
+	BalancesDto, Bolt11DecodeRequest, Bolt11DecodeResponse, Bolt11PayRequest, Bolt11PayResponse,
+	Bolt11ReceiveRequest, Bolt11ReceiveResponse, Bolt11ReceiveVarRequest, Bolt11SendRequest,
+	Bolt11SendUsingAmountRequest, Bolt12OfferDecodeRequest, Bolt12OfferDecodeResponse,
+	Bolt12OfferReceiveRequest, Bolt12OfferReceiveVarRequest, Bolt12OfferResponse,
+	Bolt12OfferSendRequest, Bolt12RefundDecodeRequest, Bolt12RefundDecodeResponse,
+	Bolt12RefundInitiateRequest, Bolt12RefundInitiateResponse, Bolt12RefundRequestPaymentRequest,
+	Bolt12RefundRequestPaymentResponse, ChannelDetailsExtendedDto, CloseChannelRequest,
+	ErrorResponse, EventAckRequest, ListeningAddressesResponse, NodeIdResponse, OkResponse,
+	OpenChannelRequest, OpenChannelResponse, PaymentDetailsDto, PaymentWaitRequest,
+	PaymentWaitResponse, PaymentsListRequest, PaymentsPage, PeerConnectRequest, PeerDetailsDto,
+	PeerDisconnectRequest, QueuedEvent, SendResponse, SpontaneousSendRequest, StatusDto,
+	VersionResponse, WalletNewAddressResponse,
+};
+use crate::ids::PaymentId;
+use crate::stream::{EventStream, EventSubscription};
+use crate::API_VERSION;
+
+/// Async client for `rgbldkd`, with one method per `/api/v1` route.
+#[derive(Debug, Clone)]
+pub struct RgbLdkClient {
+	base: String,
+	http: reqwest::Client,
+	tape: Option<Arc<Tape>>,
+}
+
+impl RgbLdkClient {
+	/// Creates a client for the daemon at `base`, e.g. `http://127.0.0.1:8500`.
+	pub fn new(base: impl Into<String>) -> Self {
+		Self::with_http_client(base, reqwest::Client::new())
+	}
+
+	/// Creates a client that reuses an existing `reqwest::Client`.
+	pub fn with_http_client(base: impl Into<String>, http: reqwest::Client) -> Self {
+		Self { base: base.into(), http, tape: None }
+	}
+
+	/// Records every exchange into a new cassette in `dir` (see [`cassette`]).
+	pub fn record_to(mut self, dir: impl Into<PathBuf>) -> Result<Self, CassetteError> {
+		self.tape = Some(Arc::new(Tape::record(dir.into())?));
+		Ok(self)
+	}
+
+	/// Answers every request from the cassette in `dir` instead of the daemon.
+	pub fn replay_from(mut self, dir: impl Into<PathBuf>) -> Result<Self, CassetteError> {
+		self.tape = Some(Arc::new(Tape::replay(dir.into())?));
+		Ok(self)
+	}
+
+	/// Whether this client records or replays a cassette.
+	pub fn cassette_mode(&self) -> Option<cassette::Mode> {
+		self.tape.as_ref().map(|t| t.mode())
+	}
+
+	/// Daemon base URL (without the `/api/v1` prefix).
+	pub fn base_url(&self) -> &str {
+		&self.base
+	}
+
+	fn url(&self, path: &str) -> String {
+		join_url(&self.base, &format!("/api/{API_VERSION}/{}", path.trim_start_matches('/')))
+	}
+
+	fn get(&self, path: &str) -> reqwest::RequestBuilder {
+		self.http.get(self.url(path))
+	}
+
+	fn post<B: Serialize + ?Sized>(&self, path: &str, body: &B) -> reqwest::RequestBuilder {
+		self.http.post(self.url(path)).json(body)
+	}
+
+	fn post_empty(&self, path: &str) -> reqwest::RequestBuilder {
+		self.post(path, &serde_json::json!({}))
+	}
+
+	// ---- Health & status ----
+
+	/// `GET /healthz`
+	pub async fn healthz(&self) -> Result<OkResponse, ApiError> {
+		self.send_json(self.get("/healthz")).await
+	}
+
+	/// `GET /readyz` (a `503` with a body is a valid "not ready" answer).
+	pub async fn readyz(&self) -> Result<OkResponse, ApiError> {
+		self.send_json_allow_status(self.get("/readyz"), &[StatusCode::SERVICE_UNAVAILABLE]).await
+	}
+
+	/// `GET /version`
+	pub async fn version(&self) -> Result<VersionResponse, ApiError> {
+		self.send_json(self.get("/version")).await
+	}
+
+	/// `GET /status`
+	pub async fn status(&self) -> Result<StatusDto, ApiError> {
+		self.send_json(self.get("/status")).await
+	}
+
+	/// `GET /node_id`
+	pub async fn node_id(&self) -> Result<NodeIdResponse, ApiError> {
+		self.send_json(self.get("/node_id")).await
+	}
+
+	/// `GET /listening_addresses`
+	pub async fn listening_addresses(&self) -> Result<ListeningAddressesResponse, ApiError> {
+		self.send_json(self.get("/listening_addresses")).await
+	}
+
+	// ---- Wallet ----
+
+	/// `POST /wallet/new_address`
+	pub async fn wallet_new_address(&self) -> Result<WalletNewAddressResponse, ApiError> {
+		self.send_json(self.post_empty("/wallet/new_address")).await
+	}
+
+	/// `POST /wallet/sync`
+	pub async fn wallet_sync(&self) -> Result<OkResponse, ApiError> {
+		self.send_json(self.post_empty("/wallet/sync")).await
+	}
+
+	/// `GET /balances`
+	pub async fn balances(&self) -> Result<BalancesDto, ApiError> {
+		self.send_json(self.get("/balances")).await
+	}
+
+	// ---- Peers ----
+
+	/// `GET /peers`
+	pub async fn peers(&self) -> Result<Vec<PeerDetailsDto>, ApiError> {
+		self.send_json(self.get("/peers")).await
+	}
+
+	/// `POST /peers/connect`
+	pub async fn peer_connect(&self, req: &PeerConnectRequest) -> Result<OkResponse, ApiError> {
+		self.send_json(self.post("/peers/connect", req)).await
+	}
+
+	/// `POST /peers/disconnect`
+	pub async fn peer_disconnect(
+		&self, req: &PeerDisconnectRequest,
+	) -> Result<OkResponse, ApiError> {
+		self.send_json(self.post("/peers/disconnect", req)).await
+	}
+
+	// ---- Channels ----
+
+	/// `GET /channels`
+	pub async fn channels(&self) -> Result<Vec<ChannelDetailsExtendedDto>, ApiError> {
+		self.send_json(self.get("/channels")).await
+	}
+
+	/// `POST /channel/open`
+	pub async fn channel_open(
+		&self, req: &OpenChannelRequest,
+	) -> Result<OpenChannelResponse, ApiError> {
+		self.send_json(self.post("/channel/open", req)).await
+	}
+
+	/// `POST /channel/close`
+	pub async fn channel_close(&self, req: &CloseChannelRequest) -> Result<OkResponse, ApiError> {
+		self.send_json(self.post("/channel/close", req)).await
+	}
+
+	/// `POST /channel/force_close`
+	pub async fn channel_force_close(
+		&self, req: &CloseChannelRequest,
+	) -> Result<OkResponse, ApiError> {
+		self.send_json(self.post("/channel/force_close", req)).await
+	}
+
+	// ---- BOLT11 ----
+
+	/// `POST /bolt11/decode`
+	pub async fn bolt11_decode(
+		&self, req: &Bolt11DecodeRequest,
+	) -> Result<Bolt11DecodeResponse, ApiError> {
+		self.send_json(self.post("/bolt11/decode", req)).await
+	}
+
+	/// `POST /bolt11/receive`
+	pub async fn bolt11_receive(
+		&self, req: &Bolt11ReceiveRequest,
+	) -> Result<Bolt11ReceiveResponse, ApiError> {
+		self.send_json(self.post("/bolt11/receive", req)).await
+	}
+
+	/// `POST /bolt11/receive_var`
+	pub async fn bolt11_receive_var(
+		&self, req: &Bolt11ReceiveVarRequest,
+	) -> Result<Bolt11ReceiveResponse, ApiError> {
+		self.send_json(self.post("/bolt11/receive_var", req)).await
+	}
+
+	/// `POST /bolt11/send`
+	pub async fn bolt11_send(&self, req: &Bolt11SendRequest) -> Result<SendResponse, ApiError> {
+		self.send_json(self.post("/bolt11/send", req)).await
+	}
+
+	/// `POST /bolt11/send_using_amount`
+	pub async fn bolt11_send_using_amount(
+		&self, req: &Bolt11SendUsingAmountRequest,
+	) -> Result<SendResponse, ApiError> {
+		self.send_json(self.post("/bolt11/send_using_amount", req)).await
+	}
+
+	/// `POST /bolt11/pay` (waits for the payment to complete).
+	pub async fn bolt11_pay(&self, req: &Bolt11PayRequest) -> Result<Bolt11PayResponse, ApiError> {
+		self.send_json(self.post("/bolt11/pay", req)).await
+	}
+
+	// ---- BOLT12 ----
+
+	/// `POST /bolt12/offer/receive`
+	pub async fn bolt12_offer_receive(
+		&self, req: &Bolt12OfferReceiveRequest,
+	) -> Result<Bolt12OfferResponse, ApiError> {
+		self.send_json(self.post("/bolt12/offer/receive", req)).await
+	}
+
+	/// `POST /bolt12/offer/receive_var`
+	pub async fn bolt12_offer_receive_var(
+		&self, req: &Bolt12OfferReceiveVarRequest,
+	) -> Result<Bolt12OfferResponse, ApiError> {
+		self.send_json(self.post("/bolt12/offer/receive_var", req)).await
+	}
+
+	/// `POST /bolt12/offer/decode`
+	pub async fn bolt12_offer_decode(
+		&self, req: &Bolt12OfferDecodeRequest,
+	) -> Result<Bolt12OfferDecodeResponse, ApiError> {
+		self.send_json(self.post("/bolt12/offer/decode", req)).await
+	}
+
+	/// `POST /bolt12/offer/send`
+	pub async fn bolt12_offer_send(
+		&self, req: &Bolt12OfferSendRequest,
+	) -> Result<SendResponse, ApiError> {
+		self.send_json(self.post("/bolt12/offer/send", req)).await
+	}
+
+	/// `POST /bolt12/refund/initiate`
+	pub async fn bolt12_refund_initiate(
+		&self, req: &Bolt12RefundInitiateRequest,
+	) -> Result<Bolt12RefundInitiateResponse, ApiError> {
+		self.send_json(self.post("/bolt12/refund/initiate", req)).await
+	}
+
+	/// `POST /bolt12/refund/decode`
+	pub async fn bolt12_refund_decode(
+		&self, req: &Bolt12RefundDecodeRequest,
+	) -> Result<Bolt12RefundDecodeResponse, ApiError> {
+		self.send_json(self.post("/bolt12/refund/decode", req)).await
+	}
+
+	/// `POST /bolt12/refund/request_payment`
+	pub async fn bolt12_refund_request_payment(
+		&self, req: &Bolt12RefundRequestPaymentRequest,
+	) -> Result<Bolt12RefundRequestPaymentResponse, ApiError> {
+		self.send_json(self.post("/bolt12/refund/request_payment", req)).await
+	}
+
+	// ---- Keysend ----
+
+	/// `POST /spontaneous/send`
+	pub async fn spontaneous_send(
+		&self, req: &SpontaneousSendRequest,
+	) -> Result<SendResponse, ApiError> {
+		self.send_json(self.post("/spontaneous/send", req)).await
+	}
+
+	// ---- Payments ----
+
+	/// `GET /payments`
+	pub async fn payments(&self) -> Result<Vec<PaymentDetailsDto>, ApiError> {
+		self.send_json(self.get("/payments")).await
+	}
+
+	/// `POST /payments/list`. Daemons without that route get `GET /payments`, filtered and paged
+	/// here the same way.
+	pub async fn payments_list(&self, req: &PaymentsListRequest) -> Result<PaymentsPage, ApiError> {
+		match self.send_json(self.post("/payments/list", req)).await {
+			Err(e) if is_unsupported(&e) => select_payments(&self.payments().await?, req),
+			page => page,
+		}
+	}
+
+	/// Every page of `POST /payments/list` from `req.cursor` on, merged into one page without a
+	/// `next_cursor`. Daemons without that route get `GET /payments` once, paged here.
+	pub async fn payments_list_all(
+		&self, req: &PaymentsListRequest,
+	) -> Result<PaymentsPage, ApiError> {
+		let mut req = req.clone();
+		let mut all = PaymentsPage { items: Vec::new(), next_cursor: None };
+		let mut fetched: Option<Vec<PaymentDetailsDto>> = None;
+		loop {
+			let page = match &fetched {
+				Some(payments) => select_payments(payments, &req)?,
+				None => match self.send_json(self.post("/payments/list", &req)).await {
+					Err(e) if is_unsupported(&e) => {
+						select_payments(fetched.insert(self.payments().await?), &req)?
+					},
+					page => page?,
+				},
+			};
+			all.items.extend(page.items);
+			match page.next_cursor {
+				Some(cursor) => req.cursor = Some(cursor),
+				None => return Ok(all),
+			}
+		}
+	}
+
+	/// `GET /payment/:payment_id`
+	pub async fn payment(&self, payment_id: &PaymentId) -> Result<PaymentDetailsDto, ApiError> {
+		self.send_json(self.get(&format!("/payment/{payment_id}"))).await
+	}
+
+	/// `POST /payment/:payment_id/wait`
+	///
+	/// Non-2xx answers still carry `checks` and `payment`; see [`ApiError::body_json`].
+	pub async fn payment_wait(
+		&self, payment_id: &PaymentId, req: &PaymentWaitRequest,
+	) -> Result<PaymentWaitResponse, ApiError> {
+		self.send_json(self.post(&format!("/payment/{payment_id}/wait"), req)).await
+	}
+
+	/// `POST /payment/:payment_id/abandon`
+	pub async fn payment_abandon(&self, payment_id: &PaymentId) -> Result<OkResponse, ApiError> {
+		self.send_json(self.post_empty(&format!("/payment/{payment_id}/abandon"))).await
+	}
+
+	// ---- Events ----
+
+	/// `POST /events/wait_next` (long-poll until the queue has a head event).
+	pub async fn events_wait_next(&self) -> Result<QueuedEvent, ApiError> {
+		self.send_json(self.post_empty("/events/wait_next")).await
+	}
+
+	/// `POST /events/handled` (ACKs the head event; with an `id`, only if it is that event).
+	pub async fn events_handled(&self, req: &EventAckRequest) -> Result<OkResponse, ApiError> {
+		self.send_json(self.post("/events/handled", req)).await
+	}
+
+	/// `GET /events/stream` (server-sent events; see [`EventStream`]).
+	///
+	/// Streams are not recorded; with a cassette this fails like an unrecorded request.
+	pub async fn events_stream(&self) -> Result<EventStream, ApiError> {
+		if self.tape.is_some() {
+			let path = format!("/api/{API_VERSION}/events/stream");
+			return Err(CassetteError::NoMatch { method: "GET".into(), path }.into());
+		}
+		let resp = self
+			.get("/events/stream")
+			.header(reqwest::header::ACCEPT, "text/event-stream")
+			.send()
+			.await?;
+		let status = resp.status();
+		if !status.is_success() {
+			return Err(http_error(status, &resp.bytes().await?));
+		}
+		Ok(EventStream::new(resp))
+	}
+
+	/// Subscribes to the event queue: over `/events/stream` when the daemon serves it, otherwise
+	/// (or when a cassette is in use) over long-poll.
+	pub async fn subscribe_events(&self) -> Result<EventSubscription, ApiError> {
+		if self.tape.is_some() {
+			return Ok(EventSubscription::long_poll(self.clone()));
+		}
+		match self.events_stream().await {
+			Ok(stream) => Ok(EventSubscription::stream(self.clone(), stream)),
+			Err(e) if is_unsupported(&e) => Ok(EventSubscription::long_poll(self.clone())),
+			Err(e) => Err(e),
+		}
+	}
+
+	async fn send_json<T: DeserializeOwned>(
+		&self, rb: reqwest::RequestBuilder,
+	) -> Result<T, ApiError> {
+		self.send_json_allow_status(rb, &[]).await
+	}
+
+	async fn send_json_allow_status<T: DeserializeOwned>(
+		&self, rb: reqwest::RequestBuilder, allowed: &[StatusCode],
+	) -> Result<T, ApiError> {
+		let (status, bytes) = self.exchange(rb).await?;
+		if status.is_success() || allowed.contains(&status) {
+			serde_json::from_slice(&bytes).map_err(|source| ApiError::Decode {
+				status,
+				body: String::from_utf8_lossy(&bytes).into_owned(),
+				source,
+			})
+		} else {
+			Err(http_error(status, &bytes))
+		}
+	}
+
+	/// Sends the request, or answers it from the cassette, and returns the raw answer.
+	async fn exchange(
+		&self, rb: reqwest::RequestBuilder,
+	) -> Result<(StatusCode, Vec<u8>), ApiError> {
+		let Some(tape) = &self.tape else {
+			let resp = rb.send().await?;
+			let status = resp.status();
+			return Ok((status, resp.bytes().await?.to_vec()));
+		};
+
+		let req = rb.build()?;
+		let url = req.url();
+		let path = match url.query() {
+			Some(q) => format!("{}?{q}", url.path()),
+			None => url.path().to_string(),
+		};
+		let mut body = req
+			.body()
+			.and_then(|b| b.as_bytes())
+			.and_then(|b| serde_json::from_slice::<serde_json::Value>(b).ok());
+		if let Some(body) = body.as_mut() {
+			cassette::redact(body);
+		}
+		let recorded = RecordedRequest { method: req.method().to_string(), path, body };
+
+		match tape.mode() {
+			cassette::Mode::Replay => {
+				let interaction = tape.next(&recorded)?;
+				let Some(resp) = interaction.response else {
+					let message = interaction.error.unwrap_or_default();
+					let RecordedRequest { method, path, .. } = recorded;
+					return Err(CassetteError::Transport { method, path, message }.into());
+				};
+				let status =
+					StatusCode::from_u16(resp.status).expect("statuses are checked on load");
+				Ok((status, resp.bytes()))
+			},
+			cassette::Mode::Record => {
+				let answer = async {
+					let resp = self.http.execute(req).await?;
+					let status = resp.status();
+					Ok::<_, reqwest::Error>((status, resp.bytes().await?.to_vec()))
+				}
+				.await;
+				let interaction = match &answer {
+					Ok((status, bytes)) => Interaction {
+						request: recorded,
+						response: Some(RecordedResponse::new(status.as_u16(), bytes)),
+						error: None,
+					},
+					Err(e) => Interaction {
+						request: recorded,
+						response: None,
+						error: Some(e.to_string()),
+					},
+				};
+				tape.append(interaction)?;
+				Ok(answer?)
+			},
+		}
+	}
+}
+
+fn http_error(status: StatusCode, bytes: &[u8]) -> ApiError {
+	ApiError::Http {
+		status,
+		error: serde_json::from_slice::<ErrorResponse>(bytes).ok(),
+		body: String::from_utf8_lossy(bytes).into_owned(),
+	}
+}
+
+/// Whether the daemon answered that it does not serve the route at all.
+fn is_unsupported(e: &ApiError) -> bool {
+	matches!(
+		e.status(),
+		Some(StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED)
+	)
+}
+
+/// [`PaymentsPage::select`] for daemons without `POST /payments/list`, failing as that route
+/// would.
+fn select_payments(
+	payments: &[PaymentDetailsDto], req: &PaymentsListRequest,
+) -> Result<PaymentsPage, ApiError> {
+	PaymentsPage::select(payments, req).map_err(|error| ApiError::Http {
+		status: StatusCode::BAD_REQUEST,
+		body: serde_json::json!({ "error": error }).to_string(),
+		error: Some(ErrorResponse { error }),
+	})
+}
+
+fn join_url(base: &str, path: &str) -> String {
+	format!("{}/{}", base.trim_end_matches('/'), path.trim_start_matches('/'))
+}
+
+/// Error returned by [`RgbLdkClient`] calls.
+#[derive(Debug)]
+pub enum ApiError {
+	/// The request could not be sent, or the response body could not be read.
+	Transport(reqwest::Error),
+	/// The daemon answered with a non-success HTTP status.
+	Http {
+		/// Response status.
+		status: StatusCode,
+		/// Parsed `{ "error": "..." }` body, if the daemon sent one.
+		error: Option<ErrorResponse>,
+		/// Raw response body.
+		body: String,
+	},
+	/// Recording or replaying a cassette failed.
+	Cassette(CassetteError),
+	/// The daemon answered successfully but the body did not match the expected type.
+	Decode {
+		/// Response status.
+		status: StatusCode,
+		/// Raw response body.
+		body: String,
+		/// Underlying JSON error.
+		source: serde_json::Error,
+	},
+}
+
+impl ApiError {
+	/// HTTP status of the response, if one was received.
+	pub fn status(&self) -> Option<StatusCode> {
+		match self {
+			ApiError::Transport(e) => e.status(),
+			ApiError::Cassette(_) => None,
+			ApiError::Http { status, .. } | ApiError::Decode { status, .. } => Some(*status),
+		}
+	}
+
+	/// Whether the daemon rejected the request (HTTP 4xx).
+	pub fn is_client_error(&self) -> bool {
+		matches!(self, ApiError::Http { status, .. } if status.is_client_error())
+	}
+
+	/// Whether the daemon failed to handle the request (HTTP 5xx).
+	pub fn is_server_error(&self) -> bool {
+		matches!(self, ApiError::Http { status, .. } if status.is_server_error())
+	}
+
+	/// Raw response body parsed as JSON, if there is one and it is valid JSON.
+	pub fn body_json(&self) -> Option<serde_json::Value> {
+		match self {
+			ApiError::Transport(_) | ApiError::Cassette(_) => None,
+			ApiError::Http { body, .. } | ApiError::Decode { body, .. } => {
+				serde_json::from_str(body).ok()
+			},
+		}
+	}
+}
+
+impl std::fmt::Display for ApiError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ApiError::Transport(e) => write!(f, "request failed: {e}"),
+			ApiError::Cassette(e) => e.fmt(f),
+			ApiError::Http { status, error: Some(err), .. } => {
+				write!(f, "HTTP {}: {}", status.as_u16(), err.error)
+			},
+			ApiError::Http { status, body, .. } => write!(f, "HTTP {}: {body}", status.as_u16()),
+			ApiError::Decode { status, source, .. } => {
+				write!(f, "invalid response (HTTP {}): {source}", status.as_u16())
+			},
+		}
+	}
+}
+
+impl std::error::Error for ApiError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			ApiError::Transport(e) => Some(e),
+			ApiError::Cassette(e) => Some(e),
+			ApiError::Http { .. } => None,
+			ApiError::Decode { source, .. } => Some(source),
+		}
+	}
+}
+
+impl From<reqwest::Error> for ApiError {
+	fn from(e: reqwest::Error) -> Self {
+		ApiError::Transport(e)
+	}
+}
+
+impl From<CassetteError> for ApiError {
+	fn from(e: CassetteError) -> Self {
+		ApiError::Cassette(e)
+	}
+}
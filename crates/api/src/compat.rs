@@ -0,0 +1,98 @@
+//! Compatibility between this crate and the daemon it talks to.
+//!
+//! The daemon reports its API version, the `rgbldk-api` version it was built with and the
+//! operations it serves in [`VersionResponse`]. [`check`] compares the versions with
+//! [`API_VERSION`] / [`CRATE_VERSION`]; [`VersionResponse::supports`] answers per route.
+
+use std::fmt;
+
+use crate::http::VersionResponse;
+use crate::{API_VERSION, CRATE_VERSION};
+
+/// How a daemon's versions relate to this crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Compatibility {
+	/// Same API version and the same `major.minor` crate version.
+	Compatible,
+	/// The daemon's API is older than this crate expects; newer routes or fields may be missing.
+	DaemonOlder {
+		/// What the daemon reported, e.g. `v1 / 0.1.0`.
+		daemon: String,
+		/// What this crate expects.
+		expected: String,
+	},
+	/// The daemon is newer than this crate; it may send values this crate does not know.
+	DaemonNewer {
+		/// What the daemon reported.
+		daemon: String,
+		/// What this crate expects.
+		expected: String,
+	},
+	/// The daemon's version could not be parsed.
+	Unknown {
+		/// What the daemon reported.
+		daemon: String,
+	},
+}
+
+impl fmt::Display for Compatibility {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Compatibility::Compatible => f.write_str("compatible"),
+			Compatibility::DaemonOlder { daemon, expected } => {
+				write!(f, "daemon API {daemon} is older than expected {expected}")
+			},
+			Compatibility::DaemonNewer { daemon, expected } => {
+				write!(f, "daemon API {daemon} is newer than expected {expected}")
+			},
+			Compatibility::Unknown { daemon } => write!(f, "unrecognized daemon version {daemon}"),
+		}
+	}
+}
+
+/// `major.minor.patch` of a version string; pre-release and build suffixes are ignored.
+fn parse_semver(s: &str) -> Option<(u64, u64, u64)> {
+	let core = s.trim().split(['-', '+']).next()?;
+	let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+	let major = parts.next()??;
+	let minor = parts.next().unwrap_or(Some(0))?;
+	let patch = parts.next().unwrap_or(Some(0))?;
+	Some((major, minor, patch))
+}
+
+fn parse_api_version(s: &str) -> Option<u64> {
+	s.trim().strip_prefix('v')?.parse().ok()
+}
+
+/// Compares the daemon's reported versions with this crate.
+///
+/// Patch releases do not change the HTTP surface, so only `major.minor` of the crate version is
+/// compared.
+pub fn check(v: &VersionResponse) -> Compatibility {
+	let daemon = format!("{} / {}", v.api_version, v.api_crate_version);
+	let expected = format!("{API_VERSION} / {CRATE_VERSION}");
+	let (Some(api), Some(ours_api)) =
+		(parse_api_version(&v.api_version), parse_api_version(API_VERSION))
+	else {
+		return Compatibility::Unknown { daemon };
+	};
+	let (Some((major, minor, _)), Some((our_major, our_minor, _))) =
+		(parse_semver(&v.api_crate_version), parse_semver(CRATE_VERSION))
+	else {
+		return Compatibility::Unknown { daemon };
+	};
+	match (api, major, minor).cmp(&(ours_api, our_major, our_minor)) {
+		std::cmp::Ordering::Equal => Compatibility::Compatible,
+		std::cmp::Ordering::Less => Compatibility::DaemonOlder { daemon, expected },
+		std::cmp::Ordering::Greater => Compatibility::DaemonNewer { daemon, expected },
+	}
+}
+
+impl VersionResponse {
+	/// Whether the daemon serves the route with this operation id.
+	///
+	/// Daemons that predate capability lists are assumed to serve every route.
+	pub fn supports(&self, operation_id: &str) -> bool {
+		self.capabilities.is_empty() || self.capabilities.iter().any(|c| c == operation_id)
+	}
+}
@@ -3,8 +3,11 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
+use crate::ids::{AssetId, ChannelId, NodeId, PaymentId, UserChannelId};
+
 /// Node status response.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct StatusDto {
 	/// Whether the node runtime is running.
 	pub is_running: bool,
@@ -14,8 +17,50 @@ pub struct StatusDto {
 	pub best_block_height: u32,
 }
 
-/// Wallet and channel balance overview.
+/// Daemon version information.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct VersionResponse {
+	/// HTTP API version, e.g. `v1`.
+	pub api_version: String,
+	/// Version of the `rgbldk-api` crate the daemon was built with.
+	pub api_crate_version: String,
+	/// Version of the node core crate.
+	pub core_crate_version: String,
+	/// Operation ids of the routes this daemon serves (see `rgbldk_api::routes::ROUTES`).
+	///
+	/// Empty when the daemon predates capability lists.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub capabilities: Vec<String>,
+}
+
+/// Response containing the node's own id.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct NodeIdResponse {
+	/// Node id (hex pubkey).
+	pub node_id: NodeId,
+}
+
+/// Response listing the P2P addresses the node listens on.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ListeningAddressesResponse {
+	/// Socket addresses, e.g. `0.0.0.0:9735`.
+	pub addresses: Vec<String>,
+}
+
+/// Response containing a fresh on-chain wallet address.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WalletNewAddressResponse {
+	/// Bitcoin address.
+	pub address: String,
+}
+
+/// Wallet and channel balance overview.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct BalancesDto {
 	/// Total confirmed on-chain balance in satoshis.
 	pub total_onchain_balance_sats: u64,
@@ -28,10 +73,11 @@ pub struct BalancesDto {
 }
 
 /// Peer details entry.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PeerDetailsDto {
 	/// Peer node id in hex.
-	pub node_id: String,
+	pub node_id: NodeId,
 	/// Selected address for the peer.
 	pub address: String,
 	/// Whether peer is persisted to peer store.
@@ -42,9 +88,10 @@ pub struct PeerDetailsDto {
 
 /// Request to connect to a peer.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PeerConnectRequest {
 	/// Peer node id in hex.
-	pub node_id: String,
+	pub node_id: NodeId,
 	/// Socket address, e.g. `lnd1:9735`.
 	pub address: String,
 	/// Whether to persist the peer to disk.
@@ -54,40 +101,289 @@ pub struct PeerConnectRequest {
 
 /// Request to disconnect a peer.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PeerDisconnectRequest {
 	/// Peer node id in hex.
-	pub node_id: String,
+	pub node_id: NodeId,
+}
+
+macro_rules! string_enum {
+	(
+		$(#[$meta:meta])*
+		$name:ident { $($(#[$vmeta:meta])* $variant:ident),+ $(,)? }
+	) => {
+		$(#[$meta])*
+		///
+		/// Values this crate does not know yet deserialize into `Unknown` instead of failing.
+		#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+		pub enum $name {
+			$($(#[$vmeta])* $variant,)+
+			/// A value not known to this version of the crate.
+			Unknown(String),
+		}
+
+		impl $name {
+			/// All variants known to this version of the crate.
+			pub const KNOWN: &'static [$name] = &[$($name::$variant),+];
+
+			/// Wire representation.
+			pub fn as_str(&self) -> &str {
+				match self {
+					$($name::$variant => stringify!($variant),)+
+					$name::Unknown(s) => s,
+				}
+			}
+		}
+
+		impl From<String> for $name {
+			fn from(s: String) -> Self {
+				match s.as_str() {
+					$(stringify!($variant) => $name::$variant,)+
+					_ => $name::Unknown(s),
+				}
+			}
+		}
+
+		/// Parses a known variant name (case-insensitive); unknown names are rejected.
+		impl std::str::FromStr for $name {
+			type Err = String;
+
+			fn from_str(s: &str) -> Result<Self, Self::Err> {
+				$name::KNOWN
+					.iter()
+					.find(|v| v.as_str().eq_ignore_ascii_case(s))
+					.cloned()
+					.ok_or_else(|| {
+						let known: Vec<&str> = $name::KNOWN.iter().map(|v| v.as_str()).collect();
+						format!("unknown value {s:?} (expected one of: {})", known.join(", "))
+					})
+			}
+		}
+
+		impl std::fmt::Display for $name {
+			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+				f.write_str(self.as_str())
+			}
+		}
+
+		impl Serialize for $name {
+			fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+				serializer.serialize_str(self.as_str())
+			}
+		}
+
+		impl<'de> Deserialize<'de> for $name {
+			fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+				String::deserialize(deserializer).map($name::from)
+			}
+		}
+
+		/// A string that is one of the known values, or any other string from a newer daemon.
+		#[cfg(feature = "schema")]
+		impl schemars::JsonSchema for $name {
+			fn schema_name() -> std::borrow::Cow<'static, str> {
+				stringify!($name).into()
+			}
+
+			fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+				schemars::json_schema!({
+					"type": "string",
+					"anyOf": [
+						{ "enum": [$(stringify!($variant)),+] },
+						{ "description": "A value not known to this version of the crate." },
+					],
+				})
+			}
+		}
+	};
 }
 
+string_enum!(
+	/// Direction of a payment.
+	PaymentDirection {
+		/// Payment received by this node.
+		Inbound,
+		/// Payment sent by this node.
+		Outbound,
+	}
+);
+
+string_enum!(
+	/// Status of a payment.
+	PaymentStatus {
+		/// The payment is in flight.
+		Pending,
+		/// The payment completed successfully.
+		Succeeded,
+		/// The payment failed or was abandoned.
+		Failed,
+	}
+);
+
+string_enum!(
+	/// Kind of a payment.
+	PaymentKind {
+		/// BOLT11 invoice payment.
+		Bolt11,
+		/// BOLT11 payment via a just-in-time channel.
+		Bolt11Jit,
+		/// BOLT12 offer payment.
+		Bolt12Offer,
+		/// BOLT12 refund payment.
+		Bolt12Refund,
+		/// Spontaneous (keysend) payment.
+		Spontaneous,
+		/// On-chain payment.
+		Onchain,
+	}
+);
+
 /// Payment details entry.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PaymentDetailsDto {
 	/// Payment id (hex-encoded 32 bytes).
-	pub id: String,
+	pub id: PaymentId,
 	/// Direction, either Inbound or Outbound.
-	pub direction: String,
+	pub direction: PaymentDirection,
 	/// Status, one of Pending, Succeeded, Failed.
-	pub status: String,
+	pub status: PaymentStatus,
 	/// Amount in millisatoshis, if known.
 	pub amount_msat: Option<u64>,
 	/// Payment kind label.
-	pub kind: String,
+	pub kind: PaymentKind,
 	/// Fee paid in millisatoshis, if known.
 	pub fee_paid_msat: Option<u64>,
 	/// Kind-specific details (when available).
 	#[serde(skip_serializing_if = "Option::is_none")]
-	pub kind_details: Option<JsonValue>,
+	pub kind_details: Option<PaymentKindDetails>,
+	/// Unix time (seconds) the daemon first recorded the payment; absent from daemons that do
+	/// not track it.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub created_at: Option<u64>,
+}
+
+/// Kind-specific payment details, tagged by `type`.
+///
+/// Hashes, preimages, secrets, offer ids and txids are hex strings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type")]
+pub enum PaymentKindDetails {
+	/// BOLT11 invoice payment.
+	Bolt11 {
+		/// Payment hash.
+		payment_hash: String,
+		/// Payment preimage, once known.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		preimage: Option<String>,
+		/// Payment secret, if known.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		secret: Option<String>,
+	},
+	/// BOLT11 payment received via a just-in-time channel.
+	Bolt11Jit {
+		/// Payment hash.
+		payment_hash: String,
+		/// Payment preimage, once known.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		preimage: Option<String>,
+		/// Payment secret, if known.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		secret: Option<String>,
+	},
+	/// BOLT12 offer payment.
+	Bolt12Offer {
+		/// Payment hash, once an invoice has been received.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		payment_hash: Option<String>,
+		/// Payment preimage, once known.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		preimage: Option<String>,
+		/// Payment secret, if known.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		secret: Option<String>,
+		/// Id of the offer this payment is for.
+		offer_id: String,
+		/// Payer note from the invoice request.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		payer_note: Option<String>,
+		/// Item quantity from the invoice request.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		quantity: Option<u64>,
+	},
+	/// BOLT12 refund payment.
+	Bolt12Refund {
+		/// Payment hash, once an invoice has been received.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		payment_hash: Option<String>,
+		/// Payment preimage, once known.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		preimage: Option<String>,
+		/// Payment secret, if known.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		secret: Option<String>,
+		/// Payer note from the refund.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		payer_note: Option<String>,
+		/// Item quantity from the refund.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		quantity: Option<u64>,
+	},
+	/// Spontaneous (keysend) payment.
+	Spontaneous {
+		/// Payment hash.
+		payment_hash: String,
+		/// Payment preimage, once known.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		preimage: Option<String>,
+	},
+	/// On-chain payment.
+	Onchain {
+		/// Transaction id.
+		txid: String,
+	},
+	/// Details this crate does not model yet, kept as raw JSON.
+	#[serde(untagged)]
+	Unknown(JsonValue),
+}
+
+impl PaymentKindDetails {
+	/// Payment hash, if this kind has one and it is known.
+	pub fn payment_hash(&self) -> Option<&str> {
+		match self {
+			PaymentKindDetails::Bolt11 { payment_hash, .. }
+			| PaymentKindDetails::Bolt11Jit { payment_hash, .. }
+			| PaymentKindDetails::Spontaneous { payment_hash, .. } => Some(payment_hash),
+			PaymentKindDetails::Bolt12Offer { payment_hash, .. }
+			| PaymentKindDetails::Bolt12Refund { payment_hash, .. } => payment_hash.as_deref(),
+			PaymentKindDetails::Onchain { .. } | PaymentKindDetails::Unknown(_) => None,
+		}
+	}
+
+	/// Payment preimage, if this kind has one and it is known.
+	pub fn preimage(&self) -> Option<&str> {
+		match self {
+			PaymentKindDetails::Bolt11 { preimage, .. }
+			| PaymentKindDetails::Bolt11Jit { preimage, .. }
+			| PaymentKindDetails::Bolt12Offer { preimage, .. }
+			| PaymentKindDetails::Bolt12Refund { preimage, .. }
+			| PaymentKindDetails::Spontaneous { preimage, .. } => preimage.as_deref(),
+			PaymentKindDetails::Onchain { .. } | PaymentKindDetails::Unknown(_) => None,
+		}
+	}
 }
 
 /// Channel details entry (extended for control-plane integrations).
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ChannelDetailsExtendedDto {
 	/// Channel id (32-byte hex).
-	pub channel_id: String,
+	pub channel_id: ChannelId,
 	/// User channel id (hex-encoded 16 bytes BIG-ENDIAN).
-	pub user_channel_id: String,
+	pub user_channel_id: UserChannelId,
 	/// Counterparty node id (hex pubkey).
-	pub counterparty_node_id: String,
+	pub counterparty_node_id: NodeId,
 	/// Funding outpoint formatted as `txid:vout` if known.
 	pub channel_point: Option<String>,
 	/// Total channel capacity in satoshis.
@@ -109,10 +405,11 @@ pub struct ChannelDetailsExtendedDto {
 }
 
 /// RGB asset balance in a channel.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct RgbChannelBalanceDto {
 	/// Asset ID (hex-encoded 32 bytes).
-	pub asset_id: String,
+	pub asset_id: AssetId,
 	/// Local (our) RGB balance in this channel.
 	pub local_amount: u64,
 	/// Remote (counterparty) RGB balance in this channel.
@@ -121,11 +418,12 @@ pub struct RgbChannelBalanceDto {
 
 /// Channel details entry.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ChannelDetailsDto {
 	/// Channel id (32-byte hex).
-	pub channel_id: String,
+	pub channel_id: ChannelId,
 	/// Counterparty node id.
-	pub counterparty_node_id: String,
+	pub counterparty_node_id: NodeId,
 	/// Whether channel is ready.
 	pub is_channel_ready: bool,
 	/// Whether channel is announced.
@@ -134,6 +432,7 @@ pub struct ChannelDetailsDto {
 
 /// Request to decode a Bolt11 invoice.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt11DecodeRequest {
 	/// The Bolt11 invoice string.
 	pub invoice: String,
@@ -141,11 +440,12 @@ pub struct Bolt11DecodeRequest {
 
 /// Decoded Bolt11 invoice summary.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt11DecodeResponse {
 	/// Payment hash (hex).
 	pub payment_hash: String,
 	/// Destination node id (hex pubkey).
-	pub destination: String,
+	pub destination: NodeId,
 	/// Amount in millisatoshis, if the invoice is fixed-amount.
 	pub amount_msat: Option<u64>,
 	/// Invoice expiry in seconds.
@@ -154,6 +454,7 @@ pub struct Bolt11DecodeResponse {
 
 /// Request to pay a Bolt11 invoice and wait for completion.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt11PayRequest {
 	/// The Bolt11 invoice string.
 	pub invoice: String,
@@ -163,24 +464,26 @@ pub struct Bolt11PayRequest {
 
 /// Response of a completed Bolt11 payment.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt11PayResponse {
 	/// Payment id (hex-encoded 32 bytes).
-	pub payment_id: String,
+	pub payment_id: PaymentId,
 	/// Payment preimage (hex-encoded 32 bytes).
 	pub preimage: String,
 	/// Amount paid in satoshis.
 	pub amount_sats: u64,
 	/// Destination node id (hex pubkey).
-	pub destination: String,
+	pub destination: NodeId,
 	/// Fee paid in millisatoshis, if known.
 	pub fee_paid_msat: Option<u64>,
 }
 
 /// Request to open a channel to a peer.
 #[derive(Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct OpenChannelRequest {
 	/// Counterparty node id (hex secp256k1 pubkey).
-	pub node_id: String,
+	pub node_id: NodeId,
 	/// Socket address of the counterparty as advertised.
 	pub address: String,
 	/// Channel capacity in satoshis.
@@ -193,22 +496,25 @@ pub struct OpenChannelRequest {
 
 /// Response of a successful channel open request.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct OpenChannelResponse {
 	/// User channel id (hex-encoded 16 bytes BIG-ENDIAN).
-	pub user_channel_id: String,
+	pub user_channel_id: UserChannelId,
 }
 
 /// Request to close or force-close a channel.
 #[derive(Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CloseChannelRequest {
 	/// User channel id (hex-encoded 16 bytes BIG-ENDIAN).
-	pub user_channel_id: String,
+	pub user_channel_id: UserChannelId,
 	/// Counterparty node id.
-	pub counterparty_node_id: String,
+	pub counterparty_node_id: NodeId,
 }
 
 /// Request to create a Bolt11 invoice.
 #[derive(Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt11ReceiveRequest {
 	/// Amount in millisatoshis.
 	pub amount_msat: u64,
@@ -220,6 +526,7 @@ pub struct Bolt11ReceiveRequest {
 
 /// Response containing a newly created Bolt11 invoice.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt11ReceiveResponse {
 	/// The invoice string.
 	pub invoice: String,
@@ -227,6 +534,7 @@ pub struct Bolt11ReceiveResponse {
 
 /// Request to create a variable-amount Bolt11 invoice.
 #[derive(Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt11ReceiveVarRequest {
 	/// Invoice description.
 	pub description: String,
@@ -236,6 +544,7 @@ pub struct Bolt11ReceiveVarRequest {
 
 /// Request to pay a Bolt11 invoice.
 #[derive(Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt11SendRequest {
 	/// The Bolt11 invoice string.
 	pub invoice: String,
@@ -243,6 +552,7 @@ pub struct Bolt11SendRequest {
 
 /// Request to pay a Bolt11 invoice using a specified amount.
 #[derive(Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt11SendUsingAmountRequest {
 	/// The Bolt11 invoice string.
 	pub invoice: String,
@@ -252,16 +562,18 @@ pub struct Bolt11SendUsingAmountRequest {
 
 /// Response containing the id of a sent payment.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SendResponse {
 	/// Payment id (hex-encoded 32 bytes).
-	pub payment_id: String,
+	pub payment_id: PaymentId,
 }
 
 /// Request to send a spontaneous (keysend) payment.
 #[derive(Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SpontaneousSendRequest {
 	/// Counterparty node id (hex pubkey).
-	pub counterparty_node_id: String,
+	pub counterparty_node_id: NodeId,
 	/// Amount in millisatoshis.
 	pub amount_msat: u64,
 	/// Optional custom TLV list.
@@ -271,6 +583,7 @@ pub struct SpontaneousSendRequest {
 
 /// Custom TLV record as passed to keysend.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CustomTlvDto {
 	/// TLV type number.
 	pub r#type: u64,
@@ -279,7 +592,8 @@ pub struct CustomTlvDto {
 }
 
 /// Generic `{ ok: true }` response.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct OkResponse {
 	/// Whether the operation succeeded.
 	pub ok: bool,
@@ -289,7 +603,8 @@ pub struct OkResponse {
 }
 
 /// A single health/readiness sub-check.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct HealthCheckDto {
 	/// Machine-readable check name.
 	pub name: String,
@@ -305,13 +620,15 @@ pub struct HealthCheckDto {
 
 /// Generic `{ error: "..." }` error response.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ErrorResponse {
 	/// Human-readable error message.
 	pub error: String,
 }
 
 /// Outpoint representation.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct OutPointDto {
 	/// Transaction id (hex string).
 	pub txid: String,
@@ -319,26 +636,31 @@ pub struct OutPointDto {
 	pub vout: u32,
 }
 
-/// Event returned by `/events/wait_next`.
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(tag = "type", content = "data")]
+/// An event from the daemon's queue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(
+	tag = "type",
+	content = "data",
+	expecting = "an event whose `data` matches its `type`, or an event of a type this crate does not model"
+)]
 pub enum EventDto {
 	/// A sent payment succeeded.
 	PaymentSuccessful {
 		/// Optional payment id.
-		payment_id: Option<String>,
+		payment_id: Option<PaymentId>,
 		/// Total fee paid in millisatoshis, if provided.
 		fee_paid_msat: Option<u64>,
 	},
 	/// A sent payment failed.
 	PaymentFailed {
 		/// Optional payment id.
-		payment_id: Option<String>,
+		payment_id: Option<PaymentId>,
 	},
 	/// A payment has been received.
 	PaymentReceived {
 		/// Optional payment id.
-		payment_id: Option<String>,
+		payment_id: Option<PaymentId>,
 		/// Amount received in millisatoshis.
 		amount_msat: u64,
 	},
@@ -350,32 +672,165 @@ pub enum EventDto {
 	/// A channel is ready to be used.
 	ChannelReady {
 		/// User channel id (hex-encoded 16 bytes BIG-ENDIAN).
-		user_channel_id: String,
+		user_channel_id: UserChannelId,
 	},
 	/// A channel has been closed.
 	ChannelClosed {
 		/// Channel id (hex-encoded 32 bytes).
-		channel_id: String,
+		channel_id: ChannelId,
 		/// User channel id (hex-encoded 16 bytes BIG-ENDIAN).
-		user_channel_id: String,
+		user_channel_id: UserChannelId,
 		/// Counterparty node id (hex pubkey), if known.
 		#[serde(default, skip_serializing_if = "Option::is_none")]
-		counterparty_node_id: Option<String>,
+		counterparty_node_id: Option<NodeId>,
 		/// Closure reason (Debug string), if provided by the node.
 		#[serde(default, skip_serializing_if = "Option::is_none")]
 		reason: Option<String>,
 	},
-	/// Other event kinds (not explicitly modeled).
+	/// An event this crate does not model, such as a kind added by a newer daemon, kept as
+	/// sent: it serializes back to the same `type` and `data`. A modeled `type` with malformed
+	/// `data` is an error, not `Other`. Daemons that wrap kinds they do not map themselves send
+	/// `type` `Other` with `data` `{"kind": ...}`; `kind` is then read from `data`, and the event
+	/// serializes back to the same wrapper.
+	#[serde(
+		untagged,
+		serialize_with = "serialize_unmodeled_event",
+		deserialize_with = "unmodeled_event"
+	)]
 	Other {
-		/// Name of the event kind.
+		/// The event's `type`.
+		#[serde(rename = "type")]
 		kind: String,
+		/// The event's `data`, as sent (`null` when absent).
+		#[serde(default)]
+		data: JsonValue,
 	},
 }
 
-/// ---- BOLT12 (offers + refunds) ----
+impl EventDto {
+	/// The `type` of every modeled kind; keep in step with the variants above.
+	const MODELED_KINDS: &[&str] = &[
+		"PaymentSuccessful",
+		"PaymentFailed",
+		"PaymentReceived",
+		"ChannelPending",
+		"ChannelReady",
+		"ChannelClosed",
+	];
+}
+
+/// Reads [`EventDto::Other`], unwrapping the daemon's legacy `Other` wrapper. A modeled kind
+/// whose `data` did not match must stay an error rather than fall through to `Other`.
+fn unmodeled_event<'de, D: serde::Deserializer<'de>>(
+	deserializer: D,
+) -> Result<(String, JsonValue), D::Error> {
+	#[derive(Deserialize)]
+	struct Raw {
+		#[serde(rename = "type")]
+		kind: String,
+		#[serde(default)]
+		data: JsonValue,
+	}
+
+	let Raw { kind, data } = Raw::deserialize(deserializer)?;
+	let kind = match legacy_kind(&data) {
+		Some(inner) if kind == "Other" => inner.to_string(),
+		_ => kind,
+	};
+	if EventDto::MODELED_KINDS.contains(&kind.as_str()) {
+		return Err(serde::de::Error::custom(format!("malformed {kind} event")));
+	}
+	Ok((kind, data))
+}
+
+/// Writes [`EventDto::Other`] back as it was read: an event whose `data.kind` is its kind goes
+/// out in the legacy `Other` wrapper.
+fn serialize_unmodeled_event<S: serde::Serializer>(
+	kind: &str, data: &JsonValue, serializer: S,
+) -> Result<S::Ok, S::Error> {
+	use serde::ser::SerializeMap;
+
+	let tag = if legacy_kind(data) == Some(kind) { "Other" } else { kind };
+	let mut map = serializer.serialize_map(None)?;
+	map.serialize_entry("type", tag)?;
+	if !data.is_null() {
+		map.serialize_entry("data", data)?;
+	}
+	map.end()
+}
+
+/// The wrapped kind of a daemon's legacy `Other` event: `data.kind`.
+fn legacy_kind(data: &JsonValue) -> Option<&str> {
+	data.get("kind").and_then(JsonValue::as_str)
+}
+
+/// Event returned by `/events/wait_next`: the event's `type` and `data`, plus its sequence id.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedEvent {
+	/// Sequence id; one higher for every queued event, never reused, and the same as the `seq`
+	/// of the event on `/events/stream`. `None` from daemons that predate event ids.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub id: Option<u64>,
+	/// The event.
+	#[serde(flatten)]
+	pub event: EventDto,
+}
+
+/// `EventDto & { id?: integer }`: flattening an enum would repeat all its variants inline.
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for QueuedEvent {
+	fn schema_name() -> std::borrow::Cow<'static, str> {
+		"QueuedEvent".into()
+	}
+
+	fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+		let event = generator.subschema_for::<EventDto>();
+		schemars::json_schema!({
+			"description": "Event returned by `/events/wait_next`: the event's `type` and `data`, plus its sequence id.",
+			"allOf": [
+				event,
+				{
+					"type": "object",
+					"properties": {
+						"id": {
+							"description": "Sequence id; one higher for every queued event, never reused, and the same as the `seq`\nof the event on `/events/stream`. Absent from daemons that predate event ids.",
+							"type": "integer",
+							"format": "uint64",
+							"minimum": 0,
+						},
+					},
+				},
+			],
+		})
+	}
+}
+
+/// Request to acknowledge the head event.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct EventAckRequest {
+	/// `id` of the event; the daemon answers `409` if it is not the head, and acknowledging an
+	/// event that is already gone is a no-op. Without it the head is acknowledged, whatever it is.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub id: Option<u64>,
+}
+
+/// Event pushed on `/events/stream`, with its position in the queue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct EventEnvelope {
+	/// Sequence number; one higher for every queued event, never reused. Acknowledge the event
+	/// with `POST /events/handled` and this number as `id`.
+	pub seq: u64,
+	/// The event.
+	pub event: EventDto,
+}
+
+// ---- BOLT12 (offers + refunds) ----
 
 /// Request to create a fixed-amount BOLT12 offer.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt12OfferReceiveRequest {
 	/// Amount in millisatoshis.
 	pub amount_msat: u64,
@@ -391,6 +846,7 @@ pub struct Bolt12OfferReceiveRequest {
 
 /// Request to create a variable-amount (zero-amount) BOLT12 offer.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt12OfferReceiveVarRequest {
 	/// Offer description.
 	pub description: String,
@@ -401,6 +857,7 @@ pub struct Bolt12OfferReceiveVarRequest {
 
 /// Response containing an encoded BOLT12 offer string.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt12OfferResponse {
 	/// Bech32-encoded offer (HRP `lno`).
 	pub offer: String,
@@ -408,6 +865,7 @@ pub struct Bolt12OfferResponse {
 
 /// Request to decode a BOLT12 offer.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt12OfferDecodeRequest {
 	/// Bech32-encoded offer (HRP `lno`).
 	pub offer: String,
@@ -415,6 +873,7 @@ pub struct Bolt12OfferDecodeRequest {
 
 /// Decoded BOLT12 offer summary.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt12OfferDecodeResponse {
 	/// Offer id (hex-encoded 32 bytes).
 	pub offer_id: String,
@@ -444,6 +903,7 @@ pub struct Bolt12OfferDecodeResponse {
 
 /// Request to pay a BOLT12 offer.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt12OfferSendRequest {
 	/// Bech32-encoded offer (HRP `lno`).
 	pub offer: String,
@@ -460,6 +920,7 @@ pub struct Bolt12OfferSendRequest {
 
 /// Request to initiate a BOLT12 refund (payer-side), returning an encoded refund string.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt12RefundInitiateRequest {
 	/// Amount in millisatoshis.
 	pub amount_msat: u64,
@@ -475,15 +936,17 @@ pub struct Bolt12RefundInitiateRequest {
 
 /// Response of a successfully initiated refund.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt12RefundInitiateResponse {
 	/// Bech32-encoded refund (HRP `lnr`).
 	pub refund: String,
 	/// Payment id associated with the refund flow (hex-encoded 32 bytes).
-	pub payment_id: String,
+	pub payment_id: PaymentId,
 }
 
 /// Request to decode a BOLT12 refund.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt12RefundDecodeRequest {
 	/// Bech32-encoded refund (HRP `lnr`).
 	pub refund: String,
@@ -491,6 +954,7 @@ pub struct Bolt12RefundDecodeRequest {
 
 /// Decoded BOLT12 refund summary.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt12RefundDecodeResponse {
 	/// Refund description.
 	pub description: String,
@@ -518,6 +982,7 @@ pub struct Bolt12RefundDecodeResponse {
 
 /// Request to respond to a refund by creating and sending an invoice (payee-side).
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt12RefundRequestPaymentRequest {
 	/// Bech32-encoded refund (HRP `lnr`).
 	pub refund: String,
@@ -525,17 +990,19 @@ pub struct Bolt12RefundRequestPaymentRequest {
 
 /// Response for a refund payment request.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Bolt12RefundRequestPaymentResponse {
 	/// Informational only (bech32-encoded BOLT12 invoice, HRP `lni`).
 	pub invoice: String,
 	/// Informational only (raw TLV bytes hex-encoded).
 	pub invoice_hex: String,
 	/// Payment id for tracking the inbound refund payment (hex-encoded 32 bytes).
-	pub payment_id: String,
+	pub payment_id: PaymentId,
 }
 
 /// Request to wait for a payment to reach a terminal state.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PaymentWaitRequest {
 	/// Default: 60 seconds.
 	#[serde(default)]
@@ -544,6 +1011,7 @@ pub struct PaymentWaitRequest {
 
 /// Response of a waited payment result.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PaymentWaitResponse {
 	/// Whether the wait succeeded (i.e., payment succeeded).
 	pub ok: bool,
@@ -553,3 +1021,198 @@ pub struct PaymentWaitResponse {
 	#[serde(default)]
 	pub checks: Vec<HealthCheckDto>,
 }
+
+/// Request for one page of payments, newest first.
+///
+/// All filters are optional and combine with AND. A payment without `created_at` never matches
+/// `created_after` or `created_before`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PaymentsListRequest {
+	/// Page size, 1 to [`PaymentsListRequest::MAX_LIMIT`]. Default:
+	/// [`PaymentsListRequest::DEFAULT_LIMIT`].
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub limit: Option<u32>,
+	/// `next_cursor` of the previous page; omit for the first page.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub cursor: Option<String>,
+	/// Only payments with this status.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub status: Option<PaymentStatus>,
+	/// Only payments in this direction.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub direction: Option<PaymentDirection>,
+	/// Only payments of this kind.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub kind: Option<PaymentKind>,
+	/// Only payments created at or after this Unix time (seconds).
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub created_after: Option<u64>,
+	/// Only payments created before this Unix time (seconds).
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub created_before: Option<u64>,
+}
+
+impl PaymentsListRequest {
+	/// Page size when `limit` is omitted.
+	pub const DEFAULT_LIMIT: u32 = 100;
+	/// Largest accepted page size.
+	pub const MAX_LIMIT: u32 = 1000;
+
+	/// Whether `payment` passes every filter of this request.
+	pub fn matches(&self, payment: &PaymentDetailsDto) -> bool {
+		let created = |bound: Option<u64>, ok: fn(u64, u64) -> bool| {
+			bound.is_none_or(|b| payment.created_at.is_some_and(|at| ok(at, b)))
+		};
+		self.status.as_ref().is_none_or(|s| &payment.status == s)
+			&& self.direction.as_ref().is_none_or(|d| &payment.direction == d)
+			&& self.kind.as_ref().is_none_or(|k| &payment.kind == k)
+			&& created(self.created_after, |at, b| at >= b)
+			&& created(self.created_before, |at, b| at < b)
+	}
+}
+
+/// One page of payments.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PaymentsPage {
+	/// Payments on this page, newest first.
+	pub items: Vec<PaymentDetailsDto>,
+	/// Cursor for the next page; `null` on the last page.
+	pub next_cursor: Option<String>,
+}
+
+impl PaymentsPage {
+	/// The page `req` selects from `payments`, newest `created_at` first. Payments created in the
+	/// same second keep the reverse of their order in `payments` (`GET /payments` lists them
+	/// oldest first), and payments without `created_at` come last. The cursor is the id of the
+	/// last payment of the previous page. Errors describe an invalid `limit` or an unknown
+	/// `cursor`.
+	pub fn select(
+		payments: &[PaymentDetailsDto], req: &PaymentsListRequest,
+	) -> Result<Self, String> {
+		let limit = req.limit.unwrap_or(PaymentsListRequest::DEFAULT_LIMIT);
+		if !(1..=PaymentsListRequest::MAX_LIMIT).contains(&limit) {
+			return Err(format!("limit must be between 1 and {}", PaymentsListRequest::MAX_LIMIT));
+		}
+		let mut newest_first: Vec<_> = payments.iter().rev().collect();
+		newest_first.sort_by_key(|p| std::cmp::Reverse(p.created_at));
+		let mut rest: Box<dyn Iterator<Item = &PaymentDetailsDto>> =
+			Box::new(newest_first.into_iter());
+		if let Some(cursor) = &req.cursor {
+			if !payments.iter().any(|p| p.id.as_str() == cursor) {
+				return Err(format!("unknown cursor {cursor:?}"));
+			}
+			rest = Box::new(rest.skip_while(move |p| p.id.as_str() != cursor).skip(1));
+		}
+		let mut matching = rest.filter(|p| req.matches(p)).peekable();
+		let items: Vec<_> = matching.by_ref().take(limit as usize).cloned().collect();
+		let next_cursor = match (matching.peek(), items.last()) {
+			(Some(_), Some(last)) => Some(last.id.to_string()),
+			_ => None,
+		};
+		Ok(Self { items, next_cursor })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+
+	use super::*;
+
+	fn event(value: JsonValue) -> Result<EventDto, serde_json::Error> {
+		serde_json::from_value(value)
+	}
+
+	#[test]
+	fn unmodeled_events_keep_their_type_and_data() {
+		let sent = json!({ "type": "SpliceLocked", "data": { "channel_id": "ab", "extra": [1] } });
+		let ev = event(sent.clone()).unwrap();
+		assert!(matches!(&ev, EventDto::Other { kind, .. } if kind == "SpliceLocked"));
+		assert_eq!(serde_json::to_value(&ev).unwrap(), sent);
+		let bare = event(json!({ "type": "Heartbeat" })).unwrap();
+		assert_eq!(serde_json::to_value(&bare).unwrap(), json!({ "type": "Heartbeat" }));
+	}
+
+	#[test]
+	fn legacy_other_wrapper_takes_its_kind_from_data() {
+		let sent = json!({ "type": "Other", "data": { "kind": "SpliceLocked", "n": 1 } });
+		let ev = event(sent.clone()).unwrap();
+		assert!(matches!(&ev, EventDto::Other { kind, .. } if kind == "SpliceLocked"));
+		assert_eq!(serde_json::to_value(&ev).unwrap(), sent);
+		assert!(event(json!({ "type": "Other", "data": { "kind": "ChannelReady" } })).is_err());
+	}
+
+	#[test]
+	fn malformed_modeled_events_are_errors() {
+		let bad_id =
+			json!({ "type": "PaymentReceived", "data": { "payment_id": "zz", "amount_msat": 1 } });
+		assert!(event(bad_id).is_err());
+		assert!(event(json!({ "type": "ChannelReady", "data": {} })).is_err());
+		assert!(event(json!({ "type": "PaymentFailed" })).is_err());
+		assert!(event(json!({ "data": {} })).is_err(), "`type` is required");
+
+		let ok =
+			json!({ "type": "PaymentReceived", "data": { "payment_id": null, "amount_msat": 1 } });
+		assert!(matches!(event(ok), Ok(EventDto::PaymentReceived { amount_msat: 1, .. })));
+	}
+
+	#[test]
+	fn modeled_kinds_match_the_variants() {
+		let id = "ab".repeat(32);
+		let modeled = [
+			EventDto::PaymentSuccessful { payment_id: None, fee_paid_msat: None },
+			EventDto::PaymentFailed { payment_id: None },
+			EventDto::PaymentReceived { payment_id: None, amount_msat: 0 },
+			EventDto::ChannelPending { funding_txo: OutPointDto { txid: id.clone(), vout: 0 } },
+			EventDto::ChannelReady { user_channel_id: "ab".repeat(16).parse().unwrap() },
+			EventDto::ChannelClosed {
+				channel_id: id.parse().unwrap(),
+				user_channel_id: "ab".repeat(16).parse().unwrap(),
+				counterparty_node_id: None,
+				reason: None,
+			},
+		];
+		let kinds: Vec<_> = modeled
+			.iter()
+			.map(|ev| serde_json::to_value(ev).unwrap()["type"].as_str().unwrap().to_string())
+			.collect();
+		assert_eq!(kinds, EventDto::MODELED_KINDS);
+	}
+
+	fn payment(n: char, created_at: Option<u64>) -> PaymentDetailsDto {
+		PaymentDetailsDto {
+			id: n.to_string().repeat(64).parse().unwrap(),
+			direction: PaymentDirection::Inbound,
+			status: PaymentStatus::Succeeded,
+			amount_msat: Some(1_000),
+			kind: PaymentKind::Bolt11,
+			fee_paid_msat: None,
+			kind_details: None,
+			created_at,
+		}
+	}
+
+	#[test]
+	fn payments_are_paged_newest_first_whatever_their_order() {
+		let payments = [
+			payment('2', Some(20)),
+			payment('0', None),
+			payment('4', Some(40)),
+			payment('1', Some(10)),
+			payment('3', Some(20)),
+		];
+		let mut req = PaymentsListRequest { limit: Some(2), ..Default::default() };
+		let mut ids = Vec::new();
+		loop {
+			let page = PaymentsPage::select(&payments, &req).unwrap();
+			ids.extend(page.items.iter().map(|p| p.id.as_str()[..1].to_string()));
+			match page.next_cursor {
+				Some(cursor) => req.cursor = Some(cursor),
+				None => break,
+			}
+		}
+		assert_eq!(ids, ["4", "3", "2", "1", "0"], "ties keep the later-listed payment first");
+	}
+}
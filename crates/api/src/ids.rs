@@ -0,0 +1,225 @@
+//! Validated identifier types.
+//!
+//! Every id is carried on the wire as a lowercase hex string; the newtypes here check the length
+//! (and, for node ids, the pubkey prefix) when parsed from user input or deserialized.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Error returned when a string is not a valid identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdParseError {
+	kind: &'static str,
+	reason: String,
+}
+
+impl IdParseError {
+	fn new(kind: &'static str, reason: impl Into<String>) -> Self {
+		Self { kind, reason: reason.into() }
+	}
+}
+
+impl fmt::Display for IdParseError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "invalid {}: {}", self.kind, self.reason)
+	}
+}
+
+impl std::error::Error for IdParseError {}
+
+fn parse_hex(s: &str, bytes: usize, kind: &'static str) -> Result<String, IdParseError> {
+	let s = s.trim();
+	if let Some(pos) = s.find(|c: char| !c.is_ascii_hexdigit()) {
+		return Err(IdParseError::new(kind, format!("non-hex character at position {pos}")));
+	}
+	if s.len() != bytes * 2 {
+		let got = if s.len().is_multiple_of(2) {
+			format!("{} bytes", s.len() / 2)
+		} else {
+			format!("{} hex chars", s.len())
+		};
+		return Err(IdParseError::new(
+			kind,
+			format!("expected {bytes} bytes ({} hex chars), got {got}", bytes * 2),
+		));
+	}
+	Ok(s.to_ascii_lowercase())
+}
+
+macro_rules! hex_id {
+	($(#[$meta:meta])* $name:ident, $bytes:expr, $kind:literal) => {
+		$(#[$meta])*
+		#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+		pub struct $name(String);
+
+		impl $name {
+			/// Length of the id in bytes.
+			pub const LEN: usize = $bytes;
+
+			/// Lowercase hex representation.
+			pub fn as_str(&self) -> &str {
+				&self.0
+			}
+		}
+
+		impl FromStr for $name {
+			type Err = IdParseError;
+
+			fn from_str(s: &str) -> Result<Self, Self::Err> {
+				Self::validate(parse_hex(s, $bytes, $kind)?).map(Self)
+			}
+		}
+
+		impl fmt::Display for $name {
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				f.write_str(&self.0)
+			}
+		}
+
+		impl AsRef<str> for $name {
+			fn as_ref(&self) -> &str {
+				&self.0
+			}
+		}
+
+		impl Serialize for $name {
+			fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+				serializer.serialize_str(&self.0)
+			}
+		}
+
+		impl<'de> Deserialize<'de> for $name {
+			fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+				let s = String::deserialize(deserializer)?;
+				s.parse().map_err(serde::de::Error::custom)
+			}
+		}
+
+		#[cfg(feature = "schema")]
+		impl schemars::JsonSchema for $name {
+			fn schema_name() -> std::borrow::Cow<'static, str> {
+				stringify!($name).into()
+			}
+
+			fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+				schemars::json_schema!({
+					"type": "string",
+					"description": format!("Hex-encoded {} ({} bytes).", $kind, $bytes),
+					"pattern": format!("^[0-9a-f]{{{}}}$", $bytes * 2),
+				})
+			}
+		}
+	};
+}
+
+hex_id!(
+	/// Node id: a compressed secp256k1 public key (33 bytes, 66 hex chars).
+	NodeId,
+	33,
+	"node id"
+);
+
+hex_id!(
+	/// Payment id (32 bytes, 64 hex chars).
+	PaymentId,
+	32,
+	"payment id"
+);
+
+hex_id!(
+	/// Internal channel id (32 bytes, 64 hex chars).
+	ChannelId,
+	32,
+	"channel id"
+);
+
+hex_id!(
+	/// User-facing channel id used by close/force-close (16 bytes big-endian, 32 hex chars).
+	UserChannelId,
+	16,
+	"user channel id"
+);
+
+hex_id!(
+	/// RGB asset id (32 bytes, 64 hex chars).
+	AssetId,
+	32,
+	"asset id"
+);
+
+impl NodeId {
+	fn validate(hex: String) -> Result<String, IdParseError> {
+		if hex.starts_with("02") || hex.starts_with("03") {
+			Ok(hex)
+		} else {
+			Err(IdParseError::new("node id", "compressed pubkey must start with 02 or 03"))
+		}
+	}
+}
+
+macro_rules! no_extra_validation {
+	($($name:ident),*) => {
+		$(
+			impl $name {
+				fn validate(hex: String) -> Result<String, IdParseError> {
+					Ok(hex)
+				}
+			}
+		)*
+	};
+}
+
+no_extra_validation!(PaymentId, ChannelId, UserChannelId, AssetId);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn wrong_length_is_rejected() {
+		let err = "ab".repeat(31).parse::<PaymentId>().unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"invalid payment id: expected 32 bytes (64 hex chars), got 31 bytes"
+		);
+		let err = "abc".parse::<UserChannelId>().unwrap_err();
+		assert!(err.to_string().ends_with("got 3 hex chars"), "{err}");
+		assert!("ab".repeat(33).parse::<PaymentId>().is_err());
+		assert!("".parse::<ChannelId>().is_err());
+	}
+
+	#[test]
+	fn non_hex_is_rejected() {
+		let err = format!("{}zz", "a".repeat(62)).parse::<PaymentId>().unwrap_err();
+		assert_eq!(err.to_string(), "invalid payment id: non-hex character at position 62");
+		assert!(format!("0x{}", "a".repeat(62)).parse::<AssetId>().is_err());
+	}
+
+	#[test]
+	fn node_ids_need_a_compressed_pubkey_prefix() {
+		for prefix in ["02", "03"] {
+			assert!(format!("{prefix}{}", "a".repeat(64)).parse::<NodeId>().is_ok());
+		}
+		let err = format!("04{}", "a".repeat(64)).parse::<NodeId>().unwrap_err();
+		assert_eq!(err.to_string(), "invalid node id: compressed pubkey must start with 02 or 03");
+	}
+
+	#[test]
+	fn input_is_trimmed_and_lowercased() {
+		let id: PaymentId = format!("  {}\n", "AB".repeat(32)).parse().unwrap();
+		assert_eq!(id.as_str(), "ab".repeat(32));
+		assert_eq!(id.to_string(), "ab".repeat(32));
+	}
+
+	#[test]
+	fn deserialize_validates() {
+		let ok: UserChannelId = serde_json::from_str(&format!("\"{}\"", "0F".repeat(16))).unwrap();
+		assert_eq!(serde_json::to_string(&ok).unwrap(), format!("\"{}\"", "0f".repeat(16)));
+		let err = serde_json::from_str::<PaymentId>(&format!("\"{}\"", "ab".repeat(31)));
+		assert!(err.unwrap_err().to_string().contains("expected 32 bytes"));
+		assert!(serde_json::from_str::<NodeId>(&format!("\"05{}\"", "a".repeat(64))).is_err());
+		assert!(serde_json::from_str::<ChannelId>("42").is_err());
+	}
+}
@@ -2,9 +2,44 @@
 
 //! Public API types shared by server and CLI.
 
+/// Amount types with human-friendly parsing.
+pub mod amount;
+
+/// Version and capability checks against the daemon.
+pub mod compat;
+
 /// HTTP API DTOs and helpers.
 pub mod http;
 
+/// Validated identifier newtypes used by the DTOs.
+pub mod ids;
+
+/// Route table of the `/api/v1` surface.
+pub mod routes;
+
+/// OpenAPI document generated from the routes and DTOs.
+#[cfg(feature = "schema")]
+pub mod openapi;
+
+/// TypeScript SDK sources generated from the OpenAPI document.
+#[cfg(feature = "schema")]
+pub mod typescript;
+
+/// Async HTTP client for the `/api/v1` routes.
+#[cfg(feature = "client")]
+pub mod client;
+
+/// Recording and replaying client traffic.
+#[cfg(feature = "client")]
+pub mod cassette;
+
+/// Event subscriptions: push stream with long-poll fallback.
+#[cfg(feature = "client")]
+pub mod stream;
+
+#[cfg(feature = "client")]
+pub use client::{ApiError, RgbLdkClient};
+
 /// API version string for the HTTP surface.
 pub const API_VERSION: &str = "v1";
 
@@ -0,0 +1,101 @@
+//! OpenAPI 3.0 document for the `/api/v1` surface, generated from [`crate::routes::ROUTES`]
+//! and the JSON Schema of the DTOs in [`crate::http`].
+
+use schemars::generate::SchemaSettings;
+use serde_json::{json, Map, Value};
+
+use crate::http::ErrorResponse;
+use crate::routes::{route_schemas, Method};
+use crate::{API_VERSION, CRATE_VERSION};
+
+/// Builds the OpenAPI document.
+pub fn document() -> Value {
+	let mut generator = SchemaSettings::openapi3().into_generator();
+	let routes = route_schemas(&mut generator);
+	let error = generator.subschema_for::<ErrorResponse>();
+
+	let mut paths = Map::new();
+	for r in routes {
+		let mut responses = Map::new();
+		let ok = if r.route.stream {
+			json!({
+				"description": "Server-sent events; the `data` of each event is one value.",
+				"content": { "text/event-stream": { "schema": r.response } },
+			})
+		} else {
+			json!({
+				"description": "Success.",
+				"content": { "application/json": { "schema": r.response } },
+			})
+		};
+		responses.insert("200".into(), ok.clone());
+		for status in r.route.also_ok {
+			responses.insert(status.to_string(), ok.clone());
+		}
+		responses.insert(
+			"default".into(),
+			json!({
+				"description": "Error.",
+				"content": { "application/json": { "schema": error } },
+			}),
+		);
+
+		let mut op = Map::new();
+		op.insert("operationId".into(), r.route.operation_id.into());
+		op.insert("summary".into(), r.route.summary.into());
+		if !r.params.is_empty() {
+			let params = r
+				.params
+				.into_iter()
+				.map(|(name, schema)| {
+					json!({
+						"name": name,
+						"in": "path",
+						"required": true,
+						"schema": schema,
+					})
+				})
+				.collect::<Vec<_>>();
+			op.insert("parameters".into(), params.into());
+		}
+		if let Some(schema) = r.request {
+			op.insert(
+				"requestBody".into(),
+				json!({
+					"required": true,
+					"content": { "application/json": { "schema": schema } },
+				}),
+			);
+		}
+		op.insert("responses".into(), responses.into());
+
+		let method = match r.route.method {
+			Method::Get => "get",
+			Method::Post => "post",
+		};
+		let item = paths
+			.entry(format!("/api/{API_VERSION}{}", r.route.path))
+			.or_insert_with(|| Value::Object(Map::new()));
+		item.as_object_mut().expect("path items are objects").insert(method.into(), op.into());
+	}
+
+	json!({
+		"openapi": "3.0.3",
+		"info": {
+			"title": "rgbldkd HTTP API",
+			"version": CRATE_VERSION,
+			"description": format!("Local HTTP JSON API of rgbldkd (API {API_VERSION})."),
+		},
+		"servers": [{ "url": "http://127.0.0.1:8500" }],
+		"paths": paths,
+		"components": { "schemas": generator.take_definitions(true) },
+	})
+}
+
+/// The OpenAPI document as pretty-printed JSON with a trailing newline, as checked in at
+/// `docs/api/openapi.json`.
+pub fn to_json_pretty() -> String {
+	let mut s = serde_json::to_string_pretty(&document()).expect("OpenAPI document serializes");
+	s.push('\n');
+	s
+}
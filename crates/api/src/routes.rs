@@ -0,0 +1,212 @@
+//! Route table of the `/api/v1` HTTP surface.
+//!
+//! This is the single list of routes the daemon serves; the OpenAPI document (behind the
+//! `schema` feature) is generated from it together with the DTOs in [`crate::http`].
+
+use std::fmt;
+
+#[cfg(feature = "schema")]
+use crate::http::*;
+#[cfg(feature = "schema")]
+use crate::ids::PaymentId;
+
+/// HTTP method of a route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Method {
+	/// `GET`
+	Get,
+	/// `POST`
+	Post,
+}
+
+impl Method {
+	/// Upper-case method name.
+	pub fn as_str(self) -> &'static str {
+		match self {
+			Method::Get => "GET",
+			Method::Post => "POST",
+		}
+	}
+}
+
+impl fmt::Display for Method {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+/// A route of the `/api/v1` surface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Route {
+	/// HTTP method.
+	pub method: Method,
+	/// Path below `/api/v1`, with `{name}` placeholders for path parameters.
+	pub path: &'static str,
+	/// Stable operation id (matches the `RgbLdkClient` method name).
+	pub operation_id: &'static str,
+	/// One-line summary.
+	pub summary: &'static str,
+	/// Non-2xx statuses that still carry the success body.
+	pub also_ok: &'static [u16],
+	/// The answer is a `text/event-stream` whose `data` lines each hold one response body.
+	pub stream: bool,
+}
+
+impl Route {
+	/// Looks up a route by operation id.
+	pub fn find(operation_id: &str) -> Option<&'static Route> {
+		ROUTES.iter().find(|r| r.operation_id == operation_id)
+	}
+}
+
+/// Schemas attached to a route, resolved against a shared generator.
+#[cfg(feature = "schema")]
+pub(crate) struct RouteSchemas {
+	pub(crate) route: &'static Route,
+	pub(crate) params: Vec<(&'static str, schemars::Schema)>,
+	pub(crate) request: Option<schemars::Schema>,
+	pub(crate) response: schemars::Schema,
+}
+
+macro_rules! routes {
+	(@stream) => { false };
+	(@stream stream) => { true };
+	($(
+		#[doc = $summary:literal]
+		$op:ident: $method:ident $path:literal
+			$([$($param:ident: $pty:ty),+])?
+			$(($req:ty))?
+			=> $resp:ty $(| $status:literal)* $(as $stream:ident)?;
+	)+) => {
+		/// Every `/api/v1` route, in documentation order.
+		pub const ROUTES: &[Route] = &[$(
+			Route {
+				method: Method::$method,
+				path: $path,
+				operation_id: stringify!($op),
+				summary: $summary.trim_ascii(),
+				also_ok: &[$($status),*],
+				stream: routes!(@stream $($stream)?),
+			},
+		)+];
+
+		#[cfg(feature = "schema")]
+		pub(crate) fn route_schemas(generator: &mut schemars::SchemaGenerator) -> Vec<RouteSchemas> {
+			let mut out = Vec::with_capacity(ROUTES.len());
+			let mut routes = ROUTES.iter();
+			$(
+				let route = routes.next().expect("route table and schemas are generated together");
+				let params: Vec<(&'static str, schemars::Schema)> =
+					vec![$($((stringify!($param), generator.subschema_for::<$pty>())),+)?];
+				#[allow(unused_mut, unused_assignments)]
+				let mut request = None;
+				$(request = Some(generator.subschema_for::<$req>());)?
+				out.push(RouteSchemas {
+					route,
+					params,
+					request,
+					response: generator.subschema_for::<$resp>(),
+				});
+			)+
+			out
+		}
+	};
+}
+
+routes! {
+	/// Liveness probe.
+	healthz: Get "/healthz" => OkResponse;
+	/// Readiness probe; answers 503 with the same body while the node is not ready.
+	readyz: Get "/readyz" => OkResponse | 503;
+	/// Daemon and API versions.
+	version: Get "/version" => VersionResponse;
+	/// Node runtime status.
+	status: Get "/status" => StatusDto;
+	/// The node's own id.
+	node_id: Get "/node_id" => NodeIdResponse;
+	/// P2P listening addresses.
+	listening_addresses: Get "/listening_addresses" => ListeningAddressesResponse;
+
+	/// Fresh on-chain address.
+	wallet_new_address: Post "/wallet/new_address" => WalletNewAddressResponse;
+	/// Sync the on-chain wallet.
+	wallet_sync: Post "/wallet/sync" => OkResponse;
+	/// Wallet and channel balances.
+	balances: Get "/balances" => BalancesDto;
+
+	/// Known peers.
+	peers: Get "/peers" => Vec<PeerDetailsDto>;
+	/// Connect to a peer.
+	peer_connect: Post "/peers/connect" (PeerConnectRequest) => OkResponse;
+	/// Disconnect a peer.
+	peer_disconnect: Post "/peers/disconnect" (PeerDisconnectRequest) => OkResponse;
+
+	/// Channels with extended details.
+	channels: Get "/channels" => Vec<ChannelDetailsExtendedDto>;
+	/// Open a channel.
+	channel_open: Post "/channel/open" (OpenChannelRequest) => OpenChannelResponse;
+	/// Cooperatively close a channel.
+	channel_close: Post "/channel/close" (CloseChannelRequest) => OkResponse;
+	/// Force-close a channel.
+	channel_force_close: Post "/channel/force_close" (CloseChannelRequest) => OkResponse;
+
+	/// Decode a BOLT11 invoice.
+	bolt11_decode: Post "/bolt11/decode" (Bolt11DecodeRequest) => Bolt11DecodeResponse;
+	/// Create a fixed-amount BOLT11 invoice.
+	bolt11_receive: Post "/bolt11/receive" (Bolt11ReceiveRequest) => Bolt11ReceiveResponse;
+	/// Create a variable-amount BOLT11 invoice.
+	bolt11_receive_var: Post "/bolt11/receive_var" (Bolt11ReceiveVarRequest)
+		=> Bolt11ReceiveResponse;
+	/// Pay a BOLT11 invoice without waiting.
+	bolt11_send: Post "/bolt11/send" (Bolt11SendRequest) => SendResponse;
+	/// Pay a variable-amount BOLT11 invoice without waiting.
+	bolt11_send_using_amount: Post "/bolt11/send_using_amount" (Bolt11SendUsingAmountRequest)
+		=> SendResponse;
+	/// Pay a BOLT11 invoice and wait for completion.
+	bolt11_pay: Post "/bolt11/pay" (Bolt11PayRequest) => Bolt11PayResponse;
+
+	/// Create a fixed-amount BOLT12 offer.
+	bolt12_offer_receive: Post "/bolt12/offer/receive" (Bolt12OfferReceiveRequest)
+		=> Bolt12OfferResponse;
+	/// Create a variable-amount BOLT12 offer.
+	bolt12_offer_receive_var: Post "/bolt12/offer/receive_var" (Bolt12OfferReceiveVarRequest)
+		=> Bolt12OfferResponse;
+	/// Decode a BOLT12 offer.
+	bolt12_offer_decode: Post "/bolt12/offer/decode" (Bolt12OfferDecodeRequest)
+		=> Bolt12OfferDecodeResponse;
+	/// Pay a BOLT12 offer.
+	bolt12_offer_send: Post "/bolt12/offer/send" (Bolt12OfferSendRequest) => SendResponse;
+	/// Initiate a BOLT12 refund (payer side).
+	bolt12_refund_initiate: Post "/bolt12/refund/initiate" (Bolt12RefundInitiateRequest)
+		=> Bolt12RefundInitiateResponse;
+	/// Decode a BOLT12 refund.
+	bolt12_refund_decode: Post "/bolt12/refund/decode" (Bolt12RefundDecodeRequest)
+		=> Bolt12RefundDecodeResponse;
+	/// Request payment for a BOLT12 refund (payee side).
+	bolt12_refund_request_payment:
+		Post "/bolt12/refund/request_payment" (Bolt12RefundRequestPaymentRequest)
+		=> Bolt12RefundRequestPaymentResponse;
+
+	/// Send a spontaneous (keysend) payment.
+	spontaneous_send: Post "/spontaneous/send" (SpontaneousSendRequest) => SendResponse;
+
+	/// All payments.
+	payments: Get "/payments" => Vec<PaymentDetailsDto>;
+	/// One page of payments, newest first, with optional filters.
+	payments_list: Post "/payments/list" (PaymentsListRequest) => PaymentsPage;
+	/// A single payment.
+	payment: Get "/payment/{payment_id}" [payment_id: PaymentId] => PaymentDetailsDto;
+	/// Wait for a payment to reach a terminal state.
+	payment_wait: Post "/payment/{payment_id}/wait" [payment_id: PaymentId]
+		(PaymentWaitRequest) => PaymentWaitResponse;
+	/// Abandon an outbound payment.
+	payment_abandon: Post "/payment/{payment_id}/abandon" [payment_id: PaymentId]
+		=> OkResponse;
+
+	/// Long-poll for the next event (does not remove it).
+	events_wait_next: Post "/events/wait_next" => QueuedEvent;
+	/// Acknowledge the head event, optionally guarded by its id (the `seq` of a streamed event).
+	events_handled: Post "/events/handled" (EventAckRequest) => OkResponse;
+	/// Server-sent events: every queued event from the head on, then new ones as they arrive.
+	events_stream: Get "/events/stream" => EventEnvelope as stream;
+}
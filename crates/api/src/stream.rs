@@ -0,0 +1,128 @@
+//! Event subscriptions over the `/events/stream` push stream, with long-poll as the fallback.
+//!
+//! [`RgbLdkClient::subscribe_events`] opens the stream when the daemon serves it and otherwise
+//! uses `/events/wait_next` + `/events/handled`. Either way [`EventSubscription::next`] yields
+//! events in queue order and [`EventSubscription::ack`] removes one from the queue, guarded by
+//! the event's sequence number so that it never removes an event this consumer has not seen; an
+//! event that is never acknowledged is delivered again (for the stream: to the next
+//! subscription).
+
+use crate::http::{EventAckRequest, EventDto, EventEnvelope, OkResponse};
+use crate::{ApiError, RgbLdkClient};
+
+/// Server-sent events from `GET /events/stream`.
+#[derive(Debug)]
+pub struct EventStream {
+	resp: reqwest::Response,
+	buf: Vec<u8>,
+}
+
+impl EventStream {
+	pub(crate) fn new(resp: reqwest::Response) -> Self {
+		Self { resp, buf: Vec::new() }
+	}
+
+	/// The next event, or `None` once the daemon closed the stream.
+	pub async fn next(&mut self) -> Result<Option<EventEnvelope>, ApiError> {
+		loop {
+			while let Some(frame) = self.take_frame() {
+				let Some(data) = frame_data(&frame) else {
+					continue;
+				};
+				return serde_json::from_str(&data).map(Some).map_err(|source| ApiError::Decode {
+					status: self.resp.status(),
+					body: data,
+					source,
+				});
+			}
+			match self.resp.chunk().await? {
+				Some(chunk) => self.buf.extend(chunk.iter().filter(|b| **b != b'\r')),
+				None => return Ok(None),
+			}
+		}
+	}
+
+	/// Removes the first complete frame (up to a blank line) from the buffer.
+	fn take_frame(&mut self) -> Option<String> {
+		let end = self.buf.windows(2).position(|w| w == b"\n\n")?;
+		let frame = String::from_utf8_lossy(&self.buf[..end]).into_owned();
+		self.buf.drain(..end + 2);
+		Some(frame)
+	}
+}
+
+/// The `data` lines of a frame joined by newlines; `None` for comments and keep-alives.
+fn frame_data(frame: &str) -> Option<String> {
+	let lines: Vec<&str> = frame
+		.lines()
+		.filter_map(|l| l.strip_prefix("data:"))
+		.map(|d| d.strip_prefix(' ').unwrap_or(d))
+		.collect();
+	(!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// How an [`EventSubscription`] receives events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+	/// Pushed over `GET /events/stream`, acknowledged by sequence number.
+	Stream,
+	/// Polled with `POST /events/wait_next`, acknowledged with `POST /events/handled`.
+	LongPoll,
+}
+
+/// An event delivered by an [`EventSubscription`].
+#[derive(Debug, Clone)]
+pub struct ReceivedEvent {
+	/// Sequence number (the `id` of a long-polled event); `None` from daemons that predate
+	/// event ids.
+	pub seq: Option<u64>,
+	/// The event.
+	pub event: EventDto,
+}
+
+/// A consumer of the daemon's event queue.
+#[derive(Debug)]
+pub struct EventSubscription {
+	api: RgbLdkClient,
+	stream: Option<EventStream>,
+}
+
+impl EventSubscription {
+	/// Consumes the queue over the push stream.
+	pub fn stream(api: RgbLdkClient, stream: EventStream) -> Self {
+		Self { api, stream: Some(stream) }
+	}
+
+	/// Consumes the queue with long-polls.
+	pub fn long_poll(api: RgbLdkClient) -> Self {
+		Self { api, stream: None }
+	}
+
+	/// The transport in use.
+	pub fn transport(&self) -> Transport {
+		match self.stream {
+			Some(_) => Transport::Stream,
+			None => Transport::LongPoll,
+		}
+	}
+
+	/// Waits for the next event. `None` means the daemon closed the stream; long-polls never
+	/// end that way.
+	pub async fn next(&mut self) -> Result<Option<ReceivedEvent>, ApiError> {
+		match &mut self.stream {
+			Some(stream) => {
+				Ok(stream.next().await?.map(|e| ReceivedEvent { seq: Some(e.seq), event: e.event }))
+			},
+			None => {
+				let queued = self.api.events_wait_next().await?;
+				Ok(Some(ReceivedEvent { seq: queued.id, event: queued.event }))
+			},
+		}
+	}
+
+	/// Acknowledges `event`, which must be the oldest one not yet acknowledged.
+	/// The daemon answers `409` if another consumer changed the head in the meantime.
+	pub async fn ack(&self, event: &ReceivedEvent) -> Result<OkResponse, ApiError> {
+		self.api.events_handled(&EventAckRequest { id: event.seq }).await
+	}
+}
@@ -0,0 +1,295 @@
+//! TypeScript SDK sources generated from the OpenAPI document (see [`crate::openapi`]).
+//!
+//! [`types_ts`] renders every component schema as a TypeScript type and [`client_ts`] renders a
+//! fetch-based client with one method per route. Both are checked in under `sdk/`; the
+//! hand-written transport (`sdk/http.ts`) and event-loop helper (`sdk/events.ts`) live next to
+//! them.
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+
+use serde_json::Value;
+
+use crate::openapi;
+use crate::routes::ROUTES;
+
+const HEADER: &str = "// Generated from the rgbldk-api crate; do not edit by hand.\n\
+	// Regenerate with `UPDATE_SDK=1 cargo test -p rgbldk-api --features schema --test sdk`.\n";
+
+/// Renders `sdk/types.ts`.
+pub fn types_ts() -> String {
+	let doc = openapi::document();
+	let mut out = String::from(HEADER);
+	out.push_str(
+		"\n/** JSON integer that may exceed `Number.MAX_SAFE_INTEGER` (u64 on the Rust side). */\n\
+		 export type Int = number | bigint;\n",
+	);
+	let schemas = doc["components"]["schemas"].as_object().expect("components.schemas");
+	for (name, schema) in schemas {
+		out.push('\n');
+		push_doc(&mut out, schema.get("description"), 0);
+		let is_interface = schema.get("type").and_then(Value::as_str) == Some("object")
+			&& schema.get("properties").is_some()
+			&& !is_nullable(schema);
+		if is_interface {
+			let _ = writeln!(out, "export interface {name} {}", render_object(schema, 0));
+		} else {
+			let ty = render(schema, 0);
+			let sep = if ty.starts_with('\n') { "" } else { " " };
+			let _ = writeln!(out, "export type {name} ={sep}{ty};");
+		}
+	}
+	out
+}
+
+/// Renders `sdk/client.ts`.
+pub fn client_ts() -> String {
+	let doc = openapi::document();
+	let mut imports = BTreeSet::new();
+	let mut methods = String::new();
+
+	for route in ROUTES {
+		let method = route.method.as_str();
+		let op = &doc["paths"][format!("/api/{}{}", crate::API_VERSION, route.path)]
+			[method.to_ascii_lowercase()];
+		let mut args = Vec::new();
+		let mut path = format!("\"{}\"", route.path);
+		if let Some(params) = op.get("parameters").and_then(Value::as_array) {
+			let mut template = route.path.to_string();
+			for p in params {
+				let name = p["name"].as_str().expect("parameter name");
+				collect_refs(&p["schema"], &mut imports);
+				args.push(format!("{}: {}", camel_case(name), render(&p["schema"], 1)));
+				template = template.replace(
+					&format!("{{{name}}}"),
+					&format!("${{encodeURIComponent({})}}", camel_case(name)),
+				);
+			}
+			path = format!("`{template}`");
+		}
+		let body = match op.pointer("/requestBody/content/application~1json/schema") {
+			Some(schema) => {
+				collect_refs(schema, &mut imports);
+				args.push(format!("req: {}", render(schema, 1)));
+				"req"
+			},
+			None => "undefined",
+		};
+		args.push("options?: RequestOptions".into());
+		let content = if route.stream { "text/event-stream" } else { "application/json" };
+		let response = &op["responses"]["200"]["content"][content]["schema"];
+		collect_refs(response, &mut imports);
+		if route.stream {
+			let _ = write!(
+				methods,
+				"\n  /** {} (`{method} /api/{}{}`) */\n  {}({}): AsyncGenerator<{}> {{\n    \
+				 return this.http.stream(\"{method}\", {path}, {body}, options);\n  }}\n",
+				route.summary,
+				crate::API_VERSION,
+				route.path,
+				camel_case(route.operation_id),
+				args.join(", "),
+				render(response, 1),
+			);
+			continue;
+		}
+		let also_ok = if route.also_ok.is_empty() {
+			String::new()
+		} else {
+			let statuses: Vec<String> = route.also_ok.iter().map(u16::to_string).collect();
+			format!(", [{}]", statuses.join(", "))
+		};
+
+		let _ = write!(
+			methods,
+			"\n  /** {} (`{method} /api/{}{}`) */\n  {}({}): Promise<{}> {{\n    \
+			 return this.http.request(\"{method}\", {path}, {body}, options{also_ok});\n  }}\n",
+			route.summary,
+			crate::API_VERSION,
+			route.path,
+			camel_case(route.operation_id),
+			args.join(", "),
+			render(response, 1),
+		);
+	}
+
+	let mut out = String::from(HEADER);
+	out.push_str(
+		"\nimport { HttpTransport, type ClientOptions, type RequestOptions } from \"./http.js\";\n",
+	);
+	out.push_str("import type {\n");
+	for name in &imports {
+		let _ = writeln!(out, "  {name},");
+	}
+	out.push_str("} from \"./types.js\";\n\n");
+	out.push_str(
+		"/** Typed client for the `/api/v1` routes, mirroring the Rust `RgbLdkClient`. */\n\
+		 export class RgbLdkClient {\n  \
+		 readonly http: HttpTransport;\n\n  \
+		 /** `baseUrl` is the daemon root, e.g. `http://127.0.0.1:8500`. */\n  \
+		 constructor(baseUrl: string, opts?: ClientOptions) {\n    \
+		 this.http = new HttpTransport(`${baseUrl.replace(/\\/+$/, \"\")}/api/v1`, opts);\n  \
+		 }\n",
+	);
+	out.push_str(&methods);
+	out.push_str("}\n");
+	out
+}
+
+fn render(schema: &Value, indent: usize) -> String {
+	let ty = render_inner(schema, indent);
+	if is_nullable(schema) && ty != "null" && !ty.ends_with("| null") {
+		format!("{ty} | null")
+	} else {
+		ty
+	}
+}
+
+fn render_inner(schema: &Value, indent: usize) -> String {
+	if let Some(r) = schema.get("$ref").and_then(Value::as_str) {
+		return r.rsplit('/').next().unwrap_or(r).to_string();
+	}
+	if let Some(all) = schema.get("allOf").and_then(Value::as_array) {
+		if let [only] = all.as_slice() {
+			return render(only, indent);
+		}
+		return all.iter().map(|m| render(m, indent)).collect::<Vec<_>>().join(" & ");
+	}
+	let is_string = schema.get("type").and_then(Value::as_str) == Some("string");
+	if let Some(members) = schema.get("anyOf").or_else(|| schema.get("oneOf")) {
+		let members = members.as_array().expect("anyOf/oneOf is an array");
+		// An untagged catch-all next to `type`-tagged members (`EventDto::Other`) would widen the
+		// tag to `string` and defeat narrowing on `type`, so TypeScript only sees the tagged ones.
+		let tagged = members.iter().any(|m| tag(m).is_some_and(|t| t.get("enum").is_some()));
+		let rendered: Vec<String> = members
+			.iter()
+			.filter(|m| !(tagged && tag(m).is_some_and(|t| t.get("enum").is_none())))
+			.map(|m| {
+				if is_unconstrained(m) {
+					if is_string {
+						"(string & {})".to_string()
+					} else {
+						"{ [key: string]: unknown }".to_string()
+					}
+				} else {
+					render(m, indent + 1)
+				}
+			})
+			.collect();
+		return union(rendered, indent);
+	}
+	if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+		let rendered = values.iter().map(|v| v.to_string()).collect();
+		return union(rendered, indent);
+	}
+	match schema.get("type").and_then(Value::as_str) {
+		Some("string") => "string".into(),
+		Some("boolean") => "boolean".into(),
+		Some("number") => "number".into(),
+		Some("integer") => match schema.get("format").and_then(Value::as_str) {
+			Some("uint64" | "int64" | "uint" | "int") => "Int".into(),
+			_ => "number".into(),
+		},
+		Some("array") => {
+			let item = render(&schema["items"], indent);
+			if item.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+				format!("{item}[]")
+			} else {
+				format!("Array<{item}>")
+			}
+		},
+		Some("object") if schema.get("properties").is_some() => render_object(schema, indent),
+		Some("object") => "Record<string, unknown>".into(),
+		_ => "unknown".into(),
+	}
+}
+
+fn render_object(schema: &Value, indent: usize) -> String {
+	let required: BTreeSet<&str> = schema
+		.get("required")
+		.and_then(Value::as_array)
+		.map(|r| r.iter().filter_map(Value::as_str).collect())
+		.unwrap_or_default();
+	let pad = "  ".repeat(indent + 1);
+	let mut out = String::from("{\n");
+	let props = schema["properties"].as_object().expect("properties");
+	// The `type` tag of tagged unions goes first; everything else keeps schema (sorted) order.
+	let tag = props.get_key_value("type");
+	for (name, prop) in tag.into_iter().chain(props.iter().filter(|(k, _)| *k != "type")) {
+		push_doc(&mut out, prop.get("description"), indent + 1);
+		let optional = if required.contains(name.as_str()) { "" } else { "?" };
+		let _ = writeln!(out, "{pad}{name}{optional}: {};", render(prop, indent + 1));
+	}
+	out.push_str(&"  ".repeat(indent));
+	out.push('}');
+	out
+}
+
+fn union(members: Vec<String>, indent: usize) -> String {
+	if members.iter().any(|m| m.contains('\n')) {
+		let pad = "  ".repeat(indent + 1);
+		members.iter().map(|m| format!("\n{pad}| {m}")).collect()
+	} else {
+		members.join(" | ")
+	}
+}
+
+/// The schema of an object's `type` property.
+fn tag(schema: &Value) -> Option<&Value> {
+	schema.get("properties")?.get("type")
+}
+
+fn is_nullable(schema: &Value) -> bool {
+	schema.get("nullable").and_then(Value::as_bool) == Some(true)
+}
+
+/// A schema that only carries documentation (e.g. the forward-compatible `Unknown` variants).
+fn is_unconstrained(schema: &Value) -> bool {
+	schema.as_object().is_some_and(|o| o.keys().all(|k| k == "description" || k == "title"))
+}
+
+fn push_doc(out: &mut String, description: Option<&Value>, indent: usize) {
+	let Some(text) = description.and_then(Value::as_str) else {
+		return;
+	};
+	let pad = "  ".repeat(indent);
+	let lines: Vec<&str> = text.lines().collect();
+	if let [line] = lines.as_slice() {
+		let _ = writeln!(out, "{pad}/** {line} */");
+	} else {
+		let _ = writeln!(out, "{pad}/**");
+		for line in lines {
+			let _ = writeln!(out, "{pad} *{}{line}", if line.is_empty() { "" } else { " " });
+		}
+		let _ = writeln!(out, "{pad} */");
+	}
+}
+
+fn collect_refs(schema: &Value, out: &mut BTreeSet<String>) {
+	match schema {
+		Value::Object(o) => {
+			if let Some(r) = o.get("$ref").and_then(Value::as_str) {
+				out.insert(r.rsplit('/').next().unwrap_or(r).to_string());
+			}
+			o.values().for_each(|v| collect_refs(v, out));
+		},
+		Value::Array(a) => a.iter().for_each(|v| collect_refs(v, out)),
+		_ => {},
+	}
+}
+
+fn camel_case(s: &str) -> String {
+	let mut out = String::with_capacity(s.len());
+	let mut upper = false;
+	for c in s.chars() {
+		if c == '_' {
+			upper = true;
+		} else if upper {
+			out.push(c.to_ascii_uppercase());
+			upper = false;
+		} else {
+			out.push(c);
+		}
+	}
+	out
+}
@@ -0,0 +1,34 @@
+//! Fails when `docs/api/openapi.json` no longer matches the DTOs and route table.
+//!
+//! Regenerate with `UPDATE_OPENAPI=1 cargo test -p rgbldk-api --features schema --test openapi`
+//! (or `rgbldk api openapi > docs/api/openapi.json`).
+
+use std::path::Path;
+
+#[test]
+fn checked_in_openapi_is_up_to_date() {
+	let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../docs/api/openapi.json");
+	let expected = rgbldk_api::openapi::to_json_pretty();
+	if std::env::var_os("UPDATE_OPENAPI").is_some() {
+		std::fs::write(&path, &expected).expect("write openapi.json");
+		return;
+	}
+	let actual = std::fs::read_to_string(&path).unwrap_or_default();
+	assert!(
+		actual == expected,
+		"docs/api/openapi.json is stale; regenerate it with \
+		 `UPDATE_OPENAPI=1 cargo test -p rgbldk-api --features schema --test openapi`"
+	);
+}
+
+#[test]
+fn every_route_is_documented() {
+	let doc = rgbldk_api::openapi::document();
+	for route in rgbldk_api::routes::ROUTES {
+		let path = format!("/api/{}{}", rgbldk_api::API_VERSION, route.path);
+		let method = route.method.as_str().to_ascii_lowercase();
+		let op = &doc["paths"][&path][&method];
+		assert_eq!(op["operationId"], route.operation_id, "{method} {path}");
+		assert!(op["responses"]["default"].is_object(), "{method} {path} lacks ErrorResponse");
+	}
+}
@@ -0,0 +1,29 @@
+//! Fails when the generated files under `sdk/` no longer match the DTOs and route table.
+//!
+//! Regenerate with `UPDATE_SDK=1 cargo test -p rgbldk-api --features schema --test sdk`.
+
+use std::path::Path;
+
+fn check(file: &str, expected: String) {
+	let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../sdk").join(file);
+	if std::env::var_os("UPDATE_SDK").is_some() {
+		std::fs::write(&path, &expected).expect("write generated sdk file");
+		return;
+	}
+	let actual = std::fs::read_to_string(&path).unwrap_or_default();
+	assert!(
+		actual == expected,
+		"sdk/{file} is stale; regenerate it with \
+		 `UPDATE_SDK=1 cargo test -p rgbldk-api --features schema --test sdk`"
+	);
+}
+
+#[test]
+fn types_ts_is_up_to_date() {
+	check("types.ts", rgbldk_api::typescript::types_ts());
+}
+
+#[test]
+fn client_ts_is_up_to_date() {
+	check("client.ts", rgbldk_api::typescript::client_ts());
+}
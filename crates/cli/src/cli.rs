@@ -1,4 +1,12 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use clap::{Args, Parser, Subcommand, ValueEnum};
+use rgbldk_api::amount::{Msat, Sat};
+use rgbldk_api::http::{PaymentDirection, PaymentKind, PaymentStatus};
+use rgbldk_api::ids::{NodeId, PaymentId, UserChannelId};
 
 #[derive(Clone, Copy, Debug, ValueEnum, PartialEq, Eq)]
 pub enum OutputOpt {
@@ -14,6 +22,13 @@ pub enum ColorOpt {
 	Never,
 }
 
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq, Eq)]
+pub enum UnitOpt {
+	Btc,
+	Sat,
+	Msat,
+}
+
 #[derive(Parser, Debug)]
 #[command(name = "rgbldk", about = "Human-friendly CLI for talking to rgbldkd over HTTP")]
 #[command(
@@ -46,6 +61,22 @@ pub struct Cli {
 	#[arg(long)]
 	pub no_truncate: bool,
 
+	/// Refuse to run against a daemon whose API is older than this CLI expects.
+	#[arg(long)]
+	pub strict: bool,
+
+	/// Unit for amounts in text tables (default: per-table; BTC/sats for balances, msat for payments).
+	#[arg(long, value_enum, global = true)]
+	pub unit: Option<UnitOpt>,
+
+	/// Record every request and response into a cassette in DIR (secrets redacted).
+	#[arg(long, value_name = "DIR", conflicts_with = "replay")]
+	pub record: Option<PathBuf>,
+
+	/// Answer every request from the cassette in DIR instead of the daemon (no network).
+	#[arg(long, value_name = "DIR")]
+	pub replay: Option<PathBuf>,
+
 	#[command(subcommand)]
 	pub command: Command,
 }
@@ -87,6 +118,106 @@ pub enum Command {
 		#[command(subcommand)]
 		command: EventsCommand,
 	},
+	/// Serve Prometheus metrics scraped from the daemon.
+	Exporter(ExporterArgs),
+	/// HTTP API description (no daemon needed).
+	Api {
+		#[command(subcommand)]
+		command: ApiCommand,
+	},
+}
+
+impl Command {
+	/// Operation ids (see `rgbldk_api::routes`) of the daemon routes this command calls.
+	///
+	/// Empty for commands that work without a daemon and for the probes (`node health`,
+	/// `node ready`, `node version`) that every daemon serves.
+	pub fn operations(&self) -> Vec<&'static str> {
+		match self {
+			Command::Ctx { .. } | Command::Api { .. } => vec![],
+			Command::Node { command } => match command {
+				NodeCommand::Health | NodeCommand::Ready | NodeCommand::Version => vec![],
+				NodeCommand::Status => vec!["status"],
+				NodeCommand::Id => vec!["node_id"],
+				NodeCommand::Listen => vec!["listening_addresses"],
+			},
+			Command::Wallet { command } => match command {
+				WalletCommand::Balance(_) => vec!["balances"],
+				WalletCommand::Address => vec!["wallet_new_address"],
+				WalletCommand::Sync => vec!["wallet_sync", "balances"],
+			},
+			Command::Peer { command } => match command {
+				PeerCommand::Ls => vec!["peers"],
+				PeerCommand::Connect(_) => vec!["peer_connect"],
+				PeerCommand::Disconnect { .. } => vec!["peer_disconnect"],
+			},
+			Command::Channel { command } => match command {
+				ChannelCommand::Ls => vec!["channels"],
+				ChannelCommand::Open(a) if a.wait_ready => {
+					vec!["channel_open", "status", "channels"]
+				},
+				ChannelCommand::Open(_) => vec!["channel_open"],
+				ChannelCommand::Close(_) => vec!["channel_close"],
+				ChannelCommand::ForceClose(_) => vec!["channel_force_close"],
+			},
+			Command::Pay { command } => match command {
+				PayCommand::Invoice { command } => match command {
+					InvoiceCommand::Create(a) => {
+						let create = if a.amount_msat.is_some() {
+							"bolt11_receive"
+						} else {
+							"bolt11_receive_var"
+						};
+						if a.wait.wait {
+							vec![create, "bolt11_decode", "payment"]
+						} else {
+							vec![create]
+						}
+					},
+					InvoiceCommand::Pay(_) => vec!["bolt11_pay"],
+				},
+				PayCommand::Offer { command } => match command {
+					OfferCommand::Create(a) => {
+						let create = if a.amount_msat.is_some() {
+							"bolt12_offer_receive"
+						} else {
+							"bolt12_offer_receive_var"
+						};
+						if a.wait.wait {
+							vec![create, "bolt12_offer_decode", "payment", "payments"]
+						} else {
+							vec![create]
+						}
+					},
+					OfferCommand::Decode { .. } => vec!["bolt12_offer_decode"],
+					OfferCommand::Pay(_) => vec!["bolt12_offer_send"],
+				},
+				PayCommand::Refund { command } => match command {
+					RefundCommand::Initiate(_) => vec!["bolt12_refund_initiate"],
+					RefundCommand::Decode { .. } => vec!["bolt12_refund_decode"],
+					RefundCommand::RequestPayment { .. } => vec!["bolt12_refund_request_payment"],
+				},
+				PayCommand::Keysend { .. } => vec!["spontaneous_send"],
+				PayCommand::Ls(_) => vec!["payments"],
+				PayCommand::Wait(_) => vec!["payment_wait"],
+				PayCommand::Abandon { .. } => vec!["payment_abandon"],
+				PayCommand::Get { .. } => vec!["payment"],
+			},
+			Command::Events { command } => match command {
+				EventsCommand::Next(args) if args.filter.skip == Some(SkipOpt::Ack) => {
+					vec!["events_wait_next", "events_handled"]
+				},
+				EventsCommand::Next(_) => vec!["events_wait_next"],
+				EventsCommand::Handled(_) => vec!["events_handled"],
+				EventsCommand::Watch(args) if args.hub.is_some() => vec![],
+				EventsCommand::Watch(_) | EventsCommand::Forward(_) | EventsCommand::Hub(_) => {
+					vec!["events_wait_next", "events_handled"]
+				},
+				EventsCommand::Log(_) => vec![],
+			},
+			Command::Exporter(_) => vec!["status", "balances", "channels", "peers", "payments"],
+		}
+	}
 }
 
 #[derive(Subcommand, Debug)]
@@ -111,6 +242,9 @@ pub struct CtxAddArgs {
 	/// Set as current after adding.
 	#[arg(long)]
 	pub use_now: bool,
+	/// Key for signing `events forward` webhooks (kept when updating a context without it).
+	#[arg(long, value_name = "SECRET")]
+	pub webhook_secret: Option<String>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -132,8 +266,8 @@ pub enum WalletCommand {
 
 #[derive(Args, Debug, Default)]
 pub struct WalletBalanceArgs {
-	/// Force displaying balances in sats (no BTC conversion, no separators).
-	#[arg(long)]
+	/// Force displaying balances in sats (no BTC conversion, no separators). Prefer `--unit sat`.
+	#[arg(long, conflicts_with = "unit")]
 	pub sats: bool,
 }
 
@@ -141,12 +275,12 @@ pub struct WalletBalanceArgs {
 pub enum PeerCommand {
 	Ls,
 	Connect(PeerConnectArgs),
-	Disconnect { node_id: String },
+	Disconnect { node_id: NodeId },
 }
 
 #[derive(Args, Debug)]
 pub struct PeerConnectArgs {
-	pub node_id: String,
+	pub node_id: NodeId,
 	pub addr: String,
 	#[arg(long)]
 	pub persist: bool,
@@ -163,24 +297,33 @@ pub enum ChannelCommand {
 #[derive(Args, Debug)]
 pub struct ChannelOpenArgs {
 	#[arg(long)]
-	pub node_id: String,
+	pub node_id: NodeId,
 	#[arg(long)]
 	pub addr: String,
+	/// Channel capacity, e.g. `100000`, `100k sat`, `0.001btc` (bare numbers are sats).
 	#[arg(long)]
-	pub amount_sats: u64,
+	pub amount_sats: Sat,
+	/// Amount to push to the counterparty (bare numbers are msat).
 	#[arg(long)]
-	pub push_msat: Option<u64>,
+	pub push_msat: Option<Msat>,
 	/// Create a private channel (unannounced).
 	#[arg(long)]
 	pub private: bool,
+	/// Wait until the channel is ready, following its events and the confirmations; exit 1 if
+	/// it is closed first.
+	#[arg(long)]
+	pub wait_ready: bool,
+	/// Stop waiting after SECS (exit code 9).
+	#[arg(long, value_name = "SECS", alias = "timeout", requires = "wait_ready")]
+	pub timeout_secs: Option<u64>,
 }
 
 #[derive(Args, Debug)]
 pub struct ChannelCloseArgs {
 	#[arg(long)]
-	pub user_channel_id: String,
+	pub user_channel_id: UserChannelId,
 	#[arg(long)]
-	pub counterparty_node_id: String,
+	pub counterparty_node_id: NodeId,
 }
 
 #[derive(Subcommand, Debug)]
@@ -210,16 +353,16 @@ pub enum PayCommand {
 	},
 
 	/// List known payments.
-	Ls,
+	Ls(PaymentListArgs),
 
 	/// Wait for a payment to reach a terminal state.
 	Wait(PaymentWaitArgs),
 
 	/// Abandon an in-flight outbound payment (useful for BOLT12 awaiting an invoice).
-	Abandon { payment_id: String },
+	Abandon { payment_id: PaymentId },
 
 	/// Get details for a payment id.
-	Get { payment_id: String },
+	Get { payment_id: PaymentId },
 }
 
 #[derive(Subcommand, Debug)]
@@ -234,18 +377,34 @@ pub enum InvoiceCommand {
 pub struct InvoiceCreateArgs {
 	#[arg(long)]
 	pub desc: String,
+	/// Amount, e.g. `1500000`, `21k sat`, `0.001btc`, `2.5mbtc` (bare numbers are msat).
 	#[arg(long)]
-	pub amount_msat: Option<u64>,
+	pub amount_msat: Option<Msat>,
 	#[arg(long, default_value_t = 3600)]
 	pub expiry_secs: u32,
+	#[command(flatten)]
+	pub wait: PaymentWaitOpt,
+}
+
+/// `--wait` of `pay invoice create` and `pay offer create`.
+#[derive(Args, Debug)]
+pub struct PaymentWaitOpt {
+	/// Print the invoice (or offer), then wait until it is paid: exit 0 when paid, 10 when it
+	/// expires first, 1 when the payment fails.
+	#[arg(long)]
+	pub wait: bool,
+	/// Stop waiting after SECS (exit code 9).
+	#[arg(long, value_name = "SECS", alias = "timeout", requires = "wait")]
+	pub timeout_secs: Option<u64>,
 }
 
 #[derive(Args, Debug)]
 pub struct InvoicePayArgs {
 	#[arg(long)]
 	pub invoice: String,
+	/// Amount, e.g. `1500000`, `21k sat`, `0.001btc`, `2.5mbtc` (bare numbers are msat).
 	#[arg(long)]
-	pub amount_msat: Option<u64>,
+	pub amount_msat: Option<Msat>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -262,8 +421,9 @@ pub enum OfferCommand {
 pub struct OfferCreateArgs {
 	#[arg(long)]
 	pub desc: String,
+	/// Amount, e.g. `1500000`, `21k sat`, `0.001btc`, `2.5mbtc` (bare numbers are msat).
 	#[arg(long)]
-	pub amount_msat: Option<u64>,
+	pub amount_msat: Option<Msat>,
 	/// Offer expiry in seconds from now. Use `--no-expiry` for no expiry.
 	#[arg(long, default_value_t = 3600)]
 	pub expiry_secs: u32,
@@ -273,6 +433,8 @@ pub struct OfferCreateArgs {
 	/// Optional item quantity.
 	#[arg(long)]
 	pub quantity: Option<u64>,
+	#[command(flatten)]
+	pub wait: PaymentWaitOpt,
 }
 
 #[derive(Args, Debug)]
@@ -281,7 +443,7 @@ pub struct OfferSendArgs {
 	pub offer: String,
 	/// Required for zero-amount offers; may be used to overpay fixed-amount offers.
 	#[arg(long)]
-	pub amount_msat: Option<u64>,
+	pub amount_msat: Option<Msat>,
 	#[arg(long)]
 	pub quantity: Option<u64>,
 	#[arg(long)]
@@ -302,8 +464,9 @@ pub enum RefundCommand {
 
 #[derive(Args, Debug)]
 pub struct RefundInitiateArgs {
+	/// Amount, e.g. `1500000`, `21k sat`, `0.001btc`, `2.5mbtc` (bare numbers are msat).
 	#[arg(long)]
-	pub amount_msat: u64,
+	pub amount_msat: Msat,
 	#[arg(long, default_value_t = 3600)]
 	pub expiry_secs: u32,
 	#[arg(long)]
@@ -312,9 +475,65 @@ pub struct RefundInitiateArgs {
 	pub payer_note: Option<String>,
 }
 
+#[derive(Args, Debug)]
+pub struct PaymentListArgs {
+	/// Payments per page, newest first (default: the daemon's, 100; at most 1000). JSON output
+	/// becomes a `{items, next_cursor}` page.
+	#[arg(long)]
+	pub limit: Option<u32>,
+	/// Start after this cursor (the `next_cursor` of a previous page); pages like `--limit`.
+	#[arg(long)]
+	pub cursor: Option<String>,
+	/// With `--limit` or `--cursor`, follow cursors and list every matching payment.
+	#[arg(long)]
+	pub all: bool,
+	/// Only show payments with this status (Pending, Succeeded, Failed).
+	#[arg(long, value_parser = PaymentStatus::from_str)]
+	pub status: Option<PaymentStatus>,
+	/// Only show payments in this direction (Inbound, Outbound).
+	#[arg(long, value_parser = PaymentDirection::from_str)]
+	pub direction: Option<PaymentDirection>,
+	/// Only show payments of this kind (Bolt11, Bolt11Jit, Bolt12Offer, Bolt12Refund, Spontaneous, Onchain).
+	#[arg(long, value_parser = PaymentKind::from_str)]
+	pub kind: Option<PaymentKind>,
+	/// Only show payments created since a Unix time or a span ago, e.g. `1750000000`, `90m`,
+	/// `24h`, `7d`.
+	#[arg(long)]
+	pub since: Option<TimeArg>,
+}
+
+/// A point in time given as Unix seconds or as a span before now.
+#[derive(Clone, Copy, Debug)]
+pub struct TimeArg(pub u64);
+
+impl std::str::FromStr for TimeArg {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s = s.trim();
+		if let Ok(unix) = s.parse::<u64>() {
+			return Ok(Self(unix));
+		}
+		let invalid = || format!("invalid time {s:?} (expected Unix seconds or e.g. 90m, 24h, 7d)");
+		let split = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+		let (n, unit) = s.split_at(split);
+		let n: u64 = n.parse().map_err(|_| invalid())?;
+		let scale = match unit {
+			"s" => 1,
+			"m" => 60,
+			"h" => 3_600,
+			"d" => 86_400,
+			"w" => 604_800,
+			_ => return Err(invalid()),
+		};
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+		Ok(Self(now.saturating_sub(n.saturating_mul(scale))))
+	}
+}
+
 #[derive(Args, Debug)]
 pub struct PaymentWaitArgs {
-	pub payment_id: String,
+	pub payment_id: PaymentId,
 	#[arg(long)]
 	pub timeout_secs: Option<u32>,
 }
@@ -339,9 +558,10 @@ impl std::str::FromStr for TlvArg {
 #[derive(Args, Debug)]
 pub struct KeysendArgs {
 	#[arg(long)]
-	pub node_id: String,
+	pub node_id: NodeId,
+	/// Amount, e.g. `1500000`, `21k sat`, `0.001btc`, `2.5mbtc` (bare numbers are msat).
 	#[arg(long)]
-	pub amount_msat: u64,
+	pub amount_msat: Msat,
 	#[arg(long, value_name = "<type>:<hex>")]
 	pub tlv: Vec<TlvArg>,
 }
@@ -354,9 +574,141 @@ pub enum KeysendCommand {
 
 #[derive(Subcommand, Debug)]
 pub enum EventsCommand {
-	Next,
-	Handled,
+	Next(EventsNextArgs),
+	Handled(EventsHandledArgs),
 	Watch(EventsWatchArgs),
+	/// POST each event to a webhook, signed with HMAC-SHA256; ACK once it answers 2xx.
+	Forward(EventsForwardArgs),
+	/// Query the local event journal (no daemon needed).
+	Log(EventsLogArgs),
+	/// Be the queue's only consumer: store and ACK each event, then serve it to local
+	/// subscribers (`events watch --hub`), each with its own durable cursor.
+	Hub(EventsHubArgs),
+}
+
+/// What to do with an event that does not match `--type`/`--match`.
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq, Eq)]
+pub enum SkipOpt {
+	/// ACK and drop it, then keep waiting.
+	Ack,
+	/// Leave it in the queue and exit with code 8.
+	Stop,
+}
+
+/// `FIELD=VALUE`: the event's `data.FIELD` (dots reach nested fields) equals VALUE.
+#[derive(Clone, Debug)]
+pub struct FieldMatch {
+	pub path: Vec<String>,
+	pub value: String,
+}
+
+impl std::str::FromStr for FieldMatch {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (field, value) =
+			s.split_once('=').filter(|(f, _)| !f.trim().is_empty()).ok_or_else(|| {
+				format!("invalid match {s:?} (expected FIELD=VALUE, e.g. payment_id=<hex>)")
+			})?;
+		Ok(Self {
+			path: field.trim().split('.').map(str::to_string).collect(),
+			value: value.trim().to_string(),
+		})
+	}
+}
+
+#[derive(Args, Debug)]
+pub struct EventFilterArgs {
+	/// Only these event types, comma-separated (e.g. `PaymentReceived,ChannelClosed`).
+	#[arg(long = "type", value_name = "TYPES", value_delimiter = ',', requires = "skip")]
+	pub types: Vec<String>,
+
+	/// Only events whose `data.FIELD` equals VALUE, e.g. `payment_id=<hex>` or
+	/// `user_channel_id=<hex>` (repeatable; all must hold).
+	#[arg(long = "match", value_name = "FIELD=VALUE", requires = "skip")]
+	pub matches: Vec<FieldMatch>,
+
+	/// Policy for events that do not match: `ack` drops them, `stop` leaves them queued for
+	/// another consumer and exits 8. Required with `--type` or `--match`.
+	#[arg(long, value_enum)]
+	pub skip: Option<SkipOpt>,
+}
+
+#[derive(Args, Debug)]
+pub struct EventsNextArgs {
+	#[command(flatten)]
+	pub filter: EventFilterArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct EventsHandledArgs {
+	/// ACK only if the head is the event with this id (as printed by `events next`); the
+	/// daemon answers 409 if another consumer changed the queue. Without it the head is ACKed,
+	/// whatever it is.
+	#[arg(long)]
+	pub id: Option<u64>,
+}
+
+#[derive(Args, Debug)]
+pub struct JournalOpt {
+	/// Append each event to the local journal before ACKing it (default FILE: the daemon's
+	/// journal under `$XDG_DATA_HOME/rgbldk/journal`).
+	#[arg(long, value_name = "FILE", num_args = 0..=1)]
+	pub journal: Option<Option<PathBuf>>,
+}
+
+#[derive(Args, Debug)]
+pub struct EventsLogArgs {
+	/// Only these event types, comma-separated (e.g. `PaymentReceived,ChannelClosed`).
+	#[arg(long = "type", value_name = "TYPES", value_delimiter = ',')]
+	pub types: Vec<String>,
+
+	/// Only events journaled at or after this time: Unix seconds, or a span before now such as
+	/// `90m`, `24h`, `7d`.
+	#[arg(long)]
+	pub since: Option<TimeArg>,
+
+	/// Only events journaled before this time (same formats as `--since`).
+	#[arg(long)]
+	pub until: Option<TimeArg>,
+
+	/// Only events about this payment id, user channel id, channel id or funding outpoint.
+	#[arg(long)]
+	pub id: Option<String>,
+
+	/// Show only the last N matching events.
+	#[arg(long)]
+	pub limit: Option<usize>,
+
+	/// Journal file (default: the daemon's journal).
+	#[arg(long, value_name = "FILE")]
+	pub journal: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct EventsForwardArgs {
+	/// Webhook endpoint.
+	#[arg(long)]
+	pub url: String,
+
+	/// HMAC-SHA256 key (default: `webhook_secret` of the active context).
+	#[arg(long, env = "RGBLDK_WEBHOOK_SECRET", hide_env_values = true, value_name = "SECRET")]
+	pub secret: Option<String>,
+
+	/// Stop after N events (default: run forever).
+	#[arg(long)]
+	pub count: Option<u64>,
+
+	/// Delay before the first redelivery; it doubles after each failure, up to 60 seconds.
+	#[arg(long, value_name = "MS", default_value_t = 1000)]
+	pub retry_delay_ms: u64,
+
+	/// Timeout of each webhook request.
+	#[arg(long, value_name = "SECS", default_value_t = 10)]
+	pub timeout_secs: u64,
+
+	#[command(flatten)]
+	pub journal: JournalOpt,
 }
 
 #[derive(Args, Debug)]
@@ -364,4 +716,65 @@ pub struct EventsWatchArgs {
 	/// Stop after N events (default: run forever).
 	#[arg(long)]
 	pub count: Option<u64>,
+
+	/// Run CMD (via `sh -c`) for each event, with the event as JSON on stdin; the event is
+	/// ACKed only once CMD exits 0.
+	#[arg(long, value_name = "CMD")]
+	pub exec: Option<String>,
+
+	/// Give up on an event after N failed runs of `--exec` (default: retry forever).
+	#[arg(long, value_name = "N", requires = "exec", value_parser = clap::value_parser!(u32).range(1..))]
+	pub max_attempts: Option<u32>,
+
+	/// Delay before the first retry; it doubles after each failure, up to 60 seconds.
+	#[arg(long, value_name = "MS", requires = "exec", default_value_t = 1000)]
+	pub retry_delay_ms: u64,
+
+	/// When `--max-attempts` runs out, append the event to FILE (JSON lines) and ACK it
+	/// instead of exiting.
+	#[arg(long, value_name = "FILE", requires = "max_attempts")]
+	pub dead_letter: Option<PathBuf>,
+
+	#[command(flatten)]
+	pub filter: EventFilterArgs,
+
+	#[command(flatten)]
+	pub journal: JournalOpt,
+
+	/// Subscribe to the `events hub` listening on SOCKET instead of consuming the daemon's
+	/// queue.
+	#[arg(long, value_name = "SOCKET", requires = "subscriber")]
+	pub hub: Option<PathBuf>,
+
+	/// Subscriber name the hub keeps this consumer's cursor under.
+	#[arg(long, value_name = "NAME", requires = "hub")]
+	pub subscriber: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct EventsHubArgs {
+	/// Unix socket to serve subscribers on.
+	#[arg(long, value_name = "PATH")]
+	pub socket: PathBuf,
+
+	/// Directory for the hub's event log and cursors (default:
+	/// `$XDG_DATA_HOME/rgbldk/hub/<host_port>`).
+	#[arg(long, value_name = "DIR")]
+	pub dir: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ApiCommand {
+	/// Print the OpenAPI 3 document for the `/api/v1` surface.
+	Openapi,
+}
+
+#[derive(Args, Debug)]
+pub struct ExporterArgs {
+	/// Address to serve `GET /metrics` on, e.g. `127.0.0.1:9477`.
+	#[arg(long, value_name = "ADDR")]
+	pub listen: SocketAddr,
+	/// Seconds between scrapes of the daemon.
+	#[arg(long, value_name = "SECS", default_value_t = 15, value_parser = clap::value_parser!(u64).range(1..))]
+	pub interval_secs: u64,
 }
@@ -0,0 +1,158 @@
+//! Version and capability negotiation with the daemon.
+//!
+//! The daemon's `/version` answer is cached per daemon URL (so per context) for
+//! [`CACHE_TTL_SECS`]; `rgbldk node version` always refreshes it, and so does a cached answer
+//! that would refuse a command, in case the daemon was upgraded since. With `--record` or `--replay`
+//! the cache is neither read nor written, so the cassette holds the `/version` exchange.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rgbldk_api::client::StatusCode;
+use rgbldk_api::compat::{self, Compatibility};
+use rgbldk_api::http::VersionResponse;
+use rgbldk_api::routes::Route;
+use rgbldk_api::{ApiError, RgbLdkClient, API_VERSION};
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::utils::{die_with, exit_code};
+
+pub const CACHE_TTL_SECS: u64 = 600;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DaemonCache {
+	#[serde(default)]
+	daemons: BTreeMap<String, CachedVersion>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedVersion {
+	fetched_at: u64,
+	version: VersionResponse,
+}
+
+fn now_secs() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn cache_path() -> Option<PathBuf> {
+	config::default_cache_dir().ok().map(|d| d.join("daemons.toml"))
+}
+
+fn load_cache(path: &Path) -> DaemonCache {
+	std::fs::read_to_string(path).ok().and_then(|s| toml::from_str(&s).ok()).unwrap_or_default()
+}
+
+fn cache_for(api: &RgbLdkClient) -> Option<PathBuf> {
+	if api.cassette_mode().is_some() {
+		return None;
+	}
+	cache_path()
+}
+
+/// Stores `version` as the cached answer for the client's daemon. Cache write failures are
+/// ignored.
+pub fn store(api: &RgbLdkClient, version: &VersionResponse) {
+	let Some(path) = cache_for(api) else {
+		return;
+	};
+	let url = api.base_url();
+	let mut cache = load_cache(&path);
+	let entry = CachedVersion { fetched_at: now_secs(), version: version.clone() };
+	cache.daemons.insert(url.to_string(), entry);
+	if let Some(parent) = path.parent() {
+		let _ = std::fs::create_dir_all(parent);
+	}
+	if let Ok(s) = toml::to_string_pretty(&cache) {
+		let _ = std::fs::write(&path, s);
+	}
+}
+
+/// The daemon's version, fetched (and cached).
+///
+/// `Ok(None)` means the daemon does not serve `/version` at all.
+async fn fetch_version(api: &RgbLdkClient) -> Result<Option<VersionResponse>, ApiError> {
+	match api.version().await {
+		Ok(v) => {
+			store(api, &v);
+			Ok(Some(v))
+		},
+		Err(e) if e.status() == Some(StatusCode::NOT_FOUND) => Ok(None),
+		Err(e) => Err(e),
+	}
+}
+
+/// The daemon's version from the cache when fresh (with `true`), otherwise fetched.
+async fn daemon_version(api: &RgbLdkClient) -> Result<Option<(VersionResponse, bool)>, ApiError> {
+	if let Some(path) = cache_for(api) {
+		let mut cache = load_cache(&path);
+		if let Some(hit) = cache.daemons.remove(api.base_url()) {
+			if now_secs().saturating_sub(hit.fetched_at) < CACHE_TTL_SECS {
+				return Ok(Some((hit.version, true)));
+			}
+		}
+	}
+	Ok(fetch_version(api).await?.map(|v| (v, false)))
+}
+
+/// Whether [`preflight`] refuses a command that calls `operations` on a daemon at `version`.
+fn refuses(version: &VersionResponse, operations: &[&str], strict: bool) -> bool {
+	let older = !matches!(
+		compat::check(version),
+		Compatibility::Compatible | Compatibility::DaemonNewer { .. }
+	);
+	(strict && older) || operations.iter().any(|op| !version.supports(op))
+}
+
+/// Checks the daemon before running a command that calls `operations`.
+///
+/// Warns (or, with `strict`, exits) when the daemon's API is older than this CLI expects, and
+/// exits with an explanation when the daemon does not advertise a route the command needs. If
+/// the daemon cannot be asked, nothing is checked and the command reports its own error. A
+/// cached answer is only refused on if a fresh one agrees.
+pub async fn preflight(api: &RgbLdkClient, operations: &[&str], strict: bool) {
+	let url = api.base_url();
+	let version = match daemon_version(api).await {
+		Ok(Some((v, true))) if refuses(&v, operations, strict) => fetch_version(api).await,
+		fetched => fetched.map(|v| v.map(|(v, _)| v)),
+	};
+	let version = match version {
+		Ok(Some(v)) => v,
+		Ok(None) => {
+			let msg = format!("daemon at {url} does not report its version (GET /api/{API_VERSION}/version); it predates this CLI");
+			return warn_or_refuse(&msg, strict);
+		},
+		Err(_) => return,
+	};
+
+	match compat::check(&version) {
+		Compatibility::Compatible | Compatibility::DaemonNewer { .. } => {},
+		c @ (Compatibility::DaemonOlder { .. } | Compatibility::Unknown { .. }) => {
+			warn_or_refuse(&format!("{c} (daemon at {url})"), strict)
+		},
+	}
+
+	for op in operations {
+		if !version.supports(op) {
+			let route = Route::find(op)
+				.map(|r| format!("{} /api/{API_VERSION}{}", r.method, r.path))
+				.unwrap_or_else(|| op.to_string());
+			die_with(
+				exit_code::INCOMPATIBLE,
+				format!(
+					"this command needs {route}, which the daemon at {url} (rgbldk-api {}) does not support; upgrade rgbldkd to use it",
+					version.api_crate_version
+				),
+			);
+		}
+	}
+}
+
+fn warn_or_refuse(msg: &str, strict: bool) {
+	if strict {
+		die_with(exit_code::INCOMPATIBLE, format!("{msg}; refusing because of --strict"));
+	}
+	eprintln!("warning: {msg}; some commands may fail (use --strict to refuse)");
+}
@@ -14,6 +14,36 @@ pub struct CliConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CtxEntry {
 	pub url: String,
+	/// Key for the HMAC signatures of `rgbldk events forward`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub webhook_secret: Option<String>,
+}
+
+impl CliConfig {
+	/// The context selected by `RGBLDK_CTX`, else the current one.
+	pub fn active_context(&self) -> Option<&CtxEntry> {
+		let from_env = std::env::var("RGBLDK_CTX").ok();
+		let name = from_env.as_deref().map(str::trim).filter(|n| !n.is_empty());
+		self.contexts.get(name.or(self.current.as_deref())?)
+	}
+
+	/// A copy safe to print: webhook secrets are masked.
+	pub fn redacted(&self) -> Self {
+		let mut cfg = self.clone();
+		for ctx in cfg.contexts.values_mut() {
+			ctx.redact();
+		}
+		cfg
+	}
+}
+
+impl CtxEntry {
+	/// Masks the webhook secret for printing.
+	pub fn redact(&mut self) {
+		if self.webhook_secret.is_some() {
+			self.webhook_secret = Some(rgbldk_api::cassette::REDACTED.to_string());
+		}
+	}
 }
 
 fn home_dir() -> Option<PathBuf> {
@@ -35,6 +65,36 @@ pub fn default_config_path() -> Result<PathBuf, String> {
 	Ok(base.join("rgbldk").join("config.toml"))
 }
 
+fn xdg_cache_home() -> Option<PathBuf> {
+	std::env::var_os("XDG_CACHE_HOME").map(PathBuf::from)
+}
+
+pub fn default_cache_dir() -> Result<PathBuf, String> {
+	let base = if let Some(xdg) = xdg_cache_home() {
+		xdg
+	} else if let Some(home) = home_dir() {
+		home.join(".cache")
+	} else {
+		return Err("cannot determine cache dir (missing $HOME and $XDG_CACHE_HOME)".to_string());
+	};
+	Ok(base.join("rgbldk"))
+}
+
+fn xdg_data_home() -> Option<PathBuf> {
+	std::env::var_os("XDG_DATA_HOME").map(PathBuf::from)
+}
+
+pub fn default_data_dir() -> Result<PathBuf, String> {
+	let base = if let Some(xdg) = xdg_data_home() {
+		xdg
+	} else if let Some(home) = home_dir() {
+		home.join(".local").join("share")
+	} else {
+		return Err("cannot determine data dir (missing $HOME and $XDG_DATA_HOME)".to_string());
+	};
+	Ok(base.join("rgbldk"))
+}
+
 pub fn load_config(path: &Path) -> Result<CliConfig, String> {
 	if !path.exists() {
 		return Ok(CliConfig::default());
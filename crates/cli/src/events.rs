@@ -0,0 +1,529 @@
+//! Event consumers behind `rgbldk events`: user hooks and webhooks that must accept each event
+//! before it is ACKed, and the reconnecting loop that feeds them.
+
+use std::collections::hash_map::RandomState;
+use std::fs::OpenOptions;
+use std::future::Future;
+use std::hash::BuildHasher;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use rgbldk_api::http::EventDto;
+use rgbldk_api::stream::{EventSubscription, ReceivedEvent};
+use rgbldk_api::{ApiError, RgbLdkClient};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::Sha256;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::watch;
+
+use crate::cli::{EventFilterArgs, FieldMatch};
+#[cfg(unix)]
+use crate::hub;
+use crate::utils::{clear_status, die_api, die_with, exit_code, status, with_spinner};
+
+/// Longest delay between two attempts.
+pub const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Exponential backoff: each delay doubles the previous one, up to [`MAX_BACKOFF`].
+#[derive(Debug, Clone)]
+pub struct Backoff {
+	next: Duration,
+}
+
+impl Backoff {
+	pub fn new(initial: Duration) -> Self {
+		Self { next: initial }
+	}
+
+	pub fn next_delay(&mut self) -> Duration {
+		let delay = self.next;
+		self.next = (self.next * 2).min(MAX_BACKOFF);
+		delay
+	}
+}
+
+/// First delay before reconnecting to the daemon.
+const RECONNECT_DELAY: Duration = Duration::from_millis(500);
+
+/// `delay` scaled by a random factor in [0.5, 1), so that consumers cut off together do not
+/// reconnect in lockstep.
+fn jitter(delay: Duration) -> Duration {
+	let random = RandomState::new().hash_one(SystemTime::now());
+	delay.mul_f64(0.5 + (random % 1_000) as f64 / 2_000.0)
+}
+
+/// Errors worth reconnecting for: the daemon is unreachable, restarting, or failing for now.
+fn is_retriable(err: &ApiError) -> bool {
+	matches!(err, ApiError::Transport(_)) || err.is_server_error()
+}
+
+/// Resolves on Ctrl-C, or SIGTERM on Unix (e.g. `systemctl stop`).
+async fn shutdown_signal() {
+	#[cfg(unix)]
+	{
+		use tokio::signal::unix::{signal, SignalKind};
+		match signal(SignalKind::terminate()) {
+			Ok(mut term) => {
+				tokio::select! {
+					_ = tokio::signal::ctrl_c() => {},
+					_ = term.recv() => {},
+				}
+			},
+			Err(_) => {
+				let _ = tokio::signal::ctrl_c().await;
+			},
+		}
+	}
+	#[cfg(not(unix))]
+	let _ = tokio::signal::ctrl_c().await;
+}
+
+/// Where a [`Consumer`] takes events from.
+#[derive(Debug)]
+enum Source {
+	/// The daemon's queue.
+	Daemon(RgbLdkClient),
+	/// A subscriber of an `events hub`.
+	#[cfg(unix)]
+	Hub { socket: PathBuf, subscriber: String },
+}
+
+/// A connection to a [`Source`]. Errors are failures worth reconnecting for; the rest exit.
+#[derive(Debug)]
+enum Feed {
+	Daemon(EventSubscription),
+	#[cfg(unix)]
+	Hub(hub::Subscription),
+}
+
+impl Source {
+	fn name(&self) -> String {
+		match self {
+			Source::Daemon(api) => api.base_url().to_string(),
+			#[cfg(unix)]
+			Source::Hub { socket, .. } => format!("the hub on {}", socket.display()),
+		}
+	}
+
+	async fn connect(&self) -> Result<Feed, String> {
+		match self {
+			Source::Daemon(api) => match api.subscribe_events().await {
+				Ok(events) => Ok(Feed::Daemon(events)),
+				Err(e) if is_retriable(&e) => Err(e.to_string()),
+				Err(e) => die_api(e),
+			},
+			#[cfg(unix)]
+			Source::Hub { socket, subscriber } => {
+				hub::Subscription::connect(socket, subscriber).await.map(Feed::Hub)
+			},
+		}
+	}
+}
+
+impl Feed {
+	/// `Ok(None)` once the other end closed the connection.
+	async fn next(&mut self) -> Result<Option<ReceivedEvent>, String> {
+		match self {
+			Feed::Daemon(events) => match events.next().await {
+				Ok(received) => Ok(received),
+				Err(e) if is_retriable(&e) => Err(e.to_string()),
+				Err(e) => die_api(e),
+			},
+			#[cfg(unix)]
+			Feed::Hub(subscription) => subscription.next().await,
+		}
+	}
+
+	async fn ack(&mut self, received: &ReceivedEvent) -> Result<(), String> {
+		match self {
+			Feed::Daemon(events) => match events.ack(received).await {
+				Ok(_) => Ok(()),
+				Err(e) if is_retriable(&e) => Err(e.to_string()),
+				Err(e) => die_api(e),
+			},
+			#[cfg(unix)]
+			Feed::Hub(subscription) => subscription.ack(received).await,
+		}
+	}
+
+	fn closed(&self) -> &'static str {
+		match self {
+			Feed::Daemon(_) => "the daemon closed the event stream",
+			#[cfg(unix)]
+			Feed::Hub(_) => "the hub closed the connection",
+		}
+	}
+}
+
+/// The loop behind `events watch` and `events forward`. It reconnects with jittered backoff when
+/// the daemon (or hub) goes away, recognizes the event it already processed when that event
+/// comes back at the head, and exits on Ctrl-C without ACKing an event it has not finished.
+#[derive(Debug)]
+pub struct Consumer {
+	source: Source,
+	feed: Option<Feed>,
+	/// Processed, but the ACK did not get through.
+	unacked: Option<ReceivedEvent>,
+	spinner: bool,
+	interrupted: watch::Receiver<bool>,
+}
+
+impl Consumer {
+	/// Consumes the daemon's queue.
+	pub fn new(api: RgbLdkClient, spinner: bool) -> Self {
+		Self::with_source(Source::Daemon(api), spinner)
+	}
+
+	/// Subscribes to the `events hub` on `socket` as `subscriber`.
+	#[cfg(unix)]
+	pub fn hub(socket: PathBuf, subscriber: String, spinner: bool) -> Self {
+		Self::with_source(Source::Hub { socket, subscriber }, spinner)
+	}
+
+	fn with_source(source: Source, spinner: bool) -> Self {
+		let (tx, interrupted) = watch::channel(false);
+		tokio::spawn(async move {
+			shutdown_signal().await;
+			let _ = tx.send(true);
+		});
+		Self { source, feed: None, unacked: None, spinner, interrupted }
+	}
+
+	/// Waits for the next event, reconnecting as often as needed; exits when interrupted.
+	pub async fn next(&mut self) -> ReceivedEvent {
+		let mut interrupted = self.interrupted.clone();
+		tokio::select! {
+			biased;
+			_ = interrupted.wait_for(|i| *i) => die_with(exit_code::INTERRUPTED, "interrupted"),
+			received = self.receive() => received,
+		}
+	}
+
+	/// Runs `work` on `received`. When interrupted, exits before the event is ACKed, so the
+	/// daemon delivers it again.
+	pub async fn process<T>(&self, received: &ReceivedEvent, work: impl Future<Output = T>) -> T {
+		let mut interrupted = self.interrupted.clone();
+		tokio::select! {
+			biased;
+			_ = interrupted.wait_for(|i| *i) => die_with(
+				exit_code::INTERRUPTED,
+				format!(
+					"interrupted; the {} event was not ACKed and will be delivered again",
+					event_type(&received.event)
+				),
+			),
+			out = work => out,
+		}
+	}
+
+	/// ACKs `received`. If the daemon cannot be reached, the event comes back at the head once
+	/// reconnected and is ACKed then without being processed again.
+	pub async fn ack(&mut self, received: &ReceivedEvent) {
+		let Some(feed) = &mut self.feed else {
+			self.unacked = Some(received.clone());
+			return;
+		};
+		if let Err(e) = feed.ack(received).await {
+			eprintln!("could not ACK the {} event: {e}", event_type(&received.event));
+			self.unacked = Some(received.clone());
+			self.feed = None;
+		}
+	}
+
+	async fn receive(&mut self) -> ReceivedEvent {
+		let mut backoff = Backoff::new(RECONNECT_DELAY);
+		let mut attempt = 0;
+		loop {
+			let failure = match &mut self.feed {
+				Some(feed) => {
+					match with_spinner(self.spinner, "Waiting for events...", feed.next()).await {
+						Ok(Some(received)) => {
+							if self.unacked.take().is_some_and(|done| same_event(&done, &received))
+							{
+								eprintln!(
+									"the {} event was already processed; ACKing it",
+									event_type(&received.event)
+								);
+								self.ack(&received).await;
+								continue;
+							}
+							return received;
+						},
+						Ok(None) => feed.closed().to_string(),
+						Err(e) => e,
+					}
+				},
+				None => match self.source.connect().await {
+					Ok(feed) => {
+						if attempt > 0 {
+							clear_status();
+							eprintln!("reconnected to {}", self.source.name());
+						}
+						self.feed = Some(feed);
+						continue;
+					},
+					Err(e) => e,
+				},
+			};
+			self.feed = None;
+			attempt += 1;
+			let delay = jitter(backoff.next_delay());
+			status(&format!(
+				"disconnected: {failure}; reconnecting in {:.1}s (attempt {attempt})",
+				delay.as_secs_f64()
+			));
+			tokio::time::sleep(delay).await;
+		}
+	}
+}
+
+/// Whether `b`, seen at the head after a reconnect, is the event `a`: the same sequence number.
+/// Without one an identical event may be a new one, so it is processed again.
+fn same_event(a: &ReceivedEvent, b: &ReceivedEvent) -> bool {
+	a.seq.is_some() && a.seq == b.seq
+}
+
+/// The `type` tag of an event, e.g. `PaymentReceived`.
+pub fn event_type(event: &EventDto) -> String {
+	serde_json::to_value(event)
+		.ok()
+		.and_then(|v| v.get("type").and_then(|t| t.as_str()).map(str::to_string))
+		.unwrap_or_default()
+}
+
+fn now_secs() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// `--type` and `--match` of `events next` and `events watch`; empty matches every event.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+	pub types: Vec<String>,
+	pub fields: Vec<FieldMatch>,
+}
+
+impl EventFilter {
+	pub fn new(args: &EventFilterArgs) -> Self {
+		Self { types: args.types.clone(), fields: args.matches.clone() }
+	}
+
+	/// Types compare case-insensitively, and so do string fields (ids are hex). Other values
+	/// compare as JSON, e.g. `amount_msat=1000`.
+	pub fn matches(&self, event: &EventDto) -> bool {
+		let value = serde_json::to_value(event).unwrap_or_default();
+		let kind = value.get("type").and_then(|t| t.as_str()).unwrap_or_default();
+		(self.types.is_empty() || self.types.iter().any(|t| t.eq_ignore_ascii_case(kind)))
+			&& self.fields.iter().all(|m| {
+				let field = m.path.iter().try_fold(&value["data"], |v, key| v.get(key));
+				match field {
+					Some(Value::String(s)) => s.eq_ignore_ascii_case(&m.value),
+					Some(Value::Null) | None => false,
+					Some(other) => m.value.parse::<Value>().is_ok_and(|v| v == *other),
+				}
+			})
+	}
+}
+
+/// `events watch --exec`: a command run once per event until it succeeds.
+#[derive(Debug, Clone)]
+pub struct Hook {
+	pub command: String,
+	pub max_attempts: Option<u32>,
+	pub retry_delay: Duration,
+	pub dead_letter: Option<PathBuf>,
+}
+
+/// Line appended to the `--dead-letter` file for an event the hook never accepted.
+#[derive(Debug, Serialize)]
+struct DeadLetter<'a> {
+	failed_at: u64,
+	attempts: u32,
+	/// Exit code of the last run; `None` if it could not be started or was killed by a signal.
+	last_exit_code: Option<i32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	seq: Option<u64>,
+	event: &'a EventDto,
+}
+
+impl Hook {
+	/// Runs the hook until it exits 0, retrying with backoff. `Ok` means the event may be
+	/// ACKed: the hook accepted it, or it was written to the dead-letter file.
+	pub async fn deliver(&self, received: &ReceivedEvent) -> Result<(), String> {
+		let mut backoff = Backoff::new(self.retry_delay);
+		let mut attempt = 1;
+		loop {
+			let status = self.run(received, attempt).await;
+			let failure = match &status {
+				Ok(s) if s.success() => return Ok(()),
+				Ok(s) => format!("`{}` exited with {s}", self.command),
+				Err(e) => format!("could not run `{}`: {e}", self.command),
+			};
+			let last_exit_code = status.ok().and_then(|s| s.code());
+
+			if self.max_attempts.is_some_and(|max| attempt >= max) {
+				let Some(path) = &self.dead_letter else {
+					return Err(format!(
+						"{failure}; giving up after {attempt} attempts (the event was not ACKed)"
+					));
+				};
+				let line = DeadLetter {
+					failed_at: now_secs(),
+					attempts: attempt,
+					last_exit_code,
+					seq: received.seq,
+					event: &received.event,
+				};
+				append_json_line(path, &line)
+					.map_err(|e| format!("cannot write dead letter to {}: {e}", path.display()))?;
+				eprintln!(
+					"{failure}; gave up after {attempt} attempts, event written to {}",
+					path.display()
+				);
+				return Ok(());
+			}
+
+			let delay = backoff.next_delay();
+			let of = self.max_attempts.map(|m| format!("/{m}")).unwrap_or_default();
+			eprintln!("{failure} (attempt {attempt}{of}); retrying in {delay:?}");
+			tokio::time::sleep(delay).await;
+			attempt += 1;
+		}
+	}
+
+	async fn run(&self, received: &ReceivedEvent, attempt: u32) -> std::io::Result<ExitStatus> {
+		let mut cmd = if cfg!(windows) {
+			let mut cmd = Command::new("cmd");
+			cmd.arg("/C");
+			cmd
+		} else {
+			let mut cmd = Command::new("sh");
+			cmd.arg("-c");
+			cmd
+		};
+		cmd.arg(&self.command)
+			.env("RGBLDK_EVENT_TYPE", event_type(&received.event))
+			.env("RGBLDK_EVENT_ATTEMPT", attempt.to_string())
+			.stdin(Stdio::piped());
+		if let Some(seq) = received.seq {
+			cmd.env("RGBLDK_EVENT_SEQ", seq.to_string());
+		}
+		let mut child = cmd.spawn()?;
+		let mut input = serde_json::to_vec(&received.event).expect("events serialize");
+		input.push(b'\n');
+		if let Some(mut stdin) = child.stdin.take() {
+			// A hook that does not read its input closes the pipe early; that is not a failure.
+			let _ = stdin.write_all(&input).await;
+		}
+		child.wait().await
+	}
+}
+
+fn append_json_line<T: Serialize>(path: &Path, value: &T) -> std::io::Result<()> {
+	let mut line = serde_json::to_vec(value).map_err(std::io::Error::other)?;
+	line.push(b'\n');
+	let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+	file.write_all(&line)
+}
+
+/// Delivery id; the same on every redelivery of one event.
+pub const DELIVERY_HEADER: &str = "x-rgbldk-delivery";
+/// Unix time of the attempt, in seconds; part of the signed payload.
+pub const TIMESTAMP_HEADER: &str = "x-rgbldk-timestamp";
+/// See [`signature`].
+pub const SIGNATURE_HEADER: &str = "x-rgbldk-signature";
+/// The event's `type`.
+pub const EVENT_HEADER: &str = "x-rgbldk-event";
+
+fn hex(bytes: &[u8]) -> String {
+	bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// `sha256=<hex>` of HMAC-SHA256 over `"{timestamp}.{body}"`.
+pub fn signature(secret: &[u8], timestamp: u64, body: &[u8]) -> String {
+	let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC takes keys of any length");
+	mac.update(timestamp.to_string().as_bytes());
+	mac.update(b".");
+	mac.update(body);
+	format!("sha256={}", hex(&mac.finalize().into_bytes()))
+}
+
+/// The delivery id of an event: 32 hex characters. It is the event's sequence number when the
+/// daemon sends one, so that a redelivery after a restart carries the same id while identical
+/// events stay distinct. Without a sequence number every delivery gets a random id.
+fn delivery_id(seq: Option<u64>) -> String {
+	match seq {
+		Some(seq) => format!("{seq:032x}"),
+		None => {
+			let random = || RandomState::new().hash_one(SystemTime::now());
+			format!("{:016x}{:016x}", random(), random())
+		},
+	}
+}
+
+/// `events forward`: POSTs each event to a webhook until it answers 2xx.
+#[derive(Debug, Clone)]
+pub struct Forwarder {
+	pub url: String,
+	pub secret: String,
+	pub retry_delay: Duration,
+	pub http: reqwest::Client,
+}
+
+/// What `events forward` prints once the webhook accepted an event.
+#[derive(Debug, Serialize)]
+pub struct Delivery<'a> {
+	pub delivery_id: String,
+	pub status: u16,
+	pub attempts: u32,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub seq: Option<u64>,
+	pub event: &'a EventDto,
+}
+
+impl Forwarder {
+	/// Delivers the event, retrying with backoff until the webhook answers 2xx. Every attempt
+	/// carries the same delivery id and a fresh timestamp and signature.
+	pub async fn deliver<'a>(&self, received: &'a ReceivedEvent) -> Delivery<'a> {
+		let body = serde_json::to_vec(&received.event).expect("events serialize");
+		let id = delivery_id(received.seq);
+		let kind = event_type(&received.event);
+		let mut backoff = Backoff::new(self.retry_delay);
+		let mut attempt = 1;
+		loop {
+			let timestamp = now_secs();
+			let sent = self
+				.http
+				.post(&self.url)
+				.header(reqwest::header::CONTENT_TYPE, "application/json")
+				.header(DELIVERY_HEADER, &id)
+				.header(TIMESTAMP_HEADER, timestamp.to_string())
+				.header(SIGNATURE_HEADER, signature(self.secret.as_bytes(), timestamp, &body))
+				.header(EVENT_HEADER, &kind)
+				.body(body.clone())
+				.send()
+				.await;
+			let failure = match sent {
+				Ok(resp) if resp.status().is_success() => {
+					return Delivery {
+						delivery_id: id,
+						status: resp.status().as_u16(),
+						attempts: attempt,
+						seq: received.seq,
+						event: &received.event,
+					};
+				},
+				Ok(resp) => format!("webhook answered HTTP {}", resp.status().as_u16()),
+				Err(e) => format!("webhook request failed: {e}"),
+			};
+			let delay = backoff.next_delay();
+			eprintln!("{failure} for delivery {id} (attempt {attempt}); retrying in {delay:?}");
+			tokio::time::sleep(delay).await;
+			attempt += 1;
+		}
+	}
+}
@@ -0,0 +1,369 @@
+//! `rgbldk exporter`: Prometheus gauges for a daemon.
+//!
+//! A background task scrapes `/status`, `/balances`, `/channels`, `/peers`, `/payments/list`
+//! and `/readyz` every interval and renders the text exposition format; `GET /metrics` serves
+//! the last rendering, so a Prometheus scrape never waits on the daemon. A source that fails is
+//! reported as `rgbldk_scrape_ok{source="..."} 0` and its gauges are left out rather than
+//! zeroed. Another task follows `/events/stream` without ACKing and counts events by type.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Write as _};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use axum::http::{header, StatusCode};
+use axum::routing::get;
+use axum::Router;
+use rgbldk_api::http::{PaymentDetailsDto, PaymentStatus, PaymentsListRequest};
+use rgbldk_api::ids::PaymentId;
+use rgbldk_api::{ApiError, RgbLdkClient};
+use tokio::net::TcpListener;
+
+use crate::events::event_type;
+
+/// Version 0.0.4 of the Prometheus text exposition format.
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// How far the daemon's clock may run behind ours when asking for the payments created since
+/// the last scrape.
+const CLOCK_SKEW: Duration = Duration::from_secs(300);
+
+/// Events seen on `/events/stream` since the exporter started, by type.
+#[derive(Debug, Default)]
+struct EventCounts {
+	/// Sequence number of the last event counted; a reconnected stream starts again at the
+	/// head of the queue.
+	last_seq: u64,
+	by_type: BTreeMap<String, u64>,
+}
+
+/// The daemon's payments, kept up to date without listing them all on every scrape.
+#[derive(Debug, Default)]
+struct Payments {
+	/// Unix time the last successful refresh started; `None` until the first one.
+	synced_at: Option<u64>,
+	by_id: HashMap<PaymentId, PaymentDetailsDto>,
+}
+
+impl Payments {
+	/// Lists the payments created since the last refresh (all of them the first time), then
+	/// fetches each older payment that was still pending, since only those can change.
+	async fn refresh(&mut self, api: &RgbLdkClient) -> Result<(), ApiError> {
+		let started = now_secs();
+		let req = PaymentsListRequest {
+			limit: Some(PaymentsListRequest::MAX_LIMIT),
+			created_after: self.synced_at.map(|t| t.saturating_sub(CLOCK_SKEW.as_secs())),
+			..Default::default()
+		};
+		let listed = api.payments_list_all(&req).await?.items;
+		let mut pending: Vec<PaymentId> = self
+			.by_id
+			.values()
+			.filter(|p| p.status == PaymentStatus::Pending)
+			.map(|p| p.id.clone())
+			.collect();
+		for payment in listed {
+			pending.retain(|id| id != &payment.id);
+			self.by_id.insert(payment.id.clone(), payment);
+		}
+		for id in pending {
+			match api.payment(&id).await {
+				Ok(payment) => {
+					self.by_id.insert(id, payment);
+				},
+				Err(e) if e.status() == Some(StatusCode::NOT_FOUND) => {
+					self.by_id.remove(&id);
+				},
+				Err(e) => return Err(e),
+			}
+		}
+		self.synced_at = Some(started);
+		Ok(())
+	}
+}
+
+fn now_secs() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// A page in the text exposition format.
+#[derive(Debug, Default)]
+struct Page {
+	out: String,
+}
+
+impl Page {
+	/// Starts a metric family.
+	fn family(&mut self, name: &str, kind: &str, help: &str) {
+		let _ = writeln!(self.out, "# HELP {name} {help}\n# TYPE {name} {kind}");
+	}
+
+	fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl Display) {
+		self.out.push_str(name);
+		if !labels.is_empty() {
+			let labels: Vec<String> =
+				labels.iter().map(|(k, v)| format!("{k}=\"{}\"", escape(v))).collect();
+			let _ = write!(self.out, "{{{}}}", labels.join(","));
+		}
+		let _ = writeln!(self.out, " {value}");
+	}
+
+	/// A family with a single unlabelled gauge.
+	fn gauge(&mut self, name: &str, help: &str, value: impl Display) {
+		self.family(name, "gauge", help);
+		self.sample(name, &[], value);
+	}
+}
+
+/// Escapes a label value.
+fn escape(value: &str) -> String {
+	value.replace('\\', r"\\").replace('"', "\\\"").replace('\n', r"\n")
+}
+
+/// Scrapes every source once and renders the page.
+async fn scrape(
+	api: &RgbLdkClient, payments: &mut Payments, events: &Mutex<EventCounts>,
+) -> String {
+	let started = Instant::now();
+	let (status, balances, channels, peers, refreshed, ready) = tokio::join!(
+		api.status(),
+		api.balances(),
+		api.channels(),
+		api.peers(),
+		payments.refresh(api),
+		api.readyz()
+	);
+	let payments = refreshed.map(|()| payments.by_id.values());
+	let mut page = Page::default();
+
+	page.gauge("rgbldk_up", "Whether the daemon answered /readyz.", u8::from(ready.is_ok()));
+	page.family("rgbldk_scrape_ok", "gauge", "Whether the last scrape of each source succeeded.");
+	for (source, ok) in [
+		("status", status.is_ok()),
+		("balances", balances.is_ok()),
+		("channels", channels.is_ok()),
+		("peers", peers.is_ok()),
+		("payments", payments.is_ok()),
+		("readyz", ready.is_ok()),
+	] {
+		page.sample("rgbldk_scrape_ok", &[("source", source)], u8::from(ok));
+	}
+
+	if let Ok(ready) = &ready {
+		page.gauge("rgbldk_ready", "Whether the node runtime is ready.", u8::from(ready.ok));
+		page.family("rgbldk_health_check", "gauge", "Readiness sub-checks (1 = passed).");
+		for check in &ready.checks {
+			page.sample("rgbldk_health_check", &[("name", &check.name)], u8::from(check.ok));
+		}
+	}
+
+	if let Ok(status) = &status {
+		page.gauge(
+			"rgbldk_running",
+			"Whether the node runtime is running.",
+			u8::from(status.is_running),
+		);
+		page.gauge(
+			"rgbldk_listening",
+			"Whether the node listens for inbound peers.",
+			u8::from(status.is_listening),
+		);
+		page.gauge(
+			"rgbldk_best_block_height",
+			"Best block height seen by the node.",
+			status.best_block_height,
+		);
+	}
+
+	if let Ok(b) = &balances {
+		page.family("rgbldk_onchain_balance_sats", "gauge", "On-chain wallet balance.");
+		page.sample(
+			"rgbldk_onchain_balance_sats",
+			&[("kind", "total")],
+			b.total_onchain_balance_sats,
+		);
+		page.sample(
+			"rgbldk_onchain_balance_sats",
+			&[("kind", "spendable")],
+			b.spendable_onchain_balance_sats,
+		);
+		page.gauge(
+			"rgbldk_anchor_channels_reserve_sats",
+			"On-chain sats reserved for anchor channels.",
+			b.total_anchor_channels_reserve_sats,
+		);
+		page.gauge(
+			"rgbldk_lightning_balance_sats",
+			"Claimable Lightning balance.",
+			b.total_lightning_balance_sats,
+		);
+	}
+
+	if let Ok(channels) = &channels {
+		let state = |usable: bool, ready: bool| match (usable, ready) {
+			(true, _) => "usable",
+			(false, true) => "ready",
+			(false, false) => "pending",
+		};
+		page.family(
+			"rgbldk_channels",
+			"gauge",
+			"Channels by state (pending, ready but not usable, usable).",
+		);
+		for s in ["pending", "ready", "usable"] {
+			let n = channels.iter().filter(|c| state(c.is_usable, c.is_channel_ready) == s).count();
+			page.sample("rgbldk_channels", &[("state", s)], n);
+		}
+		let sum = |f: fn(&rgbldk_api::http::ChannelDetailsExtendedDto) -> u64| -> u64 {
+			channels.iter().map(f).sum()
+		};
+		page.gauge(
+			"rgbldk_channel_value_sats",
+			"Total capacity of all channels.",
+			sum(|c| c.channel_value_sats),
+		);
+		page.gauge(
+			"rgbldk_outbound_capacity_msat",
+			"Sendable capacity over all channels.",
+			sum(|c| c.outbound_capacity_msat),
+		);
+		page.gauge(
+			"rgbldk_inbound_capacity_msat",
+			"Receivable capacity over all channels.",
+			sum(|c| c.inbound_capacity_msat),
+		);
+
+		let mut assets: BTreeMap<String, (usize, u64, u64)> = BTreeMap::new();
+		for rgb in channels.iter().filter_map(|c| c.rgb_balance.as_ref()) {
+			let entry = assets.entry(rgb.asset_id.to_string()).or_default();
+			entry.0 += 1;
+			entry.1 += rgb.local_amount;
+			entry.2 += rgb.remote_amount;
+		}
+		page.family("rgbldk_rgb_channels", "gauge", "RGB channels by asset.");
+		for (asset, (count, _, _)) in &assets {
+			page.sample("rgbldk_rgb_channels", &[("asset_id", asset)], count);
+		}
+		page.family(
+			"rgbldk_rgb_channel_balance",
+			"gauge",
+			"RGB channel balances by asset and side.",
+		);
+		for (asset, (_, local, remote)) in &assets {
+			page.sample(
+				"rgbldk_rgb_channel_balance",
+				&[("asset_id", asset), ("side", "local")],
+				local,
+			);
+			page.sample(
+				"rgbldk_rgb_channel_balance",
+				&[("asset_id", asset), ("side", "remote")],
+				remote,
+			);
+		}
+	}
+
+	if let Ok(peers) = &peers {
+		let connected = peers.iter().filter(|p| p.is_connected).count();
+		page.family("rgbldk_peers", "gauge", "Known peers by connection state.");
+		page.sample("rgbldk_peers", &[("state", "connected")], connected);
+		page.sample("rgbldk_peers", &[("state", "disconnected")], peers.len() - connected);
+	}
+
+	if let Ok(payments) = payments {
+		let mut groups: BTreeMap<(String, String), (usize, u64)> = BTreeMap::new();
+		for p in payments {
+			let entry = groups.entry((p.direction.to_string(), p.status.to_string())).or_default();
+			entry.0 += 1;
+			entry.1 += p.amount_msat.unwrap_or(0);
+		}
+		page.family("rgbldk_payments", "gauge", "Payments by direction and status.");
+		for ((direction, status), (count, _)) in &groups {
+			page.sample("rgbldk_payments", &[("direction", direction), ("status", status)], count);
+		}
+		page.family(
+			"rgbldk_payments_amount_msat",
+			"gauge",
+			"Payment amounts by direction and status.",
+		);
+		for ((direction, status), (_, msat)) in &groups {
+			let labels = [("direction", direction.as_str()), ("status", status.as_str())];
+			page.sample("rgbldk_payments_amount_msat", &labels, msat);
+		}
+	}
+
+	page.family(
+		"rgbldk_events_total",
+		"counter",
+		"Events seen on /events/stream since the exporter started.",
+	);
+	for (kind, n) in &events.lock().unwrap().by_type {
+		page.sample("rgbldk_events_total", &[("type", kind)], n);
+	}
+
+	page.gauge(
+		"rgbldk_scrape_duration_seconds",
+		"How long the last scrape took.",
+		format!("{:.3}", started.elapsed().as_secs_f64()),
+	);
+	page.gauge("rgbldk_last_scrape_timestamp_seconds", "Unix time of the last scrape.", now_secs());
+	page.out
+}
+
+/// Counts events from `/events/stream`, reconnecting every `retry` while it is unavailable.
+async fn count_events(api: RgbLdkClient, events: Arc<Mutex<EventCounts>>, retry: Duration) {
+	loop {
+		if let Ok(mut stream) = api.events_stream().await {
+			while let Ok(Some(envelope)) = stream.next().await {
+				let mut events = events.lock().unwrap();
+				if envelope.seq > events.last_seq {
+					events.last_seq = envelope.seq;
+					*events.by_type.entry(event_type(&envelope.event)).or_default() += 1;
+				}
+			}
+		}
+		tokio::time::sleep(retry).await;
+	}
+}
+
+/// Serves `/metrics` on `listen`, scraping the daemon every `interval`. Returns only if the
+/// listener cannot be bound or the server fails.
+pub async fn run(api: RgbLdkClient, listen: SocketAddr, interval: Duration) -> Result<(), String> {
+	let listener =
+		TcpListener::bind(listen).await.map_err(|e| format!("listen on {listen}: {e}"))?;
+	let addr = listener.local_addr().map_err(|e| format!("listen on {listen}: {e}"))?;
+
+	let events = Arc::new(Mutex::new(EventCounts::default()));
+	tokio::spawn(count_events(api.clone(), events.clone(), interval));
+	let mut payments = Payments::default();
+	let page = Arc::new(Mutex::new(scrape(&api, &mut payments, &events).await));
+	eprintln!(
+		"Serving metrics on http://{addr}/metrics (scraping {} every {}s)",
+		api.base_url(),
+		interval.as_secs_f64()
+	);
+	tokio::spawn({
+		let page = page.clone();
+		async move {
+			let mut ticks = tokio::time::interval(interval);
+			ticks.tick().await;
+			loop {
+				ticks.tick().await;
+				let rendered = scrape(&api, &mut payments, &events).await;
+				*page.lock().unwrap() = rendered;
+			}
+		}
+	});
+
+	let app = Router::new()
+		.route(
+			"/metrics",
+			get(move || {
+				let body = page.lock().unwrap().clone();
+				async move { ([(header::CONTENT_TYPE, CONTENT_TYPE)], body) }
+			}),
+		)
+		.fallback(|| async { (StatusCode::NOT_FOUND, "metrics are served at /metrics\n") });
+	axum::serve(listener, app).await.map_err(|e| format!("serve on {addr}: {e}"))
+}
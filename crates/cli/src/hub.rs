@@ -0,0 +1,435 @@
+//! `rgbldk events hub`: one consumer of the daemon's queue, many local subscribers.
+//!
+//! The hub is the only process that ACKs the daemon's events. It appends each event to its log
+//! (`events.jsonl`, with `fsync`) before the ACK, numbering them from 1, and serves the log on a
+//! Unix socket. Every subscriber has a name and a durable cursor (`cursors.json`): the id of the
+//! last event it ACKed to the hub. A subscriber gets the events after its cursor in order, one
+//! at a time, so one that is down or slow only holds up itself; a new name starts at the oldest
+//! event still in the log. Events every subscriber has ACKed are dropped from the log once there
+//! are [`COMPACT_AFTER`] of them and they are at least half of it, so it stays about as large as
+//! what the slowest subscriber has left.
+//!
+//! The protocol is JSON lines. The subscriber sends `{"subscribe":"<name>"}`; the hub sends an
+//! event in the shape of `/events/wait_next` (`{"id":N,"type":...,"data":...}`) and waits for
+//! `{"ack":N}`. Once the cursor is on disk it answers `{"acked":N}` and sends the next event. On a
+//! protocol or storage error the hub sends `{"error":"..."}` and hangs up.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rgbldk_api::http::{EventDto, QueuedEvent};
+use rgbldk_api::stream::ReceivedEvent;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{watch, Mutex as AsyncMutex};
+
+use crate::config;
+use crate::events::Consumer;
+use crate::journal::url_stem;
+use crate::utils::die;
+
+/// One event in the hub's log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HubEntry {
+	/// The hub's own sequence number, from 1.
+	pub id: u64,
+	/// Unix time the hub stored the event.
+	pub recorded_at: u64,
+	/// The daemon's sequence number, when it sent one.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub seq: Option<u64>,
+	/// The event as the daemon sent it.
+	pub event: EventDto,
+}
+
+/// A line from a subscriber.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Request {
+	Subscribe(String),
+	Ack(u64),
+}
+
+/// The hub's answer to an ACK, once the cursor is stored.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Reply {
+	Acked(u64),
+}
+
+/// The hub's directory for the daemon at `base_url`: `$XDG_DATA_HOME/rgbldk/hub/<host_port>`.
+pub fn default_dir(base_url: &str) -> Result<PathBuf, String> {
+	Ok(config::default_data_dir()?.join("hub").join(url_stem(base_url)))
+}
+
+/// The fewest ACKed entries worth rewriting the log for.
+const COMPACT_AFTER: usize = 64;
+
+fn now_secs() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Writes `bytes` to `path` through a temporary file, synced, then renamed, and the directory
+/// synced so that the rename survives a power loss.
+fn replace(dir: &Path, path: &Path, bytes: &[u8]) -> Result<(), String> {
+	let mut tmp = path.as_os_str().to_owned();
+	tmp.push(".tmp");
+	let tmp = PathBuf::from(tmp);
+	let write = || -> std::io::Result<()> {
+		let mut file = std::fs::File::create(&tmp)?;
+		file.write_all(bytes)?;
+		file.sync_all()?;
+		std::fs::rename(&tmp, path)?;
+		std::fs::File::open(dir)?.sync_all()
+	};
+	write().map_err(|e| format!("write {}: {e}", path.display()))
+}
+
+/// The event log and the cursors.
+///
+/// Its methods write and sync files, so async code calls them through [`blocking`].
+#[derive(Debug)]
+struct Store {
+	dir: PathBuf,
+	entries: Vec<HubEntry>,
+	cursors: BTreeMap<String, u64>,
+	/// Nothing appended since the log was opened.
+	reopened: bool,
+}
+
+impl Store {
+	fn open(dir: &Path) -> Result<Self, String> {
+		std::fs::create_dir_all(dir).map_err(|e| format!("mkdir {}: {e}", dir.display()))?;
+		let log = dir.join("events.jsonl");
+		let entries = match std::fs::read_to_string(&log) {
+			Ok(s) => s
+				.lines()
+				.enumerate()
+				.filter(|(_, l)| !l.trim().is_empty())
+				.filter_map(|(n, line)| match serde_json::from_str(line) {
+					Ok(entry) => Some(entry),
+					Err(e) => {
+						eprintln!(
+							"warning: {}:{}: skipping unreadable entry: {e}",
+							log.display(),
+							n + 1
+						);
+						None
+					},
+				})
+				.collect(),
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+			Err(e) => return Err(format!("read {}: {e}", log.display())),
+		};
+		let cursors_path = dir.join("cursors.json");
+		let cursors = match std::fs::read_to_string(&cursors_path) {
+			Ok(s) => serde_json::from_str(&s)
+				.map_err(|e| format!("parse {}: {e}", cursors_path.display()))?,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+			Err(e) => return Err(format!("read {}: {e}", cursors_path.display())),
+		};
+		let mut store = Self { dir: dir.to_path_buf(), entries, cursors, reopened: true };
+		store.compact()?;
+		Ok(store)
+	}
+
+	fn last_id(&self) -> u64 {
+		self.entries.last().map_or(0, |e| e.id)
+	}
+
+	/// Writes the event to the log, unless it is the last one again (the hub stopped between
+	/// storing and ACKing it). Returns the new entry.
+	///
+	/// Without sequence numbers only the first event after a restart can be such a redelivery,
+	/// so only that one is compared by content: two identical events in a row are both kept.
+	fn append(&mut self, received: &ReceivedEvent) -> Result<Option<HubEntry>, String> {
+		let reopened = std::mem::replace(&mut self.reopened, false);
+		if let Some(last) = self.entries.last() {
+			let again = match (last.seq, received.seq) {
+				(Some(a), Some(b)) => a == b,
+				_ => {
+					reopened
+						&& serde_json::to_value(&last.event).ok()
+							== serde_json::to_value(&received.event).ok()
+				},
+			};
+			if again {
+				return Ok(None);
+			}
+		}
+		let entry = HubEntry {
+			id: self.last_id() + 1,
+			recorded_at: now_secs(),
+			seq: received.seq,
+			event: received.event.clone(),
+		};
+		let path = self.dir.join("events.jsonl");
+		let mut line = serde_json::to_vec(&entry).expect("hub entries serialize");
+		line.push(b'\n');
+		let write = || -> std::io::Result<()> {
+			let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+			file.write_all(&line)?;
+			file.sync_data()
+		};
+		write().map_err(|e| format!("write {}: {e}", path.display()))?;
+		self.entries.push(entry.clone());
+		Ok(Some(entry))
+	}
+
+	/// The first entry after `cursor`.
+	fn next_after(&self, cursor: u64) -> Option<HubEntry> {
+		let i = self.entries.partition_point(|e| e.id <= cursor);
+		self.entries.get(i).cloned()
+	}
+
+	fn cursor(&self, subscriber: &str) -> u64 {
+		self.cursors.get(subscriber).copied().unwrap_or(0)
+	}
+
+	/// Moves the subscriber's cursor, writes all cursors, and compacts the log.
+	fn set_cursor(&mut self, subscriber: &str, id: u64) -> Result<(), String> {
+		let mut cursors = self.cursors.clone();
+		cursors.insert(subscriber.to_string(), id);
+		let json = serde_json::to_vec_pretty(&cursors).expect("cursors serialize");
+		replace(&self.dir, &self.dir.join("cursors.json"), &json)?;
+		self.cursors = cursors;
+		self.compact()
+	}
+
+	/// Drops the entries every subscriber has ACKed, once there are [`COMPACT_AFTER`] of them
+	/// and they are at least half of the log.
+	///
+	/// The last entry is always kept: it numbers the next one, and `append` compares a
+	/// redelivery against it.
+	fn compact(&mut self) -> Result<(), String> {
+		let Some(acked) = self.cursors.values().min() else {
+			return Ok(());
+		};
+		let upto = (*acked).min(self.last_id().saturating_sub(1));
+		let drop = self.entries.partition_point(|e| e.id <= upto);
+		if drop < COMPACT_AFTER || drop < self.entries.len() - drop {
+			return Ok(());
+		}
+		let mut log = Vec::new();
+		for entry in &self.entries[drop..] {
+			serde_json::to_writer(&mut log, entry).expect("hub entries serialize");
+			log.push(b'\n');
+		}
+		replace(&self.dir, &self.dir.join("events.jsonl"), &log)?;
+		self.entries.drain(..drop);
+		Ok(())
+	}
+}
+
+/// The store, behind a lock that async tasks wait on without blocking a worker, and who is
+/// connected.
+#[derive(Debug)]
+struct Shared {
+	store: AsyncMutex<Store>,
+	connected: Mutex<HashSet<String>>,
+}
+
+/// Runs `f` on the store on a blocking thread, for the methods that write files.
+async fn blocking<T: Send + 'static>(
+	shared: &Arc<Shared>, f: impl FnOnce(&mut Store) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+	let shared = shared.clone();
+	tokio::task::spawn_blocking(move || f(&mut shared.store.blocking_lock()))
+		.await
+		.map_err(|e| format!("the hub's store task failed: {e}"))?
+}
+
+/// Marks a subscriber connected for as long as it lives.
+struct Connected {
+	shared: Arc<Shared>,
+	name: String,
+}
+
+impl Connected {
+	fn register(shared: &Arc<Shared>, name: &str) -> Result<Self, String> {
+		if !shared.connected.lock().unwrap().insert(name.to_string()) {
+			return Err(format!("subscriber {name} is already connected"));
+		}
+		Ok(Self { shared: shared.clone(), name: name.to_string() })
+	}
+}
+
+impl Drop for Connected {
+	fn drop(&mut self) {
+		self.shared.connected.lock().unwrap().remove(&self.name);
+	}
+}
+
+async fn read_request(
+	lines: &mut Lines<BufReader<OwnedReadHalf>>,
+) -> Result<Option<Request>, String> {
+	let Some(line) = lines.next_line().await.map_err(|e| e.to_string())? else {
+		return Ok(None);
+	};
+	serde_json::from_str(&line).map(Some).map_err(|e| format!("bad request {line:?}: {e}"))
+}
+
+async fn send(write: &mut OwnedWriteHalf, value: &impl Serialize) -> Result<(), String> {
+	let mut line = serde_json::to_vec(value).expect("hub messages serialize");
+	line.push(b'\n');
+	write.write_all(&line).await.map_err(|e| e.to_string())
+}
+
+/// Serves one subscriber until it hangs up.
+async fn session(
+	lines: &mut Lines<BufReader<OwnedReadHalf>>, write: &mut OwnedWriteHalf, shared: &Arc<Shared>,
+	mut latest: watch::Receiver<u64>,
+) -> Result<(), String> {
+	let name = match read_request(lines).await? {
+		Some(Request::Subscribe(name)) => name,
+		Some(Request::Ack(_)) => return Err(r#"expected {"subscribe":"<name>"} first"#.into()),
+		None => return Ok(()),
+	};
+	let _connected = Connected::register(shared, &name)?;
+	loop {
+		let cursor = shared.store.lock().await.cursor(&name);
+		let entry = loop {
+			if let Some(entry) = shared.store.lock().await.next_after(cursor) {
+				break entry;
+			}
+			latest.changed().await.map_err(|_| "the hub stopped".to_string())?;
+		};
+		send(write, &QueuedEvent { id: Some(entry.id), event: entry.event }).await?;
+		match read_request(lines).await? {
+			Some(Request::Ack(id)) if id == entry.id => {
+				let subscriber = name.clone();
+				blocking(shared, move |store| store.set_cursor(&subscriber, id)).await?;
+				send(write, &Reply::Acked(id)).await?;
+			},
+			Some(Request::Ack(id)) => {
+				return Err(format!("ACK for event {id}, but event {} is outstanding", entry.id))
+			},
+			Some(Request::Subscribe(_)) => return Err(format!("already subscribed as {name}")),
+			None => return Ok(()),
+		}
+	}
+}
+
+/// Binds `socket`, replacing a socket file no hub is listening on any more.
+fn bind(socket: &Path) -> Result<UnixListener, String> {
+	if let Some(parent) = socket.parent().filter(|p| !p.as_os_str().is_empty()) {
+		std::fs::create_dir_all(parent).map_err(|e| format!("mkdir {}: {e}", parent.display()))?;
+	}
+	if socket.exists() {
+		if std::os::unix::net::UnixStream::connect(socket).is_ok() {
+			return Err(format!("another hub is listening on {}", socket.display()));
+		}
+		std::fs::remove_file(socket).map_err(|e| format!("remove {}: {e}", socket.display()))?;
+	}
+	UnixListener::bind(socket).map_err(|e| format!("listen on {}: {e}", socket.display()))
+}
+
+/// Consumes the daemon's queue with `events` and serves subscribers on `socket`, storing the
+/// log and cursors in `dir`; `show` is called with each new entry once it is ACKed.
+pub async fn run(
+	mut events: Consumer, socket: &Path, dir: &Path, mut show: impl FnMut(&HubEntry),
+) -> ! {
+	let store = Store::open(dir).unwrap_or_else(|e| die(e));
+	let listener = bind(socket).unwrap_or_else(|e| die(e));
+	let (latest, _) = watch::channel(store.last_id());
+	let shared = Arc::new(Shared { store: AsyncMutex::new(store), connected: Mutex::default() });
+	eprintln!("Hub listening on {} (log in {})", socket.display(), dir.display());
+
+	tokio::spawn({
+		let shared = shared.clone();
+		let latest = latest.clone();
+		async move {
+			loop {
+				let Ok((conn, _)) = listener.accept().await else {
+					continue;
+				};
+				let (shared, latest) = (shared.clone(), latest.subscribe());
+				tokio::spawn(async move {
+					let (read, mut write) = conn.into_split();
+					let mut lines = BufReader::new(read).lines();
+					if let Err(error) = session(&mut lines, &mut write, &shared, latest).await {
+						let _ = send(&mut write, &serde_json::json!({ "error": error })).await;
+					}
+				});
+			}
+		}
+	});
+
+	loop {
+		let received = events.next().await;
+		let entry = blocking(&shared, {
+			let received = received.clone();
+			move |store| store.append(&received)
+		})
+		.await
+		.unwrap_or_else(|e| die(e));
+		events.ack(&received).await;
+		if let Some(entry) = entry {
+			latest.send_replace(entry.id);
+			show(&entry);
+		}
+	}
+}
+
+/// A subscriber's connection to the hub (`events watch --hub`).
+#[derive(Debug)]
+pub struct Subscription {
+	lines: Lines<BufReader<OwnedReadHalf>>,
+	write: OwnedWriteHalf,
+}
+
+impl Subscription {
+	pub async fn connect(socket: &Path, subscriber: &str) -> Result<Self, String> {
+		let conn = UnixStream::connect(socket)
+			.await
+			.map_err(|e| format!("connect to {}: {e}", socket.display()))?;
+		let (read, mut write) = conn.into_split();
+		send(&mut write, &Request::Subscribe(subscriber.to_string())).await?;
+		Ok(Self { lines: BufReader::new(read).lines(), write })
+	}
+
+	/// The next event, or `None` once the hub hung up. Exits if the hub refused the
+	/// subscription.
+	pub async fn next(&mut self) -> Result<Option<ReceivedEvent>, String> {
+		let Some(line) = self.lines.next_line().await.map_err(|e| e.to_string())? else {
+			return Ok(None);
+		};
+		let value: Value = serde_json::from_str(&line)
+			.unwrap_or_else(|e| die(format!("the hub sent {line:?}: {e}")));
+		if let Some(error) = value.get("error").and_then(Value::as_str) {
+			die(format!("the hub refused: {error}"));
+		}
+		let queued: QueuedEvent = serde_json::from_value(value)
+			.unwrap_or_else(|e| die(format!("the hub sent {line:?}: {e}")));
+		if queued.id.is_none() {
+			die(format!("the hub sent an event without its id: {line:?}"));
+		}
+		Ok(Some(ReceivedEvent { seq: queued.id, event: queued.event }))
+	}
+
+	/// Moves this subscriber's cursor past `received` (by the id the hub sent with it), and
+	/// waits until the hub stored it.
+	pub async fn ack(&mut self, received: &ReceivedEvent) -> Result<(), String> {
+		let id = received.seq.ok_or("the event has no hub id to ACK")?;
+		send(&mut self.write, &Request::Ack(id)).await?;
+		let Some(line) = self.lines.next_line().await.map_err(|e| e.to_string())? else {
+			return Err("the hub hung up before confirming the ACK".into());
+		};
+		let value: Value =
+			serde_json::from_str(&line).map_err(|e| format!("the hub sent {line:?}: {e}"))?;
+		if let Some(error) = value.get("error").and_then(Value::as_str) {
+			return Err(format!("the hub refused the ACK: {error}"));
+		}
+		match serde_json::from_value(value) {
+			Ok(Reply::Acked(acked)) if acked == id => Ok(()),
+			_ => Err(format!("the hub answered {line:?} to the ACK of event {id}")),
+		}
+	}
+}
@@ -0,0 +1,184 @@
+//! Local event journal (JSON lines).
+//!
+//! `events watch --journal` and `events forward --journal` append each event before it is
+//! ACKed, so the history survives the daemon forgetting it; `events log` reads it back. Events
+//! are keyed by their id, and an event already in the journal (a redelivery after a crash, say)
+//! is not written twice.
+
+use std::collections::HashSet;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rgbldk_api::http::EventDto;
+use rgbldk_api::stream::ReceivedEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::events::event_type;
+
+/// One journaled event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+	/// Unix time the event was journaled.
+	pub recorded_at: u64,
+	/// Id the event was delivered with: its queue id (`id` on long-poll, `seq` on the stream),
+	/// or the hub's id with `--hub`. Absent for daemons that predate event ids.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub seq: Option<u64>,
+	/// The event's `type`.
+	#[serde(rename = "type")]
+	pub kind: String,
+	/// Payment, channel and outpoint ids the event refers to (see [`event_ids`]).
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub ids: Vec<String>,
+	/// The event as the daemon sent it.
+	pub event: EventDto,
+}
+
+/// Ids an event refers to: payment id, user channel id, channel id or funding outpoint.
+pub fn event_ids(event: &EventDto) -> Vec<String> {
+	match event {
+		EventDto::PaymentSuccessful { payment_id, .. }
+		| EventDto::PaymentFailed { payment_id }
+		| EventDto::PaymentReceived { payment_id, .. } => {
+			payment_id.iter().map(|p| p.to_string()).collect()
+		},
+		EventDto::ChannelPending { funding_txo } => {
+			vec![format!("{}:{}", funding_txo.txid, funding_txo.vout)]
+		},
+		EventDto::ChannelReady { user_channel_id } => vec![user_channel_id.to_string()],
+		EventDto::ChannelClosed { user_channel_id, channel_id, .. } => {
+			vec![user_channel_id.to_string(), channel_id.to_string()]
+		},
+		EventDto::Other { .. } => Vec::new(),
+	}
+}
+
+fn now_secs() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// `base_url` without the scheme, as a file name: `http://127.0.0.1:3001` is `127.0.0.1_3001`.
+pub fn url_stem(base_url: &str) -> String {
+	let host = base_url.split_once("://").map_or(base_url, |(_, rest)| rest);
+	host.trim_end_matches('/')
+		.chars()
+		.map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+		.collect()
+}
+
+/// The journal of the daemon at `base_url`: `$XDG_DATA_HOME/rgbldk/journal/<host_port>.jsonl`.
+pub fn default_path(base_url: &str) -> Result<PathBuf, String> {
+	let stem = url_stem(base_url);
+	Ok(config::default_data_dir()?.join("journal").join(format!("{stem}.jsonl")))
+}
+
+/// Reads every entry. Lines that do not parse (e.g. cut short by a crash) are skipped with a
+/// warning; a missing file is an empty journal.
+pub fn read(path: &Path) -> Result<Vec<JournalEntry>, String> {
+	let s = match std::fs::read_to_string(path) {
+		Ok(s) => s,
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+		Err(e) => return Err(format!("read {}: {e}", path.display())),
+	};
+	let mut entries = Vec::new();
+	for (n, line) in s.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+		match serde_json::from_str(line) {
+			Ok(entry) => entries.push(entry),
+			Err(e) => {
+				eprintln!("warning: {}:{}: skipping unreadable entry: {e}", path.display(), n + 1)
+			},
+		}
+	}
+	Ok(entries)
+}
+
+/// Appends events, skipping those already journaled. Events without an id are always written.
+#[derive(Debug)]
+pub struct Journal {
+	path: PathBuf,
+	seen: HashSet<u64>,
+}
+
+impl Journal {
+	pub fn open(path: PathBuf) -> Result<Self, String> {
+		let seen = read(&path)?.iter().filter_map(|e| e.seq).collect::<HashSet<_>>();
+		if let Some(parent) = path.parent() {
+			std::fs::create_dir_all(parent)
+				.map_err(|e| format!("mkdir {}: {e}", parent.display()))?;
+		}
+		Ok(Self { path, seen })
+	}
+
+	/// Writes the event to disk unless it is already there. Returns whether it was written.
+	pub fn record(&mut self, received: &ReceivedEvent) -> Result<bool, String> {
+		if received.seq.is_some_and(|seq| self.seen.contains(&seq)) {
+			return Ok(false);
+		}
+		let entry = JournalEntry {
+			recorded_at: now_secs(),
+			seq: received.seq,
+			kind: event_type(&received.event),
+			ids: event_ids(&received.event),
+			event: received.event.clone(),
+		};
+		let mut line = serde_json::to_vec(&entry).expect("journal entries serialize");
+		line.push(b'\n');
+		let write = || -> std::io::Result<()> {
+			let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+			file.write_all(&line)?;
+			file.sync_data()
+		};
+		write().map_err(|e| format!("write {}: {e}", self.path.display()))?;
+		self.seen.extend(received.seq);
+		Ok(true)
+	}
+}
+
+/// Filters of `events log`.
+#[derive(Debug, Default)]
+pub struct Query {
+	/// Event types, case-insensitive; empty matches all.
+	pub types: Vec<String>,
+	/// Recorded at or after (Unix seconds).
+	pub since: Option<u64>,
+	/// Recorded before (Unix seconds).
+	pub until: Option<u64>,
+	/// One of the entry's ids.
+	pub id: Option<String>,
+}
+
+impl Query {
+	pub fn matches(&self, entry: &JournalEntry) -> bool {
+		(self.types.is_empty() || self.types.iter().any(|t| t.eq_ignore_ascii_case(&entry.kind)))
+			&& self.since.is_none_or(|t| entry.recorded_at >= t)
+			&& self.until.is_none_or(|t| entry.recorded_at < t)
+			&& self
+				.id
+				.as_ref()
+				.is_none_or(|id| entry.ids.iter().any(|i| i.eq_ignore_ascii_case(id)))
+	}
+}
+
+/// `YYYY-MM-DD HH:MM:SSZ` for Unix seconds.
+pub fn format_utc(secs: u64) -> String {
+	let (days, rem) = (secs / 86_400, secs % 86_400);
+	// Civil date from days since 1970-01-01 (Howard Hinnant's algorithm).
+	let z = days as i64 + 719_468;
+	let era = z.div_euclid(146_097);
+	let doe = z.rem_euclid(146_097);
+	let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let day = doy - (153 * mp + 2) / 5 + 1;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 };
+	let year = yoe + era * 400 + i64::from(month <= 2);
+	format!(
+		"{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}Z",
+		rem / 3_600,
+		rem % 3_600 / 60,
+		rem % 60
+	)
+}
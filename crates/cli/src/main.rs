@@ -1,42 +1,52 @@
 mod cli;
-mod client;
+mod compat;
 mod config;
+mod events;
+mod exporter;
+#[cfg(unix)]
+mod hub;
+mod journal;
 mod ui;
 mod utils;
+mod wait;
 
 pub use crate::cli::OutputOpt;
 
-use clap::Parser;
+use std::time::Duration;
+
+use clap::{CommandFactory, Parser};
+use rgbldk_api::amount::Msat;
 use rgbldk_api::http::{
-	BalancesDto, Bolt11PayRequest, Bolt11PayResponse, Bolt11ReceiveRequest, Bolt11ReceiveResponse,
-	Bolt11ReceiveVarRequest, Bolt12OfferDecodeRequest, Bolt12OfferDecodeResponse,
-	Bolt12OfferReceiveRequest, Bolt12OfferReceiveVarRequest, Bolt12OfferResponse,
-	Bolt12OfferSendRequest, Bolt12RefundDecodeRequest, Bolt12RefundDecodeResponse,
-	Bolt12RefundInitiateRequest, Bolt12RefundInitiateResponse, Bolt12RefundRequestPaymentRequest,
-	Bolt12RefundRequestPaymentResponse, ChannelDetailsExtendedDto, CloseChannelRequest,
-	CustomTlvDto, EventDto, OkResponse, OpenChannelRequest, OpenChannelResponse, PaymentDetailsDto,
-	PaymentWaitRequest, PaymentWaitResponse, PeerConnectRequest, PeerDetailsDto,
-	PeerDisconnectRequest, SendResponse, SpontaneousSendRequest, StatusDto,
+	Bolt11DecodeRequest, Bolt11PayRequest, Bolt11ReceiveRequest, Bolt11ReceiveVarRequest,
+	Bolt12OfferDecodeRequest, Bolt12OfferReceiveRequest, Bolt12OfferReceiveVarRequest,
+	Bolt12OfferSendRequest, Bolt12RefundDecodeRequest, Bolt12RefundInitiateRequest,
+	Bolt12RefundRequestPaymentRequest, CloseChannelRequest, CustomTlvDto, EventAckRequest,
+	EventDto, OpenChannelRequest, PaymentDetailsDto, PaymentKindDetails, PaymentStatus,
+	PaymentWaitRequest, PaymentsListRequest, PaymentsPage, PeerConnectRequest,
+	PeerDisconnectRequest, SpontaneousSendRequest,
 };
+use rgbldk_api::ids::UserChannelId;
+use rgbldk_api::RgbLdkClient;
 
 use owo_colors::OwoColorize;
 
 use crate::cli::{
-	ChannelCommand, Cli, ColorOpt, Command, CtxCommand, EventsCommand, InvoiceCommand,
-	KeysendCommand, NodeCommand, OfferCommand, PayCommand, PeerCommand, RefundCommand,
-	WalletCommand,
+	ApiCommand, ChannelCommand, Cli, ColorOpt, Command, CtxCommand, EventsCommand, InvoiceCommand,
+	JournalOpt, KeysendCommand, NodeCommand, OfferCommand, PayCommand, PeerCommand, RefundCommand,
+	SkipOpt, UnitOpt, WalletCommand,
+};
+use crate::utils::{
+	api_exit_code, confirm_or_exit, die, die_api, die_with, exit_code, print_json, with_spinner,
 };
-use crate::client::{join_url, send_json, send_json_allow_status, send_value};
-use crate::utils::{confirm_or_exit, die, print_json, with_spinner};
 
 struct App {
-	base: String,
+	api: RgbLdkClient,
 	output: ui::OutputMode,
 	pretty: bool,
 	theme: ui::Theme,
 	yes: bool,
 	no_truncate: bool,
-	client: reqwest::Client,
+	unit: Option<UnitOpt>,
 }
 
 fn truncate_id(s: &str) -> String {
@@ -76,9 +86,41 @@ fn format_btc_from_sats(sats: u64) -> String {
 	format!("{s} BTC")
 }
 
-fn format_balance_sats(sats: u64, force_sats: bool) -> String {
+fn unit_label(unit: UnitOpt) -> &'static str {
+	match unit {
+		UnitOpt::Btc => "BTC",
+		UnitOpt::Sat => "sats",
+		UnitOpt::Msat => "msat",
+	}
+}
+
+/// Formats `msat` as a number in `unit` (exact, no unit suffix).
+fn format_msat_value(msat: u64, unit: UnitOpt) -> String {
+	let with_fraction = |whole: u64, frac: u64, digits: usize| {
+		let frac = format!("{frac:0digits$}");
+		let frac = frac.trim_end_matches('0');
+		if frac.is_empty() {
+			format_u64_with_commas(whole)
+		} else {
+			format!("{}.{frac}", format_u64_with_commas(whole))
+		}
+	};
+	match unit {
+		UnitOpt::Msat => format_u64_with_commas(msat),
+		UnitOpt::Sat => with_fraction(msat / 1_000, msat % 1_000, 3),
+		UnitOpt::Btc => with_fraction(msat / 100_000_000_000, msat % 100_000_000_000, 11),
+	}
+}
+
+fn format_msat(msat: u64, unit: UnitOpt) -> String {
+	format!("{} {}", format_msat_value(msat, unit), unit_label(unit))
+}
+
+fn format_balance_sats(sats: u64, force_sats: bool, unit: Option<UnitOpt>) -> String {
 	if force_sats {
 		format!("{sats} sats")
+	} else if let Some(unit) = unit {
+		format_msat(sats.saturating_mul(1_000), unit)
 	} else if sats >= 100_000_000 {
 		format_btc_from_sats(sats)
 	} else {
@@ -86,45 +128,134 @@ fn format_balance_sats(sats: u64, force_sats: bool) -> String {
 	}
 }
 
-fn print_event_text(ev: &EventDto) {
+fn format_payment_status(theme: ui::Theme, status: &PaymentStatus) -> String {
+	match status {
+		PaymentStatus::Succeeded if theme.color => format!("{}", "✔ Succeeded".green()),
+		PaymentStatus::Succeeded => "✔ Succeeded".to_string(),
+		PaymentStatus::Pending if theme.color => format!("{}", "… Pending".yellow()),
+		PaymentStatus::Pending => "… Pending".to_string(),
+		PaymentStatus::Failed if theme.color => format!("{}", "✘ Failed".red()),
+		PaymentStatus::Failed => "✘ Failed".to_string(),
+		PaymentStatus::Unknown(s) => s.clone(),
+	}
+}
+
+fn kind_details_rows(details: &PaymentKindDetails) -> Vec<Vec<String>> {
+	let opt = |v: &Option<String>| v.clone().unwrap_or_else(|| "-".into());
+	let opt_u64 = |v: &Option<u64>| v.map(|v| v.to_string()).unwrap_or_else(|| "-".into());
+	match details {
+		PaymentKindDetails::Bolt11 { payment_hash, preimage, secret }
+		| PaymentKindDetails::Bolt11Jit { payment_hash, preimage, secret } => vec![
+			vec!["payment_hash".into(), payment_hash.clone()],
+			vec!["preimage".into(), opt(preimage)],
+			vec!["secret".into(), opt(secret)],
+		],
+		PaymentKindDetails::Bolt12Offer {
+			payment_hash,
+			preimage,
+			secret,
+			offer_id,
+			payer_note,
+			quantity,
+		} => vec![
+			vec!["offer_id".into(), offer_id.clone()],
+			vec!["payment_hash".into(), opt(payment_hash)],
+			vec!["preimage".into(), opt(preimage)],
+			vec!["secret".into(), opt(secret)],
+			vec!["payer_note".into(), opt(payer_note)],
+			vec!["quantity".into(), opt_u64(quantity)],
+		],
+		PaymentKindDetails::Bolt12Refund {
+			payment_hash,
+			preimage,
+			secret,
+			payer_note,
+			quantity,
+		} => {
+			vec![
+				vec!["payment_hash".into(), opt(payment_hash)],
+				vec!["preimage".into(), opt(preimage)],
+				vec!["secret".into(), opt(secret)],
+				vec!["payer_note".into(), opt(payer_note)],
+				vec!["quantity".into(), opt_u64(quantity)],
+			]
+		},
+		PaymentKindDetails::Spontaneous { payment_hash, preimage } => vec![
+			vec!["payment_hash".into(), payment_hash.clone()],
+			vec!["preimage".into(), opt(preimage)],
+		],
+		PaymentKindDetails::Onchain { txid } => vec![vec!["txid".into(), txid.clone()]],
+		PaymentKindDetails::Unknown(v) => match v.as_object() {
+			Some(obj) => obj
+				.iter()
+				.map(|(k, v)| {
+					vec![k.clone(), v.as_str().map_or_else(|| v.to_string(), String::from)]
+				})
+				.collect(),
+			None => vec![vec!["raw".into(), v.to_string()]],
+		},
+	}
+}
+
+fn event_text(ev: &EventDto) -> String {
 	match ev {
 		EventDto::PaymentSuccessful { payment_id, fee_paid_msat } => {
-			let pid = payment_id.as_deref().unwrap_or("-");
+			let pid = payment_id.as_ref().map_or("-", |p| p.as_str());
 			let fee = fee_paid_msat
 				.map(|v| format!("{} msat", format_u64_with_commas(v)))
 				.unwrap_or_else(|| "-".into());
-			println!("PaymentSuccessful payment_id={pid} fee_paid={fee}");
+			format!("PaymentSuccessful payment_id={pid} fee_paid={fee}")
 		},
 		EventDto::PaymentFailed { payment_id } => {
-			let pid = payment_id.as_deref().unwrap_or("-");
-			println!("PaymentFailed payment_id={pid}");
+			let pid = payment_id.as_ref().map_or("-", |p| p.as_str());
+			format!("PaymentFailed payment_id={pid}")
 		},
 		EventDto::PaymentReceived { payment_id, amount_msat } => {
-			let pid = payment_id.as_deref().unwrap_or("-");
-			println!(
+			let pid = payment_id.as_ref().map_or("-", |p| p.as_str());
+			format!(
 				"PaymentReceived payment_id={pid} amount={} msat",
 				format_u64_with_commas(*amount_msat)
-			);
+			)
 		},
 		EventDto::ChannelPending { funding_txo } => {
-			println!("ChannelPending funding_txo={}:{}", funding_txo.txid, funding_txo.vout);
+			format!("ChannelPending funding_txo={}:{}", funding_txo.txid, funding_txo.vout)
 		},
 		EventDto::ChannelReady { user_channel_id } => {
-			println!("ChannelReady user_channel_id={user_channel_id}");
+			format!("ChannelReady user_channel_id={user_channel_id}")
 		},
 		EventDto::ChannelClosed { channel_id, user_channel_id, counterparty_node_id, reason } => {
-			let cp = counterparty_node_id.as_deref().unwrap_or("-");
+			let cp = counterparty_node_id.as_ref().map_or("-", |n| n.as_str());
 			let reason = reason.as_deref().unwrap_or("-");
-			println!(
+			format!(
 				"ChannelClosed user_channel_id={user_channel_id} channel_id={channel_id} counterparty_node_id={cp} reason={reason}"
-			);
+			)
 		},
-		EventDto::Other { kind } => {
-			println!("Other kind={kind}");
+		EventDto::Other { kind, data } => {
+			let mut text = kind.clone();
+			match data {
+				serde_json::Value::Object(fields) => {
+					// The legacy `Other` wrapper repeats the kind as `data.kind`.
+					for (name, value) in fields.iter().filter(|(name, value)| {
+						*name != "kind" || value.as_str() != Some(kind.as_str())
+					}) {
+						match value {
+							serde_json::Value::String(s) => text.push_str(&format!(" {name}={s}")),
+							value => text.push_str(&format!(" {name}={value}")),
+						}
+					}
+				},
+				serde_json::Value::Null => {},
+				data => text.push_str(&format!(" data={data}")),
+			}
+			text
 		},
 	}
 }
 
+fn print_event_text(ev: &EventDto) {
+	println!("{}", event_text(ev));
+}
+
 fn resolve_connect(cli_connect: Option<String>, cfg: &config::CliConfig) -> String {
 	if let Some(c) = cli_connect {
 		return c;
@@ -155,9 +286,92 @@ fn resolve_connect(cli_connect: Option<String>, cfg: &config::CliConfig) -> Stri
 	"http://127.0.0.1:8500".to_string()
 }
 
+/// `channel open --wait-ready`: prints the outcome and exits non-zero unless the channel is ready.
+async fn wait_channel_ready(app: &App, user_channel_id: UserChannelId, timeout: Option<Duration>) {
+	let text = app.output == ui::OutputMode::Text;
+	if text {
+		println!("{user_channel_id}");
+	}
+	let wait = wait::channel_ready(&app.api, user_channel_id, timeout, text)
+		.await
+		.unwrap_or_else(|e| die_api(e));
+	if !text {
+		print_json(&wait, app.pretty);
+	}
+	let funding = wait.funding_txo.as_deref().unwrap_or("unknown");
+	match wait.outcome {
+		wait::ChannelOutcome::Ready if text => {
+			eprintln!("Channel ready at height {} (funding {funding}).", wait.best_block_height)
+		},
+		wait::ChannelOutcome::Ready => {},
+		wait::ChannelOutcome::Closed => die(format!(
+			"channel closed before it was ready: {}",
+			wait.reason.as_deref().unwrap_or("reason unknown")
+		)),
+		wait::ChannelOutcome::Timeout => die_with(
+			exit_code::TIMEOUT,
+			format!(
+				"timed out waiting for channel {} (funding {funding}, height {})",
+				wait.user_channel_id, wait.best_block_height
+			),
+		),
+	}
+}
+
+/// `--wait` of `pay invoice create` and `pay offer create`, after the invoice or offer is printed.
+async fn wait_paid(app: &App, receivable: &wait::Receivable, timeout_secs: Option<u64>) {
+	let text = app.output == ui::OutputMode::Text;
+	let timeout = timeout_secs.map(Duration::from_secs);
+	let settlement = wait::payment_received(&app.api, receivable, timeout, text)
+		.await
+		.unwrap_or_else(|e| die_api(e));
+	if !text {
+		print_json(&settlement, app.pretty);
+	}
+	let payment = settlement.payment_id.as_ref().map_or_else(|| "-".into(), |id| id.to_string());
+	match settlement.outcome {
+		wait::PaymentOutcome::Paid if text => {
+			let amount = settlement.amount_msat.map_or_else(|| "?".into(), format_u64_with_commas);
+			eprintln!("Paid {amount} msat (payment {payment}).")
+		},
+		wait::PaymentOutcome::Paid => {},
+		wait::PaymentOutcome::Failed => die(format!("payment {payment} failed")),
+		wait::PaymentOutcome::Expired => die_with(exit_code::EXPIRED, "expired before it was paid"),
+		wait::PaymentOutcome::Timeout => {
+			die_with(exit_code::TIMEOUT, "timed out waiting for the payment")
+		},
+	}
+}
+
+/// `--skip stop`: exits without ACKing an event that did not match the filter.
+fn leave_queued(ev: &EventDto) -> ! {
+	die_with(
+		exit_code::NO_MATCH,
+		format!("next event does not match; left in the queue: {}", event_text(ev)),
+	)
+}
+
+/// Opens the `--journal` file, if one was requested.
+fn open_journal(opt: JournalOpt, base_url: &str) -> Option<journal::Journal> {
+	let path =
+		opt.journal?.unwrap_or_else(|| journal::default_path(base_url).unwrap_or_else(|e| die(e)));
+	Some(journal::Journal::open(path).unwrap_or_else(|e| die(e)))
+}
+
 #[tokio::main]
 async fn main() {
 	let cli = Cli::parse();
+	// clap checks `conflicts_with` against a global flag only when it follows the subcommand.
+	if let Command::Wallet { command: WalletCommand::Balance(args) } = &cli.command {
+		if args.sats && cli.unit.is_some() {
+			Cli::command()
+				.error(
+					clap::error::ErrorKind::ArgumentConflict,
+					"the argument '--sats' cannot be used with '--unit <UNIT>'",
+				)
+				.exit();
+		}
+	}
 
 	let cfg_path = config::default_config_path().unwrap_or_else(|e| die(e));
 	let mut cfg = config::load_config(&cfg_path).unwrap_or_else(|e| die(e));
@@ -169,20 +383,35 @@ async fn main() {
 		ColorOpt::Always => ui::ColorMode::Always,
 		ColorOpt::Never => ui::ColorMode::Never,
 	});
+	let mut api = RgbLdkClient::new(base);
+	if let Some(dir) = cli.record {
+		api = api.record_to(dir).unwrap_or_else(|e| die(e.to_string()));
+	} else if let Some(dir) = cli.replay {
+		api = api.replay_from(dir).unwrap_or_else(|e| die(e.to_string()));
+	}
 	let app = App {
-		base,
+		api,
 		output,
 		pretty: cli.pretty,
 		theme,
 		yes: cli.yes,
 		no_truncate: cli.no_truncate,
-		client: reqwest::Client::new(),
+		unit: cli.unit,
 	};
 
+	let operations = cli.command.operations();
+	if !operations.is_empty() {
+		compat::preflight(&app.api, &operations, cli.strict).await;
+	}
+
 	match cli.command {
+		Command::Api { command } => match command {
+			ApiCommand::Openapi => print!("{}", rgbldk_api::openapi::to_json_pretty()),
+		},
+
 		Command::Ctx { command } => match command {
 			CtxCommand::Ls => match app.output {
-				ui::OutputMode::Json => print_json(&cfg, app.pretty),
+				ui::OutputMode::Json => print_json(&cfg.redacted(), app.pretty),
 				ui::OutputMode::Text => {
 					let rows = cfg
 						.contexts
@@ -206,7 +435,11 @@ async fn main() {
 				if let Some(cur) = cfg.current.as_ref() {
 					if let Some(ctx) = cfg.contexts.get(cur) {
 						match app.output {
-							ui::OutputMode::Json => print_json(&ctx, app.pretty),
+							ui::OutputMode::Json => {
+								let mut ctx = ctx.clone();
+								ctx.redact();
+								print_json(&ctx, app.pretty)
+							},
 							ui::OutputMode::Text => {
 								println!("{cur} -> {}", ctx.url);
 							},
@@ -220,7 +453,11 @@ async fn main() {
 			},
 			CtxCommand::Add(args) => {
 				let name = args.name.clone();
-				cfg.contexts.insert(args.name.clone(), config::CtxEntry { url: args.url });
+				let webhook_secret = args.webhook_secret.or_else(|| {
+					cfg.contexts.get(&args.name).and_then(|c| c.webhook_secret.clone())
+				});
+				cfg.contexts
+					.insert(args.name.clone(), config::CtxEntry { url: args.url, webhook_secret });
 				if args.use_now || cfg.current.is_none() {
 					cfg.current = Some(args.name);
 				}
@@ -234,7 +471,7 @@ async fn main() {
 					}
 					println!("Next: run `rgbldk node status` to verify the connection.");
 				} else {
-					print_json(&cfg, app.pretty);
+					print_json(&cfg.redacted(), app.pretty);
 				}
 			},
 			CtxCommand::Use { name } => {
@@ -248,7 +485,7 @@ async fn main() {
 					println!("Switched to context \"{cur}\".");
 					println!("Next: run `rgbldk node status` to verify the connection.");
 				} else {
-					print_json(&cfg, app.pretty);
+					print_json(&cfg.redacted(), app.pretty);
 				}
 			},
 			CtxCommand::Rm { name } => {
@@ -260,35 +497,36 @@ async fn main() {
 				if app.output == ui::OutputMode::Text {
 					println!("Context \"{name}\" removed.");
 				} else {
-					print_json(&cfg, app.pretty);
+					print_json(&cfg.redacted(), app.pretty);
 				}
 			},
 		},
 
 		Command::Node { command } => match command {
 			NodeCommand::Version => {
-				let url = join_url(&app.base, "/api/v1/version");
-				let v: serde_json::Value =
-					send_json(app.client.get(url)).await.unwrap_or_else(|e| die(e));
+				let v = app.api.version().await.unwrap_or_else(|e| die_api(e));
+				compat::store(&app.api, &v);
 				match app.output {
 					ui::OutputMode::Json => print_json(&v, app.pretty),
 					ui::OutputMode::Text => {
-						let mut rows = Vec::new();
-						if let Some(obj) = v.as_object() {
-							for (k, vv) in obj {
-								rows.push(vec![
-									k.clone(),
-									vv.as_str().unwrap_or(&vv.to_string()).to_string(),
-								]);
-							}
-						}
+						let capabilities = if v.capabilities.is_empty() {
+							"(not reported)".to_string()
+						} else {
+							format!("{} routes", v.capabilities.len())
+						};
+						let rows = vec![
+							vec!["compatibility".into(), rgbldk_api::compat::check(&v).to_string()],
+							vec!["api_version".into(), v.api_version],
+							vec!["api_crate_version".into(), v.api_crate_version],
+							vec!["core_crate_version".into(), v.core_crate_version],
+							vec!["capabilities".into(), capabilities],
+						];
 						ui::print_table(app.theme, &["Field", "Value"], rows);
 					},
 				}
 			},
 			NodeCommand::Health => {
-				let url = join_url(&app.base, "/api/v1/healthz");
-				let v: OkResponse = send_json(app.client.get(url)).await.unwrap_or_else(|e| die(e));
+				let v = app.api.healthz().await.unwrap_or_else(|e| die_api(e));
 				match app.output {
 					ui::OutputMode::Json => print_json(&v, app.pretty),
 					ui::OutputMode::Text => {
@@ -297,13 +535,7 @@ async fn main() {
 				}
 			},
 			NodeCommand::Ready => {
-				let url = join_url(&app.base, "/api/v1/readyz");
-				let v: OkResponse = send_json_allow_status(
-					app.client.get(url),
-					&[reqwest::StatusCode::SERVICE_UNAVAILABLE],
-				)
-				.await
-				.unwrap_or_else(|e| die(e));
+				let v = app.api.readyz().await.unwrap_or_else(|e| die_api(e));
 				match app.output {
 					ui::OutputMode::Json => print_json(&v, app.pretty),
 					ui::OutputMode::Text => {
@@ -315,8 +547,7 @@ async fn main() {
 				}
 			},
 			NodeCommand::Status => {
-				let url = join_url(&app.base, "/api/v1/status");
-				let s: StatusDto = send_json(app.client.get(url)).await.unwrap_or_else(|e| die(e));
+				let s = app.api.status().await.unwrap_or_else(|e| die_api(e));
 				match app.output {
 					ui::OutputMode::Json => print_json(&s, app.pretty),
 					ui::OutputMode::Text => {
@@ -330,32 +561,19 @@ async fn main() {
 				}
 			},
 			NodeCommand::Id => {
-				let url = join_url(&app.base, "/api/v1/node_id");
-				let v: serde_json::Value =
-					send_json(app.client.get(url)).await.unwrap_or_else(|e| die(e));
+				let v = app.api.node_id().await.unwrap_or_else(|e| die_api(e));
 				match app.output {
 					ui::OutputMode::Json => print_json(&v, app.pretty),
-					ui::OutputMode::Text => {
-						println!("{}", v.get("node_id").and_then(|v| v.as_str()).unwrap_or(""))
-					},
+					ui::OutputMode::Text => println!("{}", v.node_id),
 				}
 			},
 			NodeCommand::Listen => {
-				let url = join_url(&app.base, "/api/v1/listening_addresses");
-				let v: serde_json::Value =
-					send_json(app.client.get(url)).await.unwrap_or_else(|e| die(e));
+				let v = app.api.listening_addresses().await.unwrap_or_else(|e| die_api(e));
 				match app.output {
 					ui::OutputMode::Json => print_json(&v, app.pretty),
 					ui::OutputMode::Text => {
-						let addrs = v
-							.get("addresses")
-							.and_then(|v| v.as_array())
-							.cloned()
-							.unwrap_or_default();
-						for a in addrs {
-							if let Some(s) = a.as_str() {
-								println!("{s}");
-							}
+						for a in &v.addresses {
+							println!("{a}");
 						}
 					},
 				}
@@ -364,9 +582,7 @@ async fn main() {
 
 		Command::Wallet { command } => match command {
 			WalletCommand::Balance(args) => {
-				let url = join_url(&app.base, "/api/v1/balances");
-				let b: BalancesDto =
-					send_json(app.client.get(url)).await.unwrap_or_else(|e| die(e));
+				let b = app.api.balances().await.unwrap_or_else(|e| die_api(e));
 				match app.output {
 					ui::OutputMode::Json => print_json(&b, app.pretty),
 					ui::OutputMode::Text => {
@@ -374,19 +590,27 @@ async fn main() {
 						let rows = vec![
 							vec![
 								"On-chain (total)".into(),
-								format_balance_sats(b.total_onchain_balance_sats, sats),
+								format_balance_sats(b.total_onchain_balance_sats, sats, app.unit),
 							],
 							vec![
 								"On-chain (spendable)".into(),
-								format_balance_sats(b.spendable_onchain_balance_sats, sats),
+								format_balance_sats(
+									b.spendable_onchain_balance_sats,
+									sats,
+									app.unit,
+								),
 							],
 							vec![
 								"Anchor reserve".into(),
-								format_balance_sats(b.total_anchor_channels_reserve_sats, sats),
+								format_balance_sats(
+									b.total_anchor_channels_reserve_sats,
+									sats,
+									app.unit,
+								),
 							],
 							vec![
 								"Lightning (total)".into(),
-								format_balance_sats(b.total_lightning_balance_sats, sats),
+								format_balance_sats(b.total_lightning_balance_sats, sats, app.unit),
 							],
 						];
 						ui::print_table_with_right_align(
@@ -399,42 +623,20 @@ async fn main() {
 				}
 			},
 			WalletCommand::Address => {
-				let url = join_url(&app.base, "/api/v1/wallet/new_address");
-				let v: serde_json::Value =
-					send_json(app.client.post(url).json(&serde_json::json!({})))
-						.await
-						.unwrap_or_else(|e| die(e));
+				let v = app.api.wallet_new_address().await.unwrap_or_else(|e| die_api(e));
 				match app.output {
 					ui::OutputMode::Json => print_json(&v, app.pretty),
-					ui::OutputMode::Text => {
-						println!("{}", v.get("address").and_then(|v| v.as_str()).unwrap_or(""))
-					},
+					ui::OutputMode::Text => println!("{}", v.address),
 				}
 			},
 			WalletCommand::Sync => {
 				if app.output == ui::OutputMode::Json {
-					let url = join_url(&app.base, "/api/v1/wallet/sync");
-					let v: serde_json::Value =
-						send_json(app.client.post(url).json(&serde_json::json!({})))
-							.await
-							.unwrap_or_else(|e| die(e));
+					let v = app.api.wallet_sync().await.unwrap_or_else(|e| die_api(e));
 					print_json(&v, app.pretty);
 				} else {
-					let b0: BalancesDto =
-						send_json(app.client.get(join_url(&app.base, "/api/v1/balances")))
-							.await
-							.unwrap_or_else(|e| die(e));
-					let _v: serde_json::Value = send_json(
-						app.client
-							.post(join_url(&app.base, "/api/v1/wallet/sync"))
-							.json(&serde_json::json!({})),
-					)
-					.await
-					.unwrap_or_else(|e| die(e));
-					let b1: BalancesDto =
-						send_json(app.client.get(join_url(&app.base, "/api/v1/balances")))
-							.await
-							.unwrap_or_else(|e| die(e));
+					let b0 = app.api.balances().await.unwrap_or_else(|e| die_api(e));
+					app.api.wallet_sync().await.unwrap_or_else(|e| die_api(e));
+					let b1 = app.api.balances().await.unwrap_or_else(|e| die_api(e));
 
 					let dt = b1.total_onchain_balance_sats as i128
 						- b0.total_onchain_balance_sats as i128;
@@ -448,12 +650,12 @@ async fn main() {
 						println!("No balance change.");
 					} else {
 						let fmt_delta = |d: i128| -> String {
-							if d == 0 {
-								"0 sats".to_string()
-							} else if d > 0 {
-								format!("+{}", format_balance_sats(d as u64, false))
-							} else {
-								format!("-{}", format_balance_sats((-d) as u64, false))
+							let amount =
+								format_balance_sats(d.unsigned_abs() as u64, false, app.unit);
+							match d.signum() {
+								1 => format!("+{amount}"),
+								-1 => format!("-{amount}"),
+								_ => amount,
 							}
 						};
 						println!(
@@ -469,9 +671,7 @@ async fn main() {
 
 		Command::Peer { command } => match command {
 			PeerCommand::Ls => {
-				let url = join_url(&app.base, "/api/v1/peers");
-				let peers: Vec<PeerDetailsDto> =
-					send_json(app.client.get(url)).await.unwrap_or_else(|e| die(e));
+				let peers = app.api.peers().await.unwrap_or_else(|e| die_api(e));
 				match app.output {
 					ui::OutputMode::Json => print_json(&peers, app.pretty),
 					ui::OutputMode::Text => {
@@ -479,7 +679,7 @@ async fn main() {
 							.into_iter()
 							.map(|p| {
 								vec![
-									p.node_id,
+									p.node_id.to_string(),
 									p.address,
 									p.is_connected.to_string(),
 									p.is_persisted.to_string(),
@@ -506,9 +706,7 @@ async fn main() {
 					address: args.addr,
 					persist: args.persist,
 				};
-				let url = join_url(&app.base, "/api/v1/peers/connect");
-				let v: serde_json::Value =
-					send_json(app.client.post(url).json(&req)).await.unwrap_or_else(|e| die(e));
+				let v = app.api.peer_connect(&req).await.unwrap_or_else(|e| die_api(e));
 				match app.output {
 					ui::OutputMode::Json => print_json(&v, app.pretty),
 					ui::OutputMode::Text => println!("Peer connected."),
@@ -516,9 +714,7 @@ async fn main() {
 			},
 			PeerCommand::Disconnect { node_id } => {
 				let req = PeerDisconnectRequest { node_id };
-				let url = join_url(&app.base, "/api/v1/peers/disconnect");
-				let v: serde_json::Value =
-					send_json(app.client.post(url).json(&req)).await.unwrap_or_else(|e| die(e));
+				let v = app.api.peer_disconnect(&req).await.unwrap_or_else(|e| die_api(e));
 				match app.output {
 					ui::OutputMode::Json => print_json(&v, app.pretty),
 					ui::OutputMode::Text => println!("Peer disconnected."),
@@ -528,9 +724,7 @@ async fn main() {
 
 		Command::Channel { command } => match command {
 			ChannelCommand::Ls => {
-				let url = join_url(&app.base, "/api/v1/channels");
-				let chans: Vec<ChannelDetailsExtendedDto> =
-					send_json(app.client.get(url)).await.unwrap_or_else(|e| die(e));
+				let chans = app.api.channels().await.unwrap_or_else(|e| die_api(e));
 				match app.output {
 					ui::OutputMode::Json => print_json(&chans, app.pretty),
 					ui::OutputMode::Text => {
@@ -538,9 +732,15 @@ async fn main() {
 							.into_iter()
 							.map(|c| {
 								vec![
-									c.user_channel_id,
-									c.counterparty_node_id,
-									c.channel_value_sats.to_string(),
+									c.user_channel_id.to_string(),
+									c.counterparty_node_id.to_string(),
+									match app.unit {
+										Some(unit) => format_msat_value(
+											c.channel_value_sats.saturating_mul(1_000),
+											unit,
+										),
+										None => c.channel_value_sats.to_string(),
+									},
 									c.is_channel_ready.to_string(),
 									c.is_usable.to_string(),
 								]
@@ -558,7 +758,10 @@ async fn main() {
 							&[
 								"User Channel ID",
 								"Counterparty",
-								"Capacity (sats)",
+								&format!(
+									"Capacity ({})",
+									unit_label(app.unit.unwrap_or(UnitOpt::Sat))
+								),
 								"Ready",
 								"Usable",
 							],
@@ -571,16 +774,19 @@ async fn main() {
 				let req = OpenChannelRequest {
 					node_id: args.node_id,
 					address: args.addr,
-					channel_amount_sats: args.amount_sats,
-					push_to_counterparty_msat: args.push_msat,
+					channel_amount_sats: args.amount_sats.to_sat(),
+					push_to_counterparty_msat: args.push_msat.map(Msat::to_msat),
 					announce: if args.private { Some(false) } else { None },
 				};
-				let url = join_url(&app.base, "/api/v1/channel/open");
-				let resp: OpenChannelResponse =
-					send_json(app.client.post(url).json(&req)).await.unwrap_or_else(|e| die(e));
-				match app.output {
-					ui::OutputMode::Json => print_json(&resp, app.pretty),
-					ui::OutputMode::Text => println!("{}", resp.user_channel_id),
+				let resp = app.api.channel_open(&req).await.unwrap_or_else(|e| die_api(e));
+				if args.wait_ready {
+					let timeout = args.timeout_secs.map(Duration::from_secs);
+					wait_channel_ready(&app, resp.user_channel_id, timeout).await;
+				} else {
+					match app.output {
+						ui::OutputMode::Json => print_json(&resp, app.pretty),
+						ui::OutputMode::Text => println!("{}", resp.user_channel_id),
+					}
 				}
 			},
 			ChannelCommand::Close(args) => {
@@ -588,9 +794,7 @@ async fn main() {
 					user_channel_id: args.user_channel_id,
 					counterparty_node_id: args.counterparty_node_id,
 				};
-				let url = join_url(&app.base, "/api/v1/channel/close");
-				let v: serde_json::Value =
-					send_json(app.client.post(url).json(&req)).await.unwrap_or_else(|e| die(e));
+				let v = app.api.channel_close(&req).await.unwrap_or_else(|e| die_api(e));
 				match app.output {
 					ui::OutputMode::Json => print_json(&v, app.pretty),
 					ui::OutputMode::Text => println!("Channel close initiated."),
@@ -605,9 +809,7 @@ async fn main() {
 					user_channel_id: args.user_channel_id,
 					counterparty_node_id: args.counterparty_node_id,
 				};
-				let url = join_url(&app.base, "/api/v1/channel/force_close");
-				let v: serde_json::Value =
-					send_json(app.client.post(url).json(&req)).await.unwrap_or_else(|e| die(e));
+				let v = app.api.channel_force_close(&req).await.unwrap_or_else(|e| die_api(e));
 				match app.output {
 					ui::OutputMode::Json => print_json(&v, app.pretty),
 					ui::OutputMode::Text => println!("Channel force-close initiated."),
@@ -616,94 +818,126 @@ async fn main() {
 		},
 
 		Command::Pay { command } => match command {
-			PayCommand::Ls => {
-				let url = join_url(&app.base, "/api/v1/payments");
-				let ps: Vec<PaymentDetailsDto> =
-					send_json(app.client.get(url)).await.unwrap_or_else(|e| die(e));
+			PayCommand::Ls(args) => {
+				let req = PaymentsListRequest {
+					limit: args.limit,
+					cursor: args.cursor,
+					status: args.status,
+					direction: args.direction,
+					kind: args.kind,
+					created_after: args.since.map(|s| s.0),
+					created_before: None,
+				};
+				// Without --limit or --cursor, list everything oldest first, as a bare array.
+				let paged = req.limit.is_some() || req.cursor.is_some();
+				let page = if paged && args.all {
+					app.api.payments_list_all(&req).await.unwrap_or_else(|e| die_api(e))
+				} else if paged {
+					app.api.payments_list(&req).await.unwrap_or_else(|e| die_api(e))
+				} else {
+					let ps = app.api.payments().await.unwrap_or_else(|e| die_api(e));
+					PaymentsPage {
+						items: ps.into_iter().filter(|p| req.matches(p)).collect(),
+						next_cursor: None,
+					}
+				};
 				match app.output {
-					ui::OutputMode::Json => print_json(&ps, app.pretty),
+					ui::OutputMode::Json if paged => print_json(&page, app.pretty),
+					ui::OutputMode::Json => print_json(&page.items, app.pretty),
 					ui::OutputMode::Text => {
-						let rows = ps
+						let unit = app.unit.unwrap_or(UnitOpt::Msat);
+						let rows = page
+							.items
 							.into_iter()
 							.map(|p| {
-								let id =
-									if app.no_truncate { p.id.clone() } else { truncate_id(&p.id) };
+								let id = if app.no_truncate {
+									p.id.to_string()
+								} else {
+									truncate_id(p.id.as_str())
+								};
 								vec![
 									id,
-									p.status,
-									p.kind,
-									p.direction,
+									format_payment_status(app.theme, &p.status),
+									p.kind.to_string(),
+									p.direction.to_string(),
 									p.amount_msat
-										.map(|v| format!("{}", format_u64_with_commas(v)))
+										.map(|v| format_msat_value(v, unit))
 										.unwrap_or_else(|| "-".into()),
 									p.fee_paid_msat
-										.map(|v| format!("{}", format_u64_with_commas(v)))
+										.map(|v| format_msat_value(v, unit))
 										.unwrap_or_else(|| "-".into()),
 								]
 							})
 							.collect::<Vec<_>>();
 						ui::print_table(
 							app.theme,
-							&["ID", "Status", "Kind", "Dir", "Amount (msat)", "Fee (msat)"],
+							&[
+								"ID",
+								"Status",
+								"Kind",
+								"Dir",
+								&format!("Amount ({})", unit_label(unit)),
+								&format!("Fee ({})", unit_label(unit)),
+							],
 							rows,
 						);
+						if let Some(cursor) = page.next_cursor {
+							eprintln!("More payments: rerun with --cursor {cursor}, or use --all.");
+						}
 					},
 				}
 			},
 			PayCommand::Wait(args) => {
-				use reqwest::StatusCode as HttpStatus;
-
-				let url = join_url(&app.base, &format!("/api/v1/payment/{}/wait", args.payment_id));
 				let req = PaymentWaitRequest { timeout_secs: args.timeout_secs };
-				let (status, v) =
-					send_value(app.client.post(url).json(&req)).await.unwrap_or_else(|e| die(e));
-
-				match (status, app.output) {
-					(HttpStatus::OK, ui::OutputMode::Json) => {
-						let resp: PaymentWaitResponse =
-							serde_json::from_value(v).unwrap_or_else(|e| die(e.to_string()));
-						print_json(&resp, app.pretty);
-					},
-					(HttpStatus::OK, ui::OutputMode::Text) => {
-						let resp: PaymentWaitResponse =
-							serde_json::from_value(v).unwrap_or_else(|e| die(e.to_string()));
-						ui::print_checks(app.theme, "Payment wait", resp.ok, &resp.checks);
-						println!("{}", resp.payment.id);
-					},
-					(_, ui::OutputMode::Json) => {
-						print_json(&v, app.pretty);
+				match app.api.payment_wait(&args.payment_id, &req).await {
+					Ok(resp) => match app.output {
+						ui::OutputMode::Json => print_json(&resp, app.pretty),
+						ui::OutputMode::Text => {
+							ui::print_checks(app.theme, "Payment wait", resp.ok, &resp.checks);
+							println!("{}", resp.payment.id);
+						},
 					},
-					(_, ui::OutputMode::Text) => {
-						let err =
-							v.get("error").and_then(|e| e.as_str()).unwrap_or("request failed");
-						if app.theme.color {
-							eprintln!("{}", format!("{err}").red());
-						} else {
-							eprintln!("{err}");
-						}
-						if let Some(checks) = v.get("checks") {
-							if let Ok(checks) = serde_json::from_value::<
-								Vec<rgbldk_api::http::HealthCheckDto>,
-							>(checks.clone())
-							{
-								ui::print_checks(app.theme, "Details", false, &checks);
-							}
-						}
-						if let Some(payment) = v.get("payment") {
-							if let Ok(p) =
-								serde_json::from_value::<PaymentDetailsDto>(payment.clone())
-							{
-								println!("{}", p.id);
-							}
+					Err(e @ rgbldk_api::ApiError::Http { .. }) => {
+						let v = e
+							.body_json()
+							.unwrap_or_else(|| serde_json::json!({ "error": e.to_string() }));
+						match app.output {
+							ui::OutputMode::Json => print_json(&v, app.pretty),
+							ui::OutputMode::Text => {
+								let err = v
+									.get("error")
+									.and_then(|e| e.as_str())
+									.unwrap_or("request failed");
+								if app.theme.color {
+									eprintln!("{}", err.red());
+								} else {
+									eprintln!("{err}");
+								}
+								if let Some(checks) = v.get("checks") {
+									if let Ok(checks) = serde_json::from_value::<
+										Vec<rgbldk_api::http::HealthCheckDto>,
+									>(checks.clone())
+									{
+										ui::print_checks(app.theme, "Details", false, &checks);
+									}
+								}
+								if let Some(payment) = v.get("payment") {
+									if let Ok(p) =
+										serde_json::from_value::<PaymentDetailsDto>(payment.clone())
+									{
+										println!("{}", p.id);
+									}
+								}
+							},
 						}
+						std::process::exit(api_exit_code(&e));
 					},
+					Err(e) => die_api(e),
 				}
 			},
 			PayCommand::Abandon { payment_id } => {
-				let url = join_url(&app.base, &format!("/api/v1/payment/{payment_id}/abandon"));
-				let resp: OkResponse = send_json(app.client.post(url).json(&serde_json::json!({})))
-					.await
-					.unwrap_or_else(|e| die(e));
+				let resp =
+					app.api.payment_abandon(&payment_id).await.unwrap_or_else(|e| die_api(e));
 				match app.output {
 					ui::OutputMode::Json => print_json(&resp, app.pretty),
 					ui::OutputMode::Text => {
@@ -712,99 +946,87 @@ async fn main() {
 				}
 			},
 			PayCommand::Get { payment_id } => {
-				let url = join_url(&app.base, &format!("/api/v1/payment/{}", payment_id));
-				let p: PaymentDetailsDto =
-					send_json(app.client.get(url)).await.unwrap_or_else(|e| die(e));
+				let p = app.api.payment(&payment_id).await.unwrap_or_else(|e| die_api(e));
 				match app.output {
 					ui::OutputMode::Json => print_json(&p, app.pretty),
 					ui::OutputMode::Text => {
-						let status_value = match p.status.as_str() {
-							"Succeeded" => {
-								if app.theme.color {
-									format!("{}", "✔ Succeeded".green())
-								} else {
-									"✔ Succeeded".to_string()
-								}
-							},
-							"Pending" => {
-								if app.theme.color {
-									format!("{}", "… Pending".yellow())
-								} else {
-									"… Pending".to_string()
-								}
-							},
-							"Failed" => {
-								if app.theme.color {
-									format!("{}", "✘ Failed".red())
-								} else {
-									"✘ Failed".to_string()
-								}
-							},
-							_ => p.status.clone(),
-						};
+						let status_value = format_payment_status(app.theme, &p.status);
+						let unit = app.unit.unwrap_or(UnitOpt::Msat);
 						let rows = vec![
-							vec!["id".into(), p.id],
-							vec!["direction".into(), p.direction],
+							vec!["id".into(), p.id.to_string()],
+							vec!["direction".into(), p.direction.to_string()],
 							vec!["status".into(), status_value],
-							vec!["kind".into(), p.kind],
-							vec![
-								"kind_details".into(),
-								p.kind_details
-									.as_ref()
-									.map(|v| v.to_string())
-									.unwrap_or_else(|| "-".into()),
-							],
+							vec!["kind".into(), p.kind.to_string()],
 							vec![
-								"amount (msat)".into(),
+								format!("amount ({})", unit_label(unit)),
 								p.amount_msat
-									.map(|v| format!("{} msat", format_u64_with_commas(v)))
+									.map(|v| format_msat(v, unit))
 									.unwrap_or_else(|| "-".into()),
 							],
 							vec![
-								"fee paid (msat)".into(),
+								format!("fee paid ({})", unit_label(unit)),
 								p.fee_paid_msat
-									.map(|v| format!("{} msat", format_u64_with_commas(v)))
+									.map(|v| format_msat(v, unit))
 									.unwrap_or_else(|| "-".into()),
 							],
 						];
 						ui::print_table(app.theme, &["Field", "Value"], rows);
+						if let Some(details) = &p.kind_details {
+							println!();
+							println!("Kind details:");
+							ui::print_table(
+								app.theme,
+								&["Field", "Value"],
+								kind_details_rows(details),
+							);
+						}
 					},
 				}
 			},
 			PayCommand::Invoice { command } => match command {
 				InvoiceCommand::Create(args) => {
-					let url;
-					let body;
-					if let Some(amount_msat) = args.amount_msat {
-						url = join_url(&app.base, "/api/v1/bolt11/receive");
-						body = serde_json::to_value(Bolt11ReceiveRequest {
-							amount_msat,
-							description: args.desc,
-							expiry_secs: args.expiry_secs,
-						})
-						.unwrap();
+					let resp = if let Some(amount_msat) = args.amount_msat {
+						app.api
+							.bolt11_receive(&Bolt11ReceiveRequest {
+								amount_msat: amount_msat.to_msat(),
+								description: args.desc,
+								expiry_secs: args.expiry_secs,
+							})
+							.await
 					} else {
-						url = join_url(&app.base, "/api/v1/bolt11/receive_var");
-						body = serde_json::to_value(Bolt11ReceiveVarRequest {
-							description: args.desc,
-							expiry_secs: args.expiry_secs,
-						})
-						.unwrap();
+						app.api
+							.bolt11_receive_var(&Bolt11ReceiveVarRequest {
+								description: args.desc,
+								expiry_secs: args.expiry_secs,
+							})
+							.await
 					}
-					let resp: Bolt11ReceiveResponse = send_json(app.client.post(url).json(&body))
-						.await
-						.unwrap_or_else(|e| die(e));
+					.unwrap_or_else(|e| die_api(e));
 					match app.output {
 						ui::OutputMode::Json => print_json(&resp, app.pretty),
 						ui::OutputMode::Text => println!("{}", resp.invoice),
 					}
+					if args.wait.wait {
+						let req = Bolt11DecodeRequest { invoice: resp.invoice };
+						let decoded =
+							app.api.bolt11_decode(&req).await.unwrap_or_else(|e| die_api(e));
+						let payment_hash = decoded
+							.payment_hash
+							.parse()
+							.unwrap_or_else(|e| die(format!("payment hash: {e}")));
+						let receivable = wait::Receivable::Invoice {
+							payment_hash,
+							expiry_secs: decoded.expiry_secs,
+						};
+						wait_paid(&app, &receivable, args.wait.timeout_secs).await;
+					}
 				},
 				InvoiceCommand::Pay(args) => {
-					let req =
-						Bolt11PayRequest { invoice: args.invoice, amount_msat: args.amount_msat };
-					let url = join_url(&app.base, "/api/v1/bolt11/pay");
-					let resp: Bolt11PayResponse =
-						send_json(app.client.post(url).json(&req)).await.unwrap_or_else(|e| die(e));
+					let req = Bolt11PayRequest {
+						invoice: args.invoice,
+						amount_msat: args.amount_msat.map(Msat::to_msat),
+					};
+					let resp = app.api.bolt11_pay(&req).await.unwrap_or_else(|e| die_api(e));
 					match app.output {
 						ui::OutputMode::Json => print_json(&resp, app.pretty),
 						ui::OutputMode::Text => println!("{}", resp.payment_id),
@@ -814,40 +1036,43 @@ async fn main() {
 			PayCommand::Offer { command } => match command {
 				OfferCommand::Create(args) => {
 					let expiry_secs = if args.no_expiry { None } else { Some(args.expiry_secs) };
-					let (url, body) = if let Some(amount_msat) = args.amount_msat {
-						(
-							join_url(&app.base, "/api/v1/bolt12/offer/receive"),
-							serde_json::to_value(Bolt12OfferReceiveRequest {
-								amount_msat,
+					let resp = if let Some(amount_msat) = args.amount_msat {
+						app.api
+							.bolt12_offer_receive(&Bolt12OfferReceiveRequest {
+								amount_msat: amount_msat.to_msat(),
 								description: args.desc,
 								expiry_secs,
 								quantity: args.quantity,
 							})
-							.unwrap(),
-						)
+							.await
 					} else {
-						(
-							join_url(&app.base, "/api/v1/bolt12/offer/receive_var"),
-							serde_json::to_value(Bolt12OfferReceiveVarRequest {
+						app.api
+							.bolt12_offer_receive_var(&Bolt12OfferReceiveVarRequest {
 								description: args.desc,
 								expiry_secs,
 							})
-							.unwrap(),
-						)
-					};
-					let resp: Bolt12OfferResponse = send_json(app.client.post(url).json(&body))
-						.await
-						.unwrap_or_else(|e| die(e));
+							.await
+					}
+					.unwrap_or_else(|e| die_api(e));
 					match app.output {
 						ui::OutputMode::Json => print_json(&resp, app.pretty),
 						ui::OutputMode::Text => println!("{}", resp.offer),
 					}
+					if args.wait.wait {
+						let req = Bolt12OfferDecodeRequest { offer: resp.offer };
+						let decoded =
+							app.api.bolt12_offer_decode(&req).await.unwrap_or_else(|e| die_api(e));
+						let receivable = wait::Receivable::Offer {
+							offer_id: decoded.offer_id,
+							absolute_expiry_unix_secs: decoded.absolute_expiry_unix_secs,
+						};
+						wait_paid(&app, &receivable, args.wait.timeout_secs).await;
+					}
 				},
 				OfferCommand::Decode { offer } => {
-					let url = join_url(&app.base, "/api/v1/bolt12/offer/decode");
 					let req = Bolt12OfferDecodeRequest { offer };
-					let resp: Bolt12OfferDecodeResponse =
-						send_json(app.client.post(url).json(&req)).await.unwrap_or_else(|e| die(e));
+					let resp =
+						app.api.bolt12_offer_decode(&req).await.unwrap_or_else(|e| die_api(e));
 					match app.output {
 						ui::OutputMode::Json => print_json(&resp, app.pretty),
 						ui::OutputMode::Text => {
@@ -865,7 +1090,7 @@ async fn main() {
 								vec![
 									"amount_msat".into(),
 									resp.amount_msat
-										.map(|v| format_u64_with_commas(v))
+										.map(format_u64_with_commas)
 										.unwrap_or_else(|| "-".into()),
 								],
 								vec![
@@ -883,15 +1108,13 @@ async fn main() {
 					}
 				},
 				OfferCommand::Pay(args) => {
-					let url = join_url(&app.base, "/api/v1/bolt12/offer/send");
 					let req = Bolt12OfferSendRequest {
 						offer: args.offer,
-						amount_msat: args.amount_msat,
+						amount_msat: args.amount_msat.map(Msat::to_msat),
 						quantity: args.quantity,
 						payer_note: args.payer_note,
 					};
-					let resp: SendResponse =
-						send_json(app.client.post(url).json(&req)).await.unwrap_or_else(|e| die(e));
+					let resp = app.api.bolt12_offer_send(&req).await.unwrap_or_else(|e| die_api(e));
 					match app.output {
 						ui::OutputMode::Json => print_json(&resp, app.pretty),
 						ui::OutputMode::Text => println!("{}", resp.payment_id),
@@ -900,15 +1123,14 @@ async fn main() {
 			},
 			PayCommand::Refund { command } => match command {
 				RefundCommand::Initiate(args) => {
-					let url = join_url(&app.base, "/api/v1/bolt12/refund/initiate");
 					let req = Bolt12RefundInitiateRequest {
-						amount_msat: args.amount_msat,
+						amount_msat: args.amount_msat.to_msat(),
 						expiry_secs: args.expiry_secs,
 						quantity: args.quantity,
 						payer_note: args.payer_note,
 					};
-					let resp: Bolt12RefundInitiateResponse =
-						send_json(app.client.post(url).json(&req)).await.unwrap_or_else(|e| die(e));
+					let resp =
+						app.api.bolt12_refund_initiate(&req).await.unwrap_or_else(|e| die_api(e));
 					match app.output {
 						ui::OutputMode::Json => print_json(&resp, app.pretty),
 						ui::OutputMode::Text => {
@@ -918,10 +1140,9 @@ async fn main() {
 					}
 				},
 				RefundCommand::Decode { refund } => {
-					let url = join_url(&app.base, "/api/v1/bolt12/refund/decode");
 					let req = Bolt12RefundDecodeRequest { refund };
-					let resp: Bolt12RefundDecodeResponse =
-						send_json(app.client.post(url).json(&req)).await.unwrap_or_else(|e| die(e));
+					let resp =
+						app.api.bolt12_refund_decode(&req).await.unwrap_or_else(|e| die_api(e));
 					match app.output {
 						ui::OutputMode::Json => print_json(&resp, app.pretty),
 						ui::OutputMode::Text => {
@@ -957,10 +1178,12 @@ async fn main() {
 					}
 				},
 				RefundCommand::RequestPayment { refund } => {
-					let url = join_url(&app.base, "/api/v1/bolt12/refund/request_payment");
 					let req = Bolt12RefundRequestPaymentRequest { refund };
-					let resp: Bolt12RefundRequestPaymentResponse =
-						send_json(app.client.post(url).json(&req)).await.unwrap_or_else(|e| die(e));
+					let resp = app
+						.api
+						.bolt12_refund_request_payment(&req)
+						.await
+						.unwrap_or_else(|e| die_api(e));
 					match app.output {
 						ui::OutputMode::Json => print_json(&resp, app.pretty),
 						ui::OutputMode::Text => {
@@ -979,12 +1202,10 @@ async fn main() {
 						.collect();
 					let req = SpontaneousSendRequest {
 						counterparty_node_id: args.node_id,
-						amount_msat: args.amount_msat,
+						amount_msat: args.amount_msat.to_msat(),
 						custom_tlvs: tlvs,
 					};
-					let url = join_url(&app.base, "/api/v1/spontaneous/send");
-					let resp: SendResponse =
-						send_json(app.client.post(url).json(&req)).await.unwrap_or_else(|e| die(e));
+					let resp = app.api.spontaneous_send(&req).await.unwrap_or_else(|e| die_api(e));
 					match app.output {
 						ui::OutputMode::Json => print_json(&resp, app.pretty),
 						ui::OutputMode::Text => println!("{}", resp.payment_id),
@@ -994,61 +1215,195 @@ async fn main() {
 		},
 
 		Command::Events { command } => match command {
-			EventsCommand::Next => {
-				let url = join_url(&app.base, "/api/v1/events/wait_next");
-				let ev: EventDto = with_spinner(
-					app.output == ui::OutputMode::Text,
-					"Waiting for event...",
-					send_json(app.client.post(url).json(&serde_json::json!({}))),
-				)
-				.await
-				.unwrap_or_else(|e| die(e));
-				match app.output {
-					ui::OutputMode::Json => print_json(&ev, app.pretty),
-					ui::OutputMode::Text => print_event_text(&ev),
+			EventsCommand::Next(args) => {
+				let filter = events::EventFilter::new(&args.filter);
+				let ev = loop {
+					let ev = with_spinner(
+						app.output == ui::OutputMode::Text,
+						"Waiting for event...",
+						app.api.events_wait_next(),
+					)
+					.await
+					.unwrap_or_else(|e| die_api(e));
+					if filter.matches(&ev.event) {
+						break ev;
+					}
+					match args.filter.skip {
+						Some(SkipOpt::Ack) => {
+							let ack = EventAckRequest { id: ev.id };
+							app.api.events_handled(&ack).await.unwrap_or_else(|e| die_api(e));
+						},
+						_ => leave_queued(&ev.event),
+					}
+				};
+				match (app.output, ev.id) {
+					(ui::OutputMode::Json, _) => print_json(&ev, app.pretty),
+					(ui::OutputMode::Text, Some(id)) => {
+						println!("{} id={id}", event_text(&ev.event))
+					},
+					(ui::OutputMode::Text, None) => print_event_text(&ev.event),
 				}
 			},
-			EventsCommand::Handled => {
-				let url = join_url(&app.base, "/api/v1/events/handled");
-				let v: serde_json::Value =
-					send_json(app.client.post(url).json(&serde_json::json!({})))
-						.await
-						.unwrap_or_else(|e| die(e));
-				match app.output {
-					ui::OutputMode::Json => print_json(&v, app.pretty),
-					ui::OutputMode::Text => println!("Marked handled."),
+			EventsCommand::Handled(args) => {
+				let v = app
+					.api
+					.events_handled(&EventAckRequest { id: args.id })
+					.await
+					.unwrap_or_else(|e| die_api(e));
+				match (app.output, args.id) {
+					(ui::OutputMode::Json, _) => print_json(&v, app.pretty),
+					(ui::OutputMode::Text, Some(id)) => println!("Marked event {id} handled."),
+					(ui::OutputMode::Text, None) => println!("Marked handled."),
 				}
 			},
 			EventsCommand::Watch(args) => {
+				let hook = args.exec.map(|command| events::Hook {
+					command,
+					max_attempts: args.max_attempts,
+					retry_delay: Duration::from_millis(args.retry_delay_ms),
+					dead_letter: args.dead_letter,
+				});
+				let filter = events::EventFilter::new(&args.filter);
+				if args.hub.is_some() && matches!(args.journal.journal, Some(None)) {
+					// The journal dedupes by event id, and hub ids are not the daemon's.
+					Cli::command()
+						.error(
+							clap::error::ErrorKind::ArgumentConflict,
+							"--journal with --hub needs a FILE of its own: hub event ids are not the daemon's",
+						)
+						.exit();
+				}
+				let mut journal = open_journal(args.journal, app.api.base_url());
+				let spinner = app.output == ui::OutputMode::Text;
+				let mut events = match (args.hub, args.subscriber) {
+					#[cfg(unix)]
+					(Some(socket), Some(subscriber)) => events::Consumer::hub(socket, subscriber, spinner),
+					#[cfg(not(unix))]
+					(Some(_), _) => die("--hub needs Unix sockets"),
+					_ => events::Consumer::new(app.api.clone(), spinner),
+				};
 				let mut seen: u64 = 0;
-				loop {
-					if let Some(max) = args.count {
-						if seen >= max {
-							break;
-						}
+				while args.count.is_none_or(|max| seen < max) {
+					let received = events.next().await;
+					let matched = filter.matches(&received.event);
+					if !matched && !matches!(args.filter.skip, Some(SkipOpt::Ack)) {
+						leave_queued(&received.event);
 					}
-					let url = join_url(&app.base, "/api/v1/events/wait_next");
-					let ev: EventDto = with_spinner(
-						app.output == ui::OutputMode::Text,
-						"Waiting for events...",
-						send_json(app.client.post(url).json(&serde_json::json!({}))),
-					)
-					.await
-					.unwrap_or_else(|e| die(e));
-					let _ = send_json::<serde_json::Value>(
-						app.client
-							.post(join_url(&app.base, "/api/v1/events/handled"))
-							.json(&serde_json::json!({})),
-					)
-					.await;
-
+					// Only events about to be ACKed (handled or dropped) go into the journal.
+					if let Some(journal) = &mut journal {
+						journal.record(&received).unwrap_or_else(|e| die(e));
+					}
+					if !matched {
+						events.ack(&received).await;
+						continue;
+					}
+					match (&hook, app.output) {
+						(Some(hook), _) => events
+							.process(&received, hook.deliver(&received))
+							.await
+							.unwrap_or_else(|e| die(e)),
+						(None, ui::OutputMode::Json) => print_json(&received.event, app.pretty),
+						(None, ui::OutputMode::Text) => print_event_text(&received.event),
+					}
+					events.ack(&received).await;
+					seen += 1;
+				}
+			},
+			EventsCommand::Forward(args) => {
+				let secret = args
+					.secret
+					.or_else(|| cfg.active_context().and_then(|c| c.webhook_secret.clone()))
+					.unwrap_or_else(|| {
+						die("no webhook secret: pass --secret, set RGBLDK_WEBHOOK_SECRET, or store one with `rgbldk ctx add <name> --url <url> --webhook-secret <secret>`")
+					});
+				let http = reqwest::Client::builder()
+					.timeout(Duration::from_secs(args.timeout_secs))
+					.build()
+					.unwrap_or_else(|e| die(e.to_string()));
+				let forwarder = events::Forwarder {
+					url: args.url,
+					secret,
+					retry_delay: Duration::from_millis(args.retry_delay_ms),
+					http,
+				};
+				let mut journal = open_journal(args.journal, app.api.base_url());
+				let mut events =
+					events::Consumer::new(app.api.clone(), app.output == ui::OutputMode::Text);
+				let mut seen: u64 = 0;
+				while args.count.is_none_or(|max| seen < max) {
+					let received = events.next().await;
+					if let Some(journal) = &mut journal {
+						journal.record(&received).unwrap_or_else(|e| die(e));
+					}
+					let delivery = events.process(&received, forwarder.deliver(&received)).await;
+					events.ack(&received).await;
 					match app.output {
-						ui::OutputMode::Json => print_json(&ev, app.pretty),
-						ui::OutputMode::Text => print_event_text(&ev),
+						ui::OutputMode::Json => print_json(&delivery, app.pretty),
+						ui::OutputMode::Text => println!(
+							"Forwarded {} (delivery {}, HTTP {}, attempts: {})",
+							events::event_type(delivery.event),
+							delivery.delivery_id,
+							delivery.status,
+							delivery.attempts
+						),
 					}
 					seen += 1;
 				}
 			},
+			EventsCommand::Log(args) => {
+				let path = args.journal.unwrap_or_else(|| {
+					journal::default_path(app.api.base_url()).unwrap_or_else(|e| die(e))
+				});
+				let query = journal::Query {
+					types: args.types,
+					since: args.since.map(|t| t.0),
+					until: args.until.map(|t| t.0),
+					id: args.id,
+				};
+				let mut entries: Vec<_> = journal::read(&path)
+					.unwrap_or_else(|e| die(e))
+					.into_iter()
+					.filter(|e| query.matches(e))
+					.collect();
+				if let Some(limit) = args.limit {
+					entries.drain(..entries.len().saturating_sub(limit));
+				}
+				match app.output {
+					ui::OutputMode::Json => print_json(&entries, app.pretty),
+					ui::OutputMode::Text if entries.is_empty() => {
+						println!("No matching events in {}.", path.display())
+					},
+					ui::OutputMode::Text => {
+						for entry in &entries {
+							println!(
+								"{}  {}",
+								journal::format_utc(entry.recorded_at),
+								event_text(&entry.event)
+							);
+						}
+					},
+				}
+			},
+			#[cfg(unix)]
+			EventsCommand::Hub(args) => {
+				let dir = args.dir.unwrap_or_else(|| {
+					hub::default_dir(app.api.base_url()).unwrap_or_else(|e| die(e))
+				});
+				let text = app.output == ui::OutputMode::Text;
+				let events = events::Consumer::new(app.api.clone(), text);
+				hub::run(events, &args.socket, &dir, |entry| match app.output {
+					ui::OutputMode::Json => print_json(entry, app.pretty),
+					ui::OutputMode::Text => println!("{} {}", entry.id, event_text(&entry.event)),
+				})
+				.await
+			},
+			#[cfg(not(unix))]
+			EventsCommand::Hub(_) => die("events hub needs Unix sockets"),
+		},
+
+		Command::Exporter(args) => {
+			let interval = Duration::from_secs(args.interval_secs);
+			exporter::run(app.api, args.listen, interval).await.unwrap_or_else(|e| die(e));
 		},
 	}
 }
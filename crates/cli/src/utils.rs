@@ -2,6 +2,33 @@ use std::io::IsTerminal;
 use std::io::Write;
 use std::time::Duration;
 
+use rgbldk_api::cassette::CassetteError;
+use rgbldk_api::ApiError;
+
+/// Process exit codes (see `docs/cli/README.md`).
+pub mod exit_code {
+	/// Generic failure (bad config, aborted prompt, not-ok result).
+	pub const FAILURE: i32 = 1;
+	/// The daemon could not be reached or the connection broke.
+	pub const TRANSPORT: i32 = 3;
+	/// The daemon rejected the request (HTTP 4xx).
+	pub const HTTP_CLIENT: i32 = 4;
+	/// The daemon failed while handling the request (HTTP 5xx).
+	pub const HTTP_SERVER: i32 = 5;
+	/// The daemon answered with a body the CLI could not decode.
+	pub const DECODE: i32 = 6;
+	/// The daemon is too old for this CLI (`--strict`) or lacks the command's endpoint.
+	pub const INCOMPATIBLE: i32 = 7;
+	/// The next event did not match `--type`/`--match` and was left in the queue (`--skip stop`).
+	pub const NO_MATCH: i32 = 8;
+	/// A `--wait` or `--wait-*` option gave up after its timeout.
+	pub const TIMEOUT: i32 = 9;
+	/// The invoice or offer expired before `--wait` saw it paid.
+	pub const EXPIRED: i32 = 10;
+	/// Stopped by Ctrl-C or SIGTERM.
+	pub const INTERRUPTED: i32 = 130;
+}
+
 pub fn print_json<T: serde::Serialize>(value: &T, pretty: bool) {
 	if pretty {
 		println!("{}", serde_json::to_string_pretty(value).unwrap());
@@ -11,8 +38,27 @@ pub fn print_json<T: serde::Serialize>(value: &T, pretty: bool) {
 }
 
 pub fn die(msg: impl AsRef<str>) -> ! {
+	die_with(exit_code::FAILURE, msg)
+}
+
+pub fn die_with(code: i32, msg: impl AsRef<str>) -> ! {
 	eprintln!("{}", msg.as_ref());
-	std::process::exit(1);
+	std::process::exit(code);
+}
+
+pub fn api_exit_code(err: &ApiError) -> i32 {
+	match err {
+		ApiError::Transport(_) => exit_code::TRANSPORT,
+		ApiError::Cassette(CassetteError::Transport { .. }) => exit_code::TRANSPORT,
+		ApiError::Cassette(_) => exit_code::FAILURE,
+		ApiError::Http { status, .. } if status.is_server_error() => exit_code::HTTP_SERVER,
+		ApiError::Http { .. } => exit_code::HTTP_CLIENT,
+		ApiError::Decode { .. } => exit_code::DECODE,
+	}
+}
+
+pub fn die_api(err: ApiError) -> ! {
+	die_with(api_exit_code(&err), err.to_string())
 }
 
 pub fn confirm_or_exit(yes: bool, prompt: &str) {
@@ -30,6 +76,23 @@ pub fn confirm_or_exit(yes: bool, prompt: &str) {
 	}
 }
 
+/// Writes a status line to stderr; on a terminal it replaces the previous one.
+pub fn status(msg: &str) {
+	if std::io::stderr().is_terminal() {
+		eprint!("\r\x1b[2K{msg}");
+		let _ = std::io::stderr().flush();
+	} else {
+		eprintln!("{msg}");
+	}
+}
+
+/// Clears the status line (on a terminal).
+pub fn clear_status() {
+	if std::io::stderr().is_terminal() {
+		eprint!("\r\x1b[2K");
+	}
+}
+
 pub async fn with_spinner<T>(
 	enabled: bool, msg: &str, fut: impl std::future::Future<Output = T>,
 ) -> T {
@@ -0,0 +1,365 @@
+//! Waiting for the node to get somewhere: `channel open --wait-ready` and
+//! `pay invoice|offer create --wait`.
+//!
+//! The waiters read `/events/stream` without ACKing anything, so the consumer that owns the queue
+//! still gets every event, and poll the daemon as well: without a stream (older daemons,
+//! cassettes) polling alone decides, just later.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rgbldk_api::client::StatusCode;
+use rgbldk_api::http::{
+	ChannelDetailsExtendedDto, EventDto, PaymentDetailsDto, PaymentDirection, PaymentKind,
+	PaymentKindDetails, PaymentStatus, PaymentsListRequest,
+};
+use rgbldk_api::ids::{PaymentId, UserChannelId};
+use rgbldk_api::stream::EventStream;
+use rgbldk_api::{ApiError, RgbLdkClient};
+use serde::Serialize;
+use tokio::time::Instant;
+
+use crate::utils::{clear_status, status};
+
+/// How often the daemon is polled while waiting.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How far the daemon's clock may run behind ours when looking for payments made since a wait
+/// started.
+const CLOCK_SKEW: Duration = Duration::from_secs(300);
+
+/// Follows `/events/stream` without acknowledging anything.
+#[derive(Debug)]
+struct Tail {
+	stream: Option<EventStream>,
+}
+
+impl Tail {
+	async fn open(api: &RgbLdkClient) -> Self {
+		Self { stream: api.events_stream().await.ok() }
+	}
+
+	fn is_live(&self) -> bool {
+		self.stream.is_some()
+	}
+
+	/// The next event; never resolves once the stream is gone.
+	async fn next(&mut self) -> EventDto {
+		if let Some(stream) = &mut self.stream {
+			match stream.next().await {
+				Ok(Some(envelope)) => return envelope.event,
+				Ok(None) | Err(_) => self.stream = None,
+			}
+		}
+		std::future::pending().await
+	}
+}
+
+/// Resolves at `deadline`, or never.
+async fn until(deadline: Option<Instant>) {
+	match deadline {
+		Some(deadline) => tokio::time::sleep_until(deadline).await,
+		None => std::future::pending().await,
+	}
+}
+
+/// How `channel open --wait-ready` ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelOutcome {
+	Ready,
+	Closed,
+	Timeout,
+}
+
+/// What `channel open --wait-ready` prints.
+#[derive(Debug, Serialize)]
+pub struct ChannelWait {
+	pub user_channel_id: UserChannelId,
+	pub outcome: ChannelOutcome,
+	/// Funding outpoint (`txid:vout`), once known.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub funding_txo: Option<String>,
+	/// Closure reason, for [`ChannelOutcome::Closed`].
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub reason: Option<String>,
+	/// `best_block_height` when the wait started.
+	pub opened_at_height: u32,
+	/// `best_block_height` when it ended.
+	pub best_block_height: u32,
+}
+
+impl ChannelWait {
+	fn progress(&self) -> String {
+		let funding = self.funding_txo.as_deref().unwrap_or("not broadcast yet");
+		format!(
+			"Waiting for channel {}: funding {funding}, height {} (+{} blocks)",
+			self.user_channel_id,
+			self.best_block_height,
+			self.best_block_height.saturating_sub(self.opened_at_height)
+		)
+	}
+}
+
+async fn find_channel(
+	api: &RgbLdkClient, user_channel_id: &UserChannelId,
+) -> Result<Option<ChannelDetailsExtendedDto>, ApiError> {
+	Ok(api.channels().await?.into_iter().find(|c| &c.user_channel_id == user_channel_id))
+}
+
+/// Waits until the channel is ready, is closed, or `timeout` passes. Follows `ChannelPending`
+/// (matched to the channel by funding outpoint), `ChannelReady` and `ChannelClosed`, and shows
+/// progress on stderr when `show_progress` is set.
+pub async fn channel_ready(
+	api: &RgbLdkClient, user_channel_id: UserChannelId, timeout: Option<Duration>,
+	show_progress: bool,
+) -> Result<ChannelWait, ApiError> {
+	let deadline = timeout.map(|t| Instant::now() + t);
+	let height = api.status().await?.best_block_height;
+	let mut wait = ChannelWait {
+		user_channel_id,
+		outcome: ChannelOutcome::Timeout,
+		funding_txo: None,
+		reason: None,
+		opened_at_height: height,
+		best_block_height: height,
+	};
+	let mut tail = Tail::open(api).await;
+	let mut poll = tokio::time::interval(POLL_INTERVAL);
+	let mut missing = false;
+	let outcome = loop {
+		tokio::select! {
+			biased;
+			_ = until(deadline) => break ChannelOutcome::Timeout,
+			event = tail.next() => match event {
+				EventDto::ChannelPending { funding_txo } => {
+					if wait.funding_txo.is_none() {
+						let channel = find_channel(api, &wait.user_channel_id).await?;
+						wait.funding_txo = channel.and_then(|c| c.channel_point);
+					}
+					let outpoint = format!("{}:{}", funding_txo.txid, funding_txo.vout);
+					if show_progress && wait.funding_txo.as_deref() == Some(outpoint.as_str()) {
+						status(&wait.progress());
+					}
+				},
+				EventDto::ChannelReady { user_channel_id }
+					if user_channel_id == wait.user_channel_id =>
+				{
+					break ChannelOutcome::Ready;
+				},
+				EventDto::ChannelClosed { user_channel_id, reason, .. }
+					if user_channel_id == wait.user_channel_id =>
+				{
+					wait.reason = reason;
+					break ChannelOutcome::Closed;
+				},
+				_ => {},
+			},
+			_ = poll.tick() => {
+				wait.best_block_height = api.status().await?.best_block_height;
+				match find_channel(api, &wait.user_channel_id).await? {
+					Some(channel) if channel.is_channel_ready => break ChannelOutcome::Ready,
+					Some(channel) => {
+						wait.funding_txo = wait.funding_txo.take().or(channel.channel_point);
+					},
+					// Closed; the stream gets another poll to bring `ChannelClosed` and the reason.
+					None if tail.is_live() && !missing => missing = true,
+					None => break ChannelOutcome::Closed,
+				}
+				if show_progress {
+					status(&wait.progress());
+				}
+			},
+		}
+	};
+	if show_progress {
+		clear_status();
+	}
+	wait.outcome = outcome;
+	if outcome != ChannelOutcome::Timeout {
+		wait.best_block_height = api.status().await?.best_block_height;
+		if wait.funding_txo.is_none() {
+			let channel = find_channel(api, &wait.user_channel_id).await?;
+			wait.funding_txo = channel.and_then(|c| c.channel_point);
+		}
+	}
+	Ok(wait)
+}
+
+/// What `pay invoice create --wait` and `pay offer create --wait` wait to see paid.
+#[derive(Debug, Clone)]
+pub enum Receivable {
+	/// A BOLT11 invoice; its inbound payment's id is the payment hash.
+	Invoice { payment_hash: PaymentId, expiry_secs: u64 },
+	/// A BOLT12 offer; the first payment for it ends the wait.
+	Offer { offer_id: String, absolute_expiry_unix_secs: Option<u64> },
+}
+
+impl Receivable {
+	/// When it expires, for an invoice created just now.
+	fn expires_at(&self) -> Option<Instant> {
+		match self {
+			Self::Invoice { expiry_secs, .. } => {
+				Some(Instant::now() + Duration::from_secs(*expiry_secs))
+			},
+			Self::Offer { absolute_expiry_unix_secs, .. } => absolute_expiry_unix_secs.map(|t| {
+				let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+				Instant::now() + Duration::from_secs(t).saturating_sub(now)
+			}),
+		}
+	}
+
+	fn matches(&self, payment: &PaymentDetailsDto) -> bool {
+		match self {
+			Self::Invoice { payment_hash, .. } => &payment.id == payment_hash,
+			Self::Offer { offer_id, .. } => matches!(
+				&payment.kind_details,
+				Some(PaymentKindDetails::Bolt12Offer { offer_id: id, .. }) if id == offer_id
+			),
+		}
+	}
+
+	/// The payment for it, if the daemon knows one. An offer is looked up among the payments
+	/// created at or after `since` (Unix seconds), so that each poll costs the same however many
+	/// payments the node has made before.
+	async fn find(
+		&self, api: &RgbLdkClient, since: u64,
+	) -> Result<Option<PaymentDetailsDto>, ApiError> {
+		match self {
+			Self::Invoice { payment_hash, .. } => known_payment(api, payment_hash).await,
+			Self::Offer { .. } => {
+				let req = PaymentsListRequest {
+					limit: Some(PaymentsListRequest::MAX_LIMIT),
+					status: Some(PaymentStatus::Succeeded),
+					direction: Some(PaymentDirection::Inbound),
+					kind: Some(PaymentKind::Bolt12Offer),
+					created_after: Some(since),
+					..Default::default()
+				};
+				let page = api.payments_list_all(&req).await?;
+				Ok(page.items.into_iter().find(|p| self.matches(p)))
+			},
+		}
+	}
+}
+
+/// `None` for a payment the daemon does not know (yet).
+async fn known_payment(
+	api: &RgbLdkClient, id: &PaymentId,
+) -> Result<Option<PaymentDetailsDto>, ApiError> {
+	match api.payment(id).await {
+		Ok(payment) => Ok(Some(payment)),
+		Err(e) if e.status() == Some(StatusCode::NOT_FOUND) => Ok(None),
+		Err(e) => Err(e),
+	}
+}
+
+/// How a `--wait` for a payment ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentOutcome {
+	Paid,
+	Failed,
+	Expired,
+	Timeout,
+}
+
+/// The second record `pay invoice|offer create --wait` prints.
+#[derive(Debug, Serialize)]
+pub struct Settlement {
+	pub outcome: PaymentOutcome,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub payment_id: Option<PaymentId>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub amount_msat: Option<u64>,
+	/// The payment's status when the wait ended, if the daemon knew the payment.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub status: Option<PaymentStatus>,
+}
+
+impl Settlement {
+	fn settled(payment: &PaymentDetailsDto) -> Option<PaymentOutcome> {
+		match payment.status {
+			PaymentStatus::Succeeded => Some(PaymentOutcome::Paid),
+			PaymentStatus::Failed => Some(PaymentOutcome::Failed),
+			_ => None,
+		}
+	}
+
+	fn record(&mut self, payment: PaymentDetailsDto) {
+		self.payment_id = Some(payment.id);
+		self.amount_msat = payment.amount_msat;
+		self.status = Some(payment.status);
+	}
+
+	fn record_opt(&mut self, payment: Option<PaymentDetailsDto>) {
+		if let Some(payment) = payment {
+			self.record(payment);
+		}
+	}
+}
+
+/// Waits until `receivable` is paid, its payment fails, it expires, or `timeout` passes.
+/// Follows `PaymentReceived` and polls the payment, and shows a countdown to the expiry on
+/// stderr when `show_progress` is set.
+pub async fn payment_received(
+	api: &RgbLdkClient, receivable: &Receivable, timeout: Option<Duration>, show_progress: bool,
+) -> Result<Settlement, ApiError> {
+	let deadline = timeout.map(|t| Instant::now() + t);
+	let expires_at = receivable.expires_at();
+	let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+	let since = now.saturating_sub(CLOCK_SKEW).as_secs();
+	let mut settlement = Settlement {
+		outcome: PaymentOutcome::Timeout,
+		payment_id: None,
+		amount_msat: None,
+		status: None,
+	};
+	let mut tail = Tail::open(api).await;
+	let mut poll = tokio::time::interval(POLL_INTERVAL);
+	let outcome = loop {
+		tokio::select! {
+			biased;
+			_ = until(deadline) => break PaymentOutcome::Timeout,
+			event = tail.next() => {
+				let EventDto::PaymentReceived { payment_id: Some(id), .. } = event else {
+					continue;
+				};
+				if let Some(payment) = known_payment(api, &id).await? {
+					if receivable.matches(&payment) && payment.status == PaymentStatus::Succeeded {
+						settlement.record(payment);
+						break PaymentOutcome::Paid;
+					}
+				}
+			},
+			_ = until(expires_at) => {
+				// A payment may have landed since the last poll.
+				let payment = receivable.find(api, since).await?;
+				let outcome = payment.as_ref().and_then(Settlement::settled);
+				settlement.record_opt(payment);
+				break outcome.unwrap_or(PaymentOutcome::Expired);
+			},
+			_ = poll.tick() => {
+				let payment = receivable.find(api, since).await?;
+				let outcome = payment.as_ref().and_then(Settlement::settled);
+				settlement.record_opt(payment);
+				if let Some(outcome) = outcome {
+					break outcome;
+				}
+				if show_progress {
+					status(&match expires_at {
+						Some(t) => format!(
+							"Waiting for payment; expires in {}s",
+							t.saturating_duration_since(Instant::now()).as_secs()
+						),
+						None => "Waiting for payment".into(),
+					});
+				}
+			},
+		}
+	};
+	if show_progress {
+		clear_status();
+	}
+	settlement.outcome = outcome;
+	Ok(settlement)
+}
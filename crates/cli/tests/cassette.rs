@@ -0,0 +1,119 @@
+//! `--record` / `--replay`: a command recorded against the mock daemon replays byte for byte
+//! after the mock is gone.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use rgbldk_api::cassette::{Cassette, REDACTED};
+use rgbldk_api::http::ChannelDetailsExtendedDto;
+use rgbldk_mock::{Fault, FaultAction, MockServer};
+
+const PEER: &str = "02aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+fn home(port: u16) -> PathBuf {
+	std::env::temp_dir().join(format!("rgbldk-cassette-{}-{port}", std::process::id()))
+}
+
+/// Runs `rgbldk --output json` with `cassette_flag DIR`, against `url`.
+fn rgbldk(home: &Path, url: &str, cassette_flag: &str, args: &[&str]) -> Output {
+	Command::new(env!("CARGO_BIN_EXE_rgbldk"))
+		.args(["--connect", url, "--output", "json", cassette_flag])
+		.arg(home.join("cassette"))
+		.args(args)
+		.env("XDG_CONFIG_HOME", home.join("config"))
+		.env("XDG_CACHE_HOME", home.join("cache"))
+		.output()
+		.expect("run rgbldk")
+}
+
+/// Records `args` against a fresh mock prepared by `setup`, stops the mock and replays them.
+/// Returns the recorded run, the replayed run and the cassette.
+async fn round_trip(args: &[&str], setup: impl FnOnce(&MockServer)) -> (Output, Output, Cassette) {
+	let server = MockServer::start().await.unwrap();
+	setup(&server);
+	let home = home(server.addr().port());
+	let url = server.url();
+	let recorded = rgbldk(&home, &url, "--record", args);
+	server.shutdown().await.unwrap();
+
+	let replayed = rgbldk(&home, &url, "--replay", args);
+	assert_eq!(replayed.status.code(), recorded.status.code());
+	assert_eq!(
+		String::from_utf8_lossy(&replayed.stderr),
+		String::from_utf8_lossy(&recorded.stderr)
+	);
+	let cassette = Cassette::load(&home.join("cassette")).unwrap();
+	let _ = std::fs::remove_dir_all(&home);
+	(recorded, replayed, cassette)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn replays_a_listing_without_the_daemon() {
+	let args = ["channel", "ls"];
+	let (recorded, replayed, cassette) = round_trip(&args, |server| {
+		server.update(|s| {
+			s.channels.push(ChannelDetailsExtendedDto {
+				channel_id: "aa".repeat(32).parse().unwrap(),
+				user_channel_id: "0123456789abcdef0123456789abcdef".parse().unwrap(),
+				counterparty_node_id: PEER.parse().unwrap(),
+				channel_point: None,
+				channel_value_sats: 100_000,
+				outbound_capacity_msat: 90_000_000,
+				inbound_capacity_msat: 0,
+				is_channel_ready: true,
+				is_usable: true,
+				is_announced: false,
+				rgb_balance: None,
+			})
+		});
+	})
+	.await;
+	assert!(replayed.status.success());
+	assert_eq!(
+		String::from_utf8_lossy(&replayed.stdout),
+		String::from_utf8_lossy(&recorded.stdout)
+	);
+	let paths: Vec<_> = cassette.interactions.iter().map(|i| i.request.path.as_str()).collect();
+	assert_eq!(paths, ["/api/v1/version", "/api/v1/channels"]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn secrets_never_reach_the_cassette() {
+	let invoice = format!("lnmock1_{PEER}_{}_1000_{}_3600", "77".repeat(32), u32::MAX);
+	let args = ["pay", "invoice", "pay", "--invoice", invoice.as_str()];
+	let (recorded, replayed, cassette) = round_trip(&args, |_| {}).await;
+	let recorded: serde_json::Value = serde_json::from_slice(&recorded.stdout).unwrap();
+	let mut replayed: serde_json::Value = serde_json::from_slice(&replayed.stdout).unwrap();
+	assert_eq!(replayed["preimage"], REDACTED);
+	replayed["preimage"] = recorded["preimage"].clone();
+	assert_eq!(replayed, recorded);
+
+	let text = serde_json::to_string(&cassette).unwrap();
+	let pay = cassette.interactions.last().unwrap().response.as_ref().unwrap();
+	assert_eq!(pay.body.as_ref().unwrap()["preimage"], REDACTED);
+	assert_eq!(text.matches(REDACTED).count(), 1, "only the preimage is secret: {text}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn replays_daemon_errors_and_fails_on_unrecorded_requests() {
+	let (_, out, _) = round_trip(&["node", "status"], |server| {
+		server.inject(Fault::new("status", FaultAction::error(500, "boom")));
+	})
+	.await;
+	assert_eq!(out.status.code(), Some(5));
+
+	let server = MockServer::start().await.unwrap();
+	let home = home(server.addr().port());
+	let url = server.url();
+	assert!(rgbldk(&home, &url, "--record", &["node", "id"]).status.success());
+	drop(server);
+	let out = rgbldk(&home, &url, "--replay", &["peer", "ls"]);
+	assert_eq!(out.status.code(), Some(1));
+	assert!(
+		String::from_utf8_lossy(&out.stderr)
+			.contains("cassette has no recorded response for GET /api/v1/peers"),
+		"{}",
+		String::from_utf8_lossy(&out.stderr)
+	);
+	let _ = std::fs::remove_dir_all(&home);
+}
@@ -0,0 +1,168 @@
+//! `events forward`: a stand-in webhook receiver checks signatures and redelivery.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::{Arc, Mutex};
+
+use axum::body::Bytes;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use hmac::{Hmac, Mac};
+use rgbldk_api::http::EventDto;
+use rgbldk_mock::{Fault, FaultAction, MockServer};
+use serde_json::Value;
+use sha2::Sha256;
+
+const SECRET: &str = "whsec_test";
+
+/// One request the receiver got.
+struct Hit {
+	headers: HeaderMap,
+	body: Bytes,
+}
+
+impl Hit {
+	fn header(&self, name: &str) -> &str {
+		self.headers.get(name).and_then(|v| v.to_str().ok()).unwrap_or_default()
+	}
+
+	/// Whether the signature header is HMAC-SHA256 of `"{timestamp}.{body}"` under `secret`.
+	fn signed_with(&self, secret: &str) -> bool {
+		let Some(hex) = self.header("x-rgbldk-signature").strip_prefix("sha256=") else {
+			return false;
+		};
+		let Ok(sig) = (0..hex.len())
+			.step_by(2)
+			.map(|i| u8::from_str_radix(hex.get(i..i + 2).unwrap_or("zz"), 16))
+			.collect::<Result<Vec<u8>, _>>()
+		else {
+			return false;
+		};
+		let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+		mac.update(self.header("x-rgbldk-timestamp").as_bytes());
+		mac.update(b".");
+		mac.update(&self.body);
+		mac.verify_slice(&sig).is_ok()
+	}
+}
+
+/// Starts a webhook receiver that answers 500 to the first `failures` requests and 204 after.
+async fn receiver(failures: usize) -> (String, Arc<Mutex<Vec<Hit>>>) {
+	let hits = Arc::new(Mutex::new(Vec::new()));
+	let recorded = hits.clone();
+	let app = Router::new().route(
+		"/hook",
+		post(move |headers: HeaderMap, body: Bytes| async move {
+			let mut hits = recorded.lock().unwrap();
+			hits.push(Hit { headers, body });
+			if hits.len() <= failures {
+				StatusCode::INTERNAL_SERVER_ERROR
+			} else {
+				StatusCode::NO_CONTENT
+			}
+		}),
+	);
+	let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let url = format!("http://{}/hook", listener.local_addr().unwrap());
+	tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+	(url, hits)
+}
+
+fn home(server: &MockServer) -> PathBuf {
+	std::env::temp_dir().join(format!("rgbldk-forward-{}", server.addr().port()))
+}
+
+fn rgbldk(server: &MockServer, home: &Path, args: &[&str]) -> Output {
+	Command::new(env!("CARGO_BIN_EXE_rgbldk"))
+		.args(["--connect", &server.url(), "--output", "json"])
+		.args(args)
+		.env("XDG_CONFIG_HOME", home.join("config"))
+		.env("XDG_CACHE_HOME", home.join("cache"))
+		.env_remove("RGBLDK_WEBHOOK_SECRET")
+		.env_remove("RGBLDK_CTX")
+		.output()
+		.expect("run rgbldk")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn redelivers_until_accepted_then_acks() {
+	let server = MockServer::start().await.unwrap();
+	server.push_event(EventDto::PaymentReceived { payment_id: None, amount_msat: 1000 });
+	let (url, hits) = receiver(2).await;
+	let home = home(&server);
+
+	let args = ["events", "forward", "--url", &url, "--secret", SECRET, "--count", "1"];
+	let out = rgbldk(&server, &home, &[&args[..], &["--retry-delay-ms", "10"]].concat());
+	assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+	let printed: Value = serde_json::from_slice(&out.stdout).unwrap();
+	assert_eq!(printed["attempts"], 3);
+	assert_eq!(printed["status"], 204);
+
+	let hits = hits.lock().unwrap();
+	assert_eq!(hits.len(), 3);
+	for hit in hits.iter() {
+		assert!(hit.signed_with(SECRET), "bad signature: {:?}", hit.headers);
+		assert!(!hit.signed_with("another secret"));
+		assert_eq!(hit.header("x-rgbldk-delivery"), printed["delivery_id"]);
+		assert_eq!(hit.header("x-rgbldk-event"), "PaymentReceived");
+		assert_eq!(hit.header("content-type"), "application/json");
+		let body: Value = serde_json::from_slice(&hit.body).unwrap();
+		let data = serde_json::json!({ "payment_id": null, "amount_msat": 1000 });
+		assert_eq!(body, serde_json::json!({ "type": "PaymentReceived", "data": data }));
+	}
+	assert_eq!(server.read(|s| s.events.len()), 0, "ACKed after the webhook accepted it");
+	let _ = std::fs::remove_dir_all(&home);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn keeps_the_delivery_id_when_an_unacked_event_comes_back() {
+	let server = MockServer::start().await.unwrap();
+	for _ in 0..2 {
+		server.push_event(EventDto::PaymentFailed { payment_id: None });
+	}
+	server.inject(Fault::new("events_handled", FaultAction::error(500, "boom")).times(1));
+	let (url, hits) = receiver(0).await;
+	let home = home(&server);
+
+	let forward = ["events", "forward", "--url", &url, "--secret", SECRET, "--count", "1"];
+	let out = rgbldk(&server, &home, &forward);
+	assert!(String::from_utf8_lossy(&out.stderr).contains("could not ACK"));
+	assert_eq!(server.read(|s| s.events.len()), 2, "the first event stays queued");
+	let forward = ["events", "forward", "--url", &url, "--secret", SECRET, "--count", "2"];
+	let out = rgbldk(&server, &home, &forward);
+	assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+	assert_eq!(server.read(|s| s.events.len()), 0);
+
+	let hits = hits.lock().unwrap();
+	let ids: Vec<_> = hits.iter().map(|h| h.header("x-rgbldk-delivery")).collect();
+	assert_eq!(ids.len(), 3);
+	assert_eq!(ids[0], ids[1], "the redelivery keeps its id");
+	assert_ne!(ids[1], ids[2], "an identical event with another seq gets its own");
+	let _ = std::fs::remove_dir_all(&home);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn signs_with_the_context_secret() {
+	let server = MockServer::start().await.unwrap();
+	let data = serde_json::json!({ "kind": "Ping" });
+	server.push_event(EventDto::Other { kind: "Other".into(), data });
+	let (url, hits) = receiver(0).await;
+	let home = home(&server);
+	let forward = ["events", "forward", "--url", &url, "--count", "1"];
+
+	let out = rgbldk(&server, &home, &forward);
+	assert_eq!(out.status.code(), Some(1));
+	assert!(String::from_utf8_lossy(&out.stderr).contains("no webhook secret"));
+	assert!(hits.lock().unwrap().is_empty());
+
+	let add = ["ctx", "add", "node", "--url", &server.url(), "--webhook-secret", SECRET];
+	let out = rgbldk(&server, &home, &add);
+	assert!(out.status.success());
+	assert!(!String::from_utf8_lossy(&out.stdout).contains(SECRET), "secrets are not printed");
+
+	let out = rgbldk(&server, &home, &forward);
+	assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+	assert!(hits.lock().unwrap()[0].signed_with(SECRET));
+	let _ = std::fs::remove_dir_all(&home);
+}
@@ -0,0 +1,728 @@
+//! End-to-end runs of the `rgbldk` binary against the in-process mock daemon.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant};
+
+use rgbldk_api::client::StatusCode;
+use rgbldk_api::http::EventDto;
+use rgbldk_api::stream::Transport;
+use rgbldk_api::RgbLdkClient;
+use rgbldk_mock::{Fault, FaultAction, MockServer};
+use serde_json::Value;
+
+/// `rgbldk --output json` against `server`, with config and cache dirs of its own.
+fn rgbldk_command(server: &MockServer, args: &[&str]) -> Command {
+	let home: PathBuf = std::env::temp_dir().join(format!("rgbldk-mock-{}", server.addr().port()));
+	let mut cmd = Command::new(env!("CARGO_BIN_EXE_rgbldk"));
+	cmd.args(["--connect", &server.url(), "--output", "json", "--yes"])
+		.args(args)
+		.env("XDG_CONFIG_HOME", home.join("config"))
+		.env("XDG_CACHE_HOME", home.join("cache"))
+		.env("XDG_DATA_HOME", home.join("data"));
+	cmd
+}
+
+/// Runs [`rgbldk_command`] to completion.
+fn rgbldk(server: &MockServer, args: &[&str]) -> Output {
+	rgbldk_command(server, args).output().expect("run rgbldk")
+}
+
+fn json(out: &Output) -> Value {
+	assert!(out.status.success(), "rgbldk failed: {}", String::from_utf8_lossy(&out.stderr));
+	serde_json::from_slice(&out.stdout).expect("JSON output")
+}
+
+const PEER: &str = "02aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+#[tokio::test(flavor = "multi_thread")]
+async fn node_status_and_version() {
+	let server = MockServer::start().await.unwrap();
+	let status = json(&rgbldk(&server, &["node", "status"]));
+	assert_eq!(status["is_running"], true);
+	let version = json(&rgbldk(&server, &["node", "version"]));
+	assert_eq!(version["api_version"], "v1");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn events_are_redelivered_until_acked() {
+	let server = MockServer::start().await.unwrap();
+	server.push_event(other("First"));
+	server.push_event(other("Second"));
+
+	let first = json(&rgbldk(&server, &["events", "next"]));
+	assert_eq!(first["data"]["kind"], "First");
+	assert_eq!(json(&rgbldk(&server, &["events", "next"])), first);
+
+	json(&rgbldk(&server, &["events", "handled"]));
+	let second = json(&rgbldk(&server, &["events", "next"]));
+	assert_eq!(second["data"]["kind"], "Second");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn events_handled_by_id_never_acks_an_unseen_event() {
+	let server = MockServer::start().await.unwrap();
+	for kind in ["First", "Second"] {
+		server.push_event(other(kind));
+	}
+	let first = json(&rgbldk(&server, &["events", "next"]));
+	assert_eq!(first["id"], 1);
+
+	// Another consumer handles First in the meantime: ACKing it again leaves Second queued.
+	server.update(|s| s.ack_event());
+	json(&rgbldk(&server, &["events", "handled", "--id", "1"]));
+	assert_eq!(server.read(|s| s.events.len()), 1);
+
+	let out = rgbldk(&server, &["events", "handled", "--id", "3"]);
+	assert_eq!(out.status.code(), Some(4));
+	assert!(String::from_utf8_lossy(&out.stderr).contains("event 3 is not the head"));
+
+	json(&rgbldk(&server, &["events", "handled", "--id", "2"]));
+	assert_eq!(server.read(|s| s.events.len()), 0);
+}
+
+/// One JSON value per output line.
+fn json_lines(out: &Output) -> Vec<Value> {
+	assert!(out.status.success(), "rgbldk failed: {}", String::from_utf8_lossy(&out.stderr));
+	String::from_utf8_lossy(&out.stdout)
+		.lines()
+		.map(|l| serde_json::from_str(l).expect("JSON line"))
+		.collect()
+}
+
+/// An event of a kind the daemon does not map, as it sends them: `Other` with `{"kind": ...}`.
+fn other(kind: &str) -> EventDto {
+	EventDto::Other { kind: "Other".into(), data: serde_json::json!({ "kind": kind }) }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn events_watch_uses_the_stream_and_acks_each_event() {
+	let server = MockServer::start().await.unwrap();
+	for kind in ["First", "Second", "Third"] {
+		server.push_event(other(kind));
+	}
+	server.inject(Fault::new("events_wait_next", FaultAction::error(500, "long-poll used")));
+
+	let events = json_lines(&rgbldk(&server, &["events", "watch", "--count", "2"]));
+	let kinds: Vec<_> = events.iter().map(|e| e["data"]["kind"].clone()).collect();
+	assert_eq!(kinds, ["First", "Second"]);
+	assert_eq!(server.read(|s| (s.acked_events, s.events.len())), (2, 1));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn events_watch_falls_back_to_long_poll() {
+	let server = MockServer::start().await.unwrap();
+	server.push_event(other("First"));
+	server.inject(Fault::new("events_stream", FaultAction::error(404, "not found")));
+
+	let events = json_lines(&rgbldk(&server, &["events", "watch", "--count", "1"]));
+	assert_eq!(events[0]["data"]["kind"], "First");
+	assert_eq!(server.read(|s| s.events.len()), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stream_acks_must_name_the_head() {
+	let server = MockServer::start().await.unwrap();
+	for kind in ["First", "Second"] {
+		server.push_event(other(kind));
+	}
+	let api = RgbLdkClient::new(server.url());
+	let mut events = api.subscribe_events().await.unwrap();
+	assert_eq!(events.transport(), Transport::Stream);
+
+	let first = events.next().await.unwrap().unwrap();
+	let second = events.next().await.unwrap().unwrap();
+	assert_eq!((first.seq, second.seq), (Some(1), Some(2)));
+	let err = events.ack(&second).await.unwrap_err();
+	assert_eq!(err.status(), Some(StatusCode::CONFLICT));
+	events.ack(&first).await.unwrap();
+	events.ack(&first).await.unwrap();
+	events.ack(&second).await.unwrap();
+	assert_eq!(server.read(|s| s.events.len()), 0);
+}
+
+/// A scratch directory for hook output, unique to `server`.
+fn scratch(server: &MockServer) -> PathBuf {
+	let dir = std::env::temp_dir().join(format!("rgbldk-hook-{}", server.addr().port()));
+	let _ = std::fs::remove_dir_all(&dir);
+	std::fs::create_dir_all(&dir).unwrap();
+	dir
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn events_watch_exec_acks_once_the_hook_succeeds() {
+	let server = MockServer::start().await.unwrap();
+	for kind in ["First", "Second"] {
+		server.push_event(other(kind));
+	}
+	let dir = scratch(&server);
+	// Fails the first run, then appends the event and the attempt number.
+	let hook = format!(
+		"cd {} && if [ ! -e failed ]; then touch failed; exit 1; fi; \
+		 cat >> events && echo $RGBLDK_EVENT_TYPE $RGBLDK_EVENT_SEQ $RGBLDK_EVENT_ATTEMPT >> env",
+		dir.display()
+	);
+	let out = rgbldk(
+		&server,
+		&["events", "watch", "--count", "2", "--exec", &hook, "--retry-delay-ms", "10"],
+	);
+	assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+	assert!(String::from_utf8_lossy(&out.stderr).contains("exited with exit status: 1 (attempt 1)"));
+
+	let events = std::fs::read_to_string(dir.join("events")).unwrap();
+	let kinds: Vec<Value> = events.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+	assert_eq!(kinds[0]["data"]["kind"], "First");
+	assert_eq!(kinds[1]["data"]["kind"], "Second");
+	assert_eq!(std::fs::read_to_string(dir.join("env")).unwrap(), "Other 1 2\nOther 2 1\n");
+	assert_eq!(server.read(|s| s.events.len()), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn events_watch_exec_gives_up_after_max_attempts() {
+	let server = MockServer::start().await.unwrap();
+	server.push_event(other("Poison"));
+	let dir = scratch(&server);
+	let watch = |extra: &[&str]| {
+		let mut args = vec!["events", "watch", "--count", "1", "--exec", "exit 3"];
+		args.extend(["--max-attempts", "2", "--retry-delay-ms", "10"]);
+		args.extend(extra);
+		rgbldk(&server, &args)
+	};
+
+	let out = watch(&[]);
+	assert_eq!(out.status.code(), Some(1));
+	assert!(String::from_utf8_lossy(&out.stderr).contains("giving up after 2 attempts"));
+	assert_eq!(server.read(|s| s.events.len()), 1, "not ACKed");
+
+	let dead = dir.join("dead.jsonl");
+	let out = watch(&["--dead-letter", dead.to_str().unwrap()]);
+	assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+	let line: Value = serde_json::from_str(&std::fs::read_to_string(&dead).unwrap()).unwrap();
+	assert_eq!(line["attempts"], 2);
+	assert_eq!(line["last_exit_code"], 3);
+	assert_eq!(line["event"]["data"]["kind"], "Poison");
+	assert_eq!(server.read(|s| s.events.len()), 0, "ACKed once dead-lettered");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn events_journal_dedups_redeliveries_and_filters_the_log() {
+	let server = MockServer::start().await.unwrap();
+	let paid = |id: char| EventDto::PaymentReceived {
+		payment_id: Some(id.to_string().repeat(64).parse().unwrap()),
+		amount_msat: 1000,
+	};
+	// Two events about the same payment are both journaled.
+	for event in [paid('a'), paid('a'), other("Ping"), paid('b')] {
+		server.push_event(event);
+	}
+	// The first one is journaled, then its ACK is rejected, as after a crash in between.
+	server.inject(Fault::new("events_handled", FaultAction::error(400, "rejected")).times(1));
+	let out = rgbldk(&server, &["events", "watch", "--count", "1", "--journal"]);
+	assert_eq!(out.status.code(), Some(4));
+	let out = rgbldk(&server, &["events", "watch", "--count", "4", "--journal"]);
+	assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+	assert_eq!(json_lines(&out).len(), 4, "the watcher still prints every delivery");
+
+	let log = |extra: &[&str]| json(&rgbldk(&server, &[&["events", "log"], extra].concat()));
+	let all = log(&[]);
+	let kinds: Vec<&str> =
+		all.as_array().unwrap().iter().map(|e| e["type"].as_str().unwrap()).collect();
+	assert_eq!(kinds, ["PaymentReceived", "PaymentReceived", "Other", "PaymentReceived"]);
+	assert_eq!(all[0]["seq"], 1);
+	assert_eq!(all[0]["event"]["data"]["amount_msat"], 1000);
+
+	assert_eq!(log(&["--type", "paymentreceived"]).as_array().unwrap().len(), 3);
+	let by_id = log(&["--id", &"b".repeat(64)]);
+	assert_eq!(by_id.as_array().unwrap().len(), 1);
+	assert_eq!(by_id[0]["seq"], 4);
+	assert_eq!(log(&["--limit", "1"])[0]["seq"], 4);
+	assert_eq!(log(&["--since", "4102444800"]), serde_json::json!([]));
+
+	server.push_event(other("Pong"));
+	let watch = ["events", "watch", "--journal", "--type", "PaymentReceived", "--skip", "stop"];
+	assert_eq!(rgbldk(&server, &watch).status.code(), Some(8));
+	assert_eq!(log(&[]).as_array().unwrap().len(), 4, "an event left queued is not journaled");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn event_filters_drop_or_keep_non_matching_events() {
+	let server = MockServer::start().await.unwrap();
+	let paid = |id: char| EventDto::PaymentReceived {
+		payment_id: Some(id.to_string().repeat(64).parse().unwrap()),
+		amount_msat: 1000,
+	};
+	for event in [other("Ping"), paid('a'), other("Pong"), paid('b')] {
+		server.push_event(event);
+	}
+
+	let out = rgbldk(&server, &["events", "next", "--type", "PaymentReceived"]);
+	assert_eq!(out.status.code(), Some(2), "a filter needs an explicit --skip policy");
+
+	let watch = ["events", "watch", "--count", "1", "--type", "paymentreceived", "--skip", "ack"];
+	let out = rgbldk(&server, &watch);
+	let printed = json_lines(&out);
+	assert_eq!(printed.len(), 1);
+	assert_eq!(printed[0]["data"]["payment_id"], "a".repeat(64));
+	assert_eq!(server.read(|s| s.events.len()), 2, "Ping dropped, the payment ACKed");
+
+	let want = format!("payment_id={}", "B".repeat(64));
+	let out = rgbldk(&server, &["events", "next", "--match", &want, "--skip", "stop"]);
+	assert_eq!(out.status.code(), Some(8));
+	assert!(String::from_utf8_lossy(&out.stderr).ends_with("left in the queue: Pong\n"));
+	assert_eq!(server.read(|s| s.events.len()), 2, "left in the queue for another consumer");
+
+	let out = rgbldk(&server, &["events", "next", "--match", &want, "--skip", "ack"]);
+	assert_eq!(json(&out)["data"]["payment_id"], "b".repeat(64));
+	assert_eq!(server.read(|s| s.events.len()), 1, "`next` does not ACK the match");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn events_of_unknown_kinds_keep_their_data() {
+	let server = MockServer::start().await.unwrap();
+	let data = serde_json::json!({ "channel_id": "c".repeat(64), "splice_id": 7 });
+	server.push_event(other("Ping"));
+	server.push_event(EventDto::Other { kind: "SpliceLocked".into(), data: data.clone() });
+
+	let next = ["events", "next", "--type", "splicelocked", "--skip", "ack"];
+	let event = json(&rgbldk(&server, &next));
+	assert_eq!((&event["type"], &event["data"]), (&Value::from("SpliceLocked"), &data));
+	let next = ["events", "next", "--match", "splice_id=7", "--skip", "stop"];
+	assert_eq!(json(&rgbldk(&server, &next)), event, "Ping ACKed, the match left queued");
+	json(&rgbldk(&server, &["events", "handled"]));
+	assert_eq!(server.read(|s| s.events.len()), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn events_watch_reconnects_and_acks_a_redelivered_head_once() {
+	let server = MockServer::start().await.unwrap();
+	for kind in ["First", "Second"] {
+		server.push_event(other(kind));
+	}
+	server.inject(Fault::new("events_stream", FaultAction::error(503, "starting")).times(1));
+	server.inject(Fault::new("events_handled", FaultAction::error(503, "restarting")).times(1));
+
+	let out = rgbldk(&server, &["events", "watch", "--count", "2"]);
+	assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+	let kinds: Vec<Value> = json_lines(&out).iter().map(|e| e["data"]["kind"].clone()).collect();
+	assert_eq!(kinds, ["First", "Second"], "First is not processed twice");
+	let stderr = String::from_utf8_lossy(&out.stderr);
+	assert!(stderr.contains("disconnected: HTTP 503"), "{stderr}");
+	assert!(stderr.contains("reconnected to"), "{stderr}");
+	assert!(stderr.contains("the Other event was already processed; ACKing it"), "{stderr}");
+	assert_eq!(server.read(|s| s.events.len()), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn events_watch_survives_a_daemon_restart() {
+	let server = MockServer::start().await.unwrap();
+	server.push_event(other("Before"));
+	let mut watch = rgbldk_command(&server, &["events", "watch", "--count", "2"])
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.unwrap();
+
+	let deadline = Instant::now() + Duration::from_secs(20);
+	while server.read(|s| s.acked_events) == 0 {
+		assert!(Instant::now() < deadline, "first event never ACKed");
+		tokio::time::sleep(Duration::from_millis(20)).await;
+	}
+	let (addr, state) = (server.addr(), server.read(|s| s.clone()));
+	server.shutdown().await.unwrap();
+	tokio::time::sleep(Duration::from_millis(300)).await;
+	let server = MockServer::bind(addr, state).await.unwrap();
+	server.push_event(other("After"));
+
+	while watch.try_wait().unwrap().is_none() {
+		assert!(Instant::now() < deadline, "watch did not pick up the restarted daemon");
+		tokio::time::sleep(Duration::from_millis(20)).await;
+	}
+	let out = watch.wait_with_output().unwrap();
+	assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+	assert_eq!(json_lines(&out)[1]["data"]["kind"], "After");
+	assert!(String::from_utf8_lossy(&out.stderr).contains("reconnecting in"));
+	assert_eq!(server.read(|s| s.events.len()), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn channel_open_confirm_and_close() {
+	let server = MockServer::start().await.unwrap();
+	let opened = json(&rgbldk(
+		&server,
+		&[
+			"channel",
+			"open",
+			"--node-id",
+			PEER,
+			"--addr",
+			"127.0.0.1:9736",
+			"--amount-sats",
+			"100k sat",
+		],
+	));
+	let user_channel_id = opened["user_channel_id"].as_str().unwrap().to_string();
+
+	let channels = json(&rgbldk(&server, &["channel", "ls"]));
+	assert_eq!(channels[0]["is_channel_ready"], false);
+	server.mine(1);
+	let channels = json(&rgbldk(&server, &["channel", "ls"]));
+	assert_eq!(channels[0]["is_channel_ready"], true);
+	assert_eq!(server.read(|s| s.events.len()), 2, "ChannelPending and ChannelReady");
+
+	json(&rgbldk(
+		&server,
+		&[
+			"channel",
+			"close",
+			"--user-channel-id",
+			&user_channel_id,
+			"--counterparty-node-id",
+			PEER,
+		],
+	));
+	assert!(server.read(|s| s.channels.is_empty()));
+}
+
+/// Starts `channel open --wait-ready` and waits until the channel exists.
+async fn open_and_wait(server: &MockServer, extra: &[&str]) -> std::process::Child {
+	let mut args = vec!["channel", "open", "--node-id", PEER, "--addr", "127.0.0.1:9736"];
+	args.extend(["--amount-sats", "100k sat", "--wait-ready"]);
+	args.extend(extra);
+	let child = rgbldk_command(server, &args)
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.unwrap();
+	let deadline = Instant::now() + Duration::from_secs(20);
+	while server.read(|s| s.channels.is_empty()) {
+		assert!(Instant::now() < deadline, "channel never opened");
+		tokio::time::sleep(Duration::from_millis(20)).await;
+	}
+	child
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn channel_open_waits_until_ready() {
+	let server = MockServer::start().await.unwrap();
+	let child = open_and_wait(&server, &[]).await;
+	tokio::time::sleep(Duration::from_millis(300)).await;
+	server.mine(3);
+
+	let out = child.wait_with_output().unwrap();
+	let wait = json(&out);
+	assert_eq!(wait["outcome"], "ready");
+	assert_eq!(wait["opened_at_height"], 100);
+	assert_eq!(wait["best_block_height"], 103);
+	let channel_point = server.read(|s| s.channels[0].channel_point.clone());
+	assert_eq!(wait["funding_txo"].as_str(), channel_point.as_deref());
+	assert_eq!(server.read(|s| s.events.len()), 2, "nothing ACKed behind the owner's back");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn channel_open_wait_fails_on_close_and_timeout() {
+	let server = MockServer::start().await.unwrap();
+	let child = open_and_wait(&server, &[]).await;
+	let user_channel_id = server.read(|s| s.channels[0].user_channel_id.to_string());
+	let close = ["channel", "close", "--user-channel-id", &user_channel_id];
+	json(&rgbldk(&server, &[&close[..], &["--counterparty-node-id", PEER]].concat()));
+
+	let out = child.wait_with_output().unwrap();
+	assert_eq!(out.status.code(), Some(1));
+	assert_eq!(serde_json::from_slice::<Value>(&out.stdout).unwrap()["outcome"], "closed");
+	let stderr = String::from_utf8_lossy(&out.stderr);
+	assert!(stderr.contains("closed before it was ready: LocallyInitiatedCooperativeClosure"));
+
+	let out = open_and_wait(&server, &["--timeout", "1"]).await.wait_with_output().unwrap();
+	assert_eq!(out.status.code(), Some(9));
+	assert_eq!(serde_json::from_slice::<Value>(&out.stdout).unwrap()["outcome"], "timeout");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn invoice_paid_by_the_simulated_payer() {
+	let server = MockServer::start().await.unwrap();
+	let created = json(&rgbldk(
+		&server,
+		&["pay", "invoice", "create", "--desc", "coffee", "--amount-msat", "21k sat"],
+	));
+	let invoice = created["invoice"].as_str().unwrap();
+
+	let payment_id = server.update(|s| s.receive(invoice, None)).unwrap();
+	let payment = json(&rgbldk(&server, &["pay", "get", payment_id.as_str()]));
+	assert_eq!(payment["status"], "Succeeded");
+	assert_eq!(payment["amount_msat"], 21_000_000);
+	let event = json(&rgbldk(&server, &["events", "next"]));
+	assert_eq!(event["type"], "PaymentReceived");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pay_ls_all_without_the_list_route_fetches_payments_once() {
+	let server = MockServer::start().await.unwrap();
+	server.update(|s| {
+		for (n, created_at) in [('a', 30), ('b', 10), ('c', 20)] {
+			let payment = serde_json::json!({
+				"id": n.to_string().repeat(64), "direction": "Inbound", "status": "Succeeded",
+				"amount_msat": 1000, "kind": "Bolt11", "fee_paid_msat": null,
+				"created_at": created_at,
+			});
+			s.payments.push(serde_json::from_value(payment).unwrap());
+		}
+	});
+	server.inject(Fault::new("payments_list", FaultAction::error(404, "not found")));
+	let cassette = scratch(&server);
+
+	let args = ["--record", cassette.to_str().unwrap(), "pay", "ls", "--limit", "1", "--all"];
+	let page = json(&rgbldk(&server, &args));
+	let ids: Vec<_> =
+		page["items"].as_array().unwrap().iter().map(|p| &p["id"].as_str().unwrap()[..1]).collect();
+	assert_eq!(ids, ["a", "c", "b"]);
+	let recorded = std::fs::read_to_string(cassette.join("cassette.json")).unwrap();
+	let recorded: Value = serde_json::from_str(&recorded).unwrap();
+	let fetches = recorded["interactions"]
+		.as_array()
+		.unwrap()
+		.iter()
+		.filter(|i| i["request"]["method"] == "GET" && i["request"]["path"] == "/api/v1/payments")
+		.count();
+	assert_eq!(fetches, 1, "three pages from one GET /payments");
+}
+
+/// Runs `pay invoice|offer create --wait` and returns the first record (printed before the
+/// wait), the second record and the exit code; `pay` runs once the first record is out.
+fn create_and_wait(
+	server: &MockServer, args: &[&str], pay: impl FnOnce(&Value),
+) -> (Value, Value, Option<i32>) {
+	let mut child = rgbldk_command(server, &[&["pay"], args, &["--wait"]].concat())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::null())
+		.spawn()
+		.unwrap();
+	let mut stdout = BufReader::new(child.stdout.take().unwrap());
+	let mut line = String::new();
+	stdout.read_line(&mut line).unwrap();
+	let created: Value = serde_json::from_str(&line).unwrap();
+	pay(&created);
+	let mut rest = String::new();
+	stdout.read_to_string(&mut rest).unwrap();
+	let code = child.wait().unwrap().code();
+	(created, serde_json::from_str(&rest).unwrap(), code)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn invoice_and_offer_create_wait_for_the_payment() {
+	let server = MockServer::start().await.unwrap();
+	let invoice = ["invoice", "create", "--desc", "coffee", "--amount-msat", "21k sat"];
+	let (created, settled, code) = create_and_wait(&server, &invoice, |created| {
+		std::thread::sleep(Duration::from_millis(300));
+		server.update(|s| s.receive(created["invoice"].as_str().unwrap(), None)).unwrap();
+	});
+	assert_eq!(code, Some(0));
+	assert!(created["invoice"].is_string());
+	assert_eq!(settled["outcome"], "paid");
+	assert_eq!(settled["status"], "Succeeded");
+	assert_eq!(settled["amount_msat"], 21_000_000);
+
+	// A daemon without `POST /payments/list`: the offer's payment is found through `GET /payments`.
+	server.inject(Fault::new("payments_list", FaultAction::error(404, "not found")));
+	let offer = ["offer", "create", "--desc", "tea", "--amount-msat", "5k sat"];
+	let (_, settled, code) = create_and_wait(&server, &offer, |created| {
+		server.update(|s| s.receive(created["offer"].as_str().unwrap(), None)).unwrap();
+	});
+	assert_eq!(code, Some(0));
+	assert_eq!(settled["outcome"], "paid");
+	assert_eq!(settled["amount_msat"], 5_000_000);
+	assert_eq!(server.read(|s| s.events.len()), 2, "nothing ACKed behind the owner's back");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn invoice_create_wait_exits_on_expiry_and_timeout() {
+	let server = MockServer::start().await.unwrap();
+	let invoice = ["invoice", "create", "--desc", "coffee", "--amount-msat", "1000"];
+	let expiring = [&invoice[..], &["--expiry-secs", "1"]].concat();
+	let (_, settled, code) = create_and_wait(&server, &expiring, |_| {});
+	assert_eq!(code, Some(10));
+	assert_eq!(settled["outcome"], "expired");
+	assert_eq!(settled["status"], "Pending");
+
+	let (_, settled, code) =
+		create_and_wait(&server, &[&invoice[..], &["--timeout", "1"]].concat(), |_| {});
+	assert_eq!(code, Some(9));
+	assert_eq!(settled["outcome"], "timeout");
+}
+
+/// `GET {path}` from `addr` over a plain connection; the whole response.
+fn http_get(addr: &str, path: &str) -> String {
+	let mut conn = std::net::TcpStream::connect(addr).unwrap();
+	write!(conn, "GET {path} HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\n\r\n").unwrap();
+	let mut response = String::new();
+	conn.read_to_string(&mut response).unwrap();
+	response
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn exporter_serves_gauges_and_event_counts() {
+	let server = MockServer::start().await.unwrap();
+	let open = ["channel", "open", "--node-id", PEER, "--addr", "127.0.0.1:9736"];
+	json(&rgbldk(&server, &[&open[..], &["--amount-sats", "100k sat"]].concat()));
+	let invoice = ["pay", "invoice", "create", "--desc", "coffee", "--amount-msat", "1000"];
+	let created = json(&rgbldk(&server, &invoice));
+	server.update(|s| s.receive(created["invoice"].as_str().unwrap(), None)).unwrap();
+	server.inject(Fault::new("balances", FaultAction::error(500, "boom")).times(1000));
+
+	let args = ["exporter", "--listen", "127.0.0.1:0", "--interval-secs", "1"];
+	let mut child = rgbldk_command(&server, &args).stderr(Stdio::piped()).spawn().unwrap();
+	let mut banner = String::new();
+	BufReader::new(child.stderr.take().unwrap()).read_line(&mut banner).unwrap();
+	let addr = banner.split("http://").nth(1).unwrap().split('/').next().unwrap().to_string();
+
+	let deadline = Instant::now() + Duration::from_secs(20);
+	let metrics = loop {
+		let response = http_get(&addr, "/metrics");
+		if response.contains(r#"rgbldk_events_total{type="PaymentReceived"} 1"#) {
+			break response;
+		}
+		assert!(Instant::now() < deadline, "events never counted:\n{response}");
+		tokio::time::sleep(Duration::from_millis(200)).await;
+	};
+	assert!(http_get(&addr, "/").starts_with("HTTP/1.1 404"));
+
+	// Payments made after the first scrape are picked up by the incremental refresh.
+	let created = json(&rgbldk(&server, &invoice));
+	server.update(|s| s.receive(created["invoice"].as_str().unwrap(), None)).unwrap();
+	let paid_twice = r#"rgbldk_payments{direction="Inbound",status="Succeeded"} 2"#;
+	while !http_get(&addr, "/metrics").lines().any(|l| l == paid_twice) {
+		assert!(Instant::now() < deadline, "the second payment was never counted");
+		tokio::time::sleep(Duration::from_millis(200)).await;
+	}
+	let _ = child.kill();
+	let _ = child.wait();
+
+	assert!(metrics.starts_with("HTTP/1.1 200 OK"));
+	assert!(metrics.to_ascii_lowercase().contains("content-type: text/plain; version=0.0.4"));
+	for line in [
+		"rgbldk_up 1",
+		"rgbldk_ready 1",
+		"rgbldk_best_block_height 100",
+		r#"rgbldk_scrape_ok{source="status"} 1"#,
+		r#"rgbldk_scrape_ok{source="balances"} 0"#,
+		r#"rgbldk_channels{state="pending"} 1"#,
+		r#"rgbldk_channels{state="usable"} 0"#,
+		r#"rgbldk_payments{direction="Inbound",status="Succeeded"} 1"#,
+		r#"rgbldk_events_total{type="ChannelPending"} 1"#,
+		"# TYPE rgbldk_events_total counter",
+	] {
+		assert!(metrics.lines().any(|l| l == line), "missing {line:?} in:\n{metrics}");
+	}
+	assert!(!metrics.contains("rgbldk_lightning_balance_sats"), "failed sources are left out");
+	assert_eq!(server.read(|s| s.events.len()), 3, "nothing ACKed behind the owner's back");
+}
+
+/// The directory [`start_hub`] keeps the hub's log and cursors in.
+#[cfg(unix)]
+fn hub_dir(server: &MockServer) -> PathBuf {
+	std::env::temp_dir().join(format!("rgbldk-mock-{}", server.addr().port())).join("hub")
+}
+
+/// Starts `events hub` on a socket of its own and waits until it listens.
+#[cfg(unix)]
+async fn start_hub(server: &MockServer) -> (std::process::Child, String) {
+	let dir = hub_dir(server);
+	let socket = dir.with_file_name("hub.sock");
+	let _ = std::fs::remove_file(&socket);
+	let socket = socket.to_str().unwrap().to_string();
+	let hub = rgbldk_command(server, &["events", "hub", "--socket", &socket])
+		.arg("--dir")
+		.arg(&dir)
+		.stdout(Stdio::null())
+		.stderr(Stdio::null())
+		.spawn()
+		.unwrap();
+	let deadline = Instant::now() + Duration::from_secs(20);
+	while std::os::unix::net::UnixStream::connect(&socket).is_err() {
+		assert!(Instant::now() < deadline, "hub never listened");
+		tokio::time::sleep(Duration::from_millis(20)).await;
+	}
+	(hub, socket)
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn events_hub_fans_out_with_durable_cursors() {
+	let server = MockServer::start().await.unwrap();
+	let _ = std::fs::remove_dir_all(hub_dir(&server));
+	let (mut hub, socket) = start_hub(&server).await;
+	for amount_msat in [1, 2] {
+		server.push_event(EventDto::PaymentReceived { payment_id: None, amount_msat });
+	}
+	let watch = |subscriber: &str, count: &str| {
+		let args = ["events", "watch", "--hub", &socket, "--subscriber", subscriber];
+		let out = rgbldk(&server, &[&args[..], &["--count", count]].concat());
+		assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+		String::from_utf8(out.stdout)
+			.unwrap()
+			.lines()
+			.map(|l| serde_json::from_str::<Value>(l).unwrap()["data"]["amount_msat"].clone())
+			.collect::<Vec<_>>()
+	};
+
+	assert_eq!(watch("a", "1"), [1]);
+	assert_eq!(watch("a", "1"), [2], "the cursor survives the subscriber");
+	assert_eq!(watch("b", "2"), [1, 2], "each subscriber has its own cursor");
+	assert_eq!(server.read(|s| s.events.len()), 0, "the hub ACKed both");
+
+	let _ = hub.kill();
+	let _ = hub.wait();
+	server.push_event(EventDto::PaymentReceived { payment_id: None, amount_msat: 3 });
+	let (mut hub, _) = start_hub(&server).await;
+	assert_eq!(watch("a", "1"), [3], "the cursor survives the hub");
+	assert_eq!(watch("b", "1"), [3]);
+	let _ = hub.kill();
+	let _ = hub.wait();
+
+	for amount_msat in 4..=73 {
+		server.push_event(EventDto::PaymentReceived { payment_id: None, amount_msat });
+	}
+	let (mut hub, _) = start_hub(&server).await;
+	assert_eq!(watch("a", "70").len(), 70);
+	let log = hub_dir(&server).join("events.jsonl");
+	let log_ids = || {
+		std::fs::read_to_string(&log)
+			.unwrap()
+			.lines()
+			.map(|l| serde_json::from_str::<Value>(l).unwrap()["id"].as_u64().unwrap())
+			.collect::<Vec<_>>()
+	};
+	assert_eq!(log_ids(), (1..=73).collect::<Vec<_>>(), "b holds the log back");
+	assert_eq!(watch("b", "70").len(), 70);
+	assert_eq!(log_ids(), (65..=73).collect::<Vec<_>>(), "what both ACKed is compacted");
+	assert_eq!(watch("c", "1"), [65], "a new subscriber starts at the oldest entry kept");
+	let _ = hub.kill();
+	let _ = hub.wait();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_cached_version_is_checked_again_before_refusing() {
+	let server = MockServer::start().await.unwrap();
+	server.update(|s| s.version.capabilities.retain(|op| op != "channels"));
+	let _ = std::fs::remove_dir_all(
+		std::env::temp_dir().join(format!("rgbldk-mock-{}", server.addr().port())),
+	);
+	assert!(rgbldk(&server, &["node", "status"]).status.success(), "caches the version");
+	assert_eq!(rgbldk(&server, &["channel", "ls"]).status.code(), Some(7));
+
+	server.update(|s| s.version.capabilities.push("channels".into()));
+	let out = rgbldk(&server, &["channel", "ls"]);
+	assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn injected_faults_map_to_exit_codes() {
+	let server = MockServer::start().await.unwrap();
+	server.inject(Fault::new("status", FaultAction::error(500, "boom")).times(1));
+	server.inject(Fault::new("status", FaultAction::error(400, "nope")).times(1));
+	server.inject(Fault::new("status", FaultAction::Garbage).times(1));
+
+	let codes: Vec<_> =
+		(0..4).map(|_| rgbldk(&server, &["node", "status"]).status.code()).collect();
+	assert_eq!(codes, [Some(5), Some(4), Some(6), Some(0)]);
+}
@@ -0,0 +1,557 @@
+//! Golden-file tests for the text and JSON output of every subcommand.
+//!
+//! Each case runs `rgbldk` twice, with `--output text` and `--output json`, replaying the
+//! daemon's answers from `tests/cassettes/<case>/<output>` (`--replay`), and compares stdout,
+//! stderr and the exit code with `tests/snapshots/<case>.txt`. Review snapshot diffs like code;
+//! regenerate them with `UPDATE_SNAPSHOTS=1 cargo test -p rgbldk-cli --test snapshots`.
+//!
+//! A cassette can be recorded from any daemon with `rgbldk --record <dir> <command>`.
+//! `RECORD_CASSETTES=1` re-records every case against a fresh mock daemon seeded with
+//! [`fixture`] and the case's setup, and implies `UPDATE_SNAPSHOTS`.
+
+use std::collections::{BTreeSet, VecDeque};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use rgbldk_api::http::*;
+use rgbldk_mock::{Fault, FaultAction, MockServer, MockState, PaymentOutcome};
+
+const CLOCK: u64 = 1_750_000_000;
+const PEER_A: &str = "02aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const PEER_B: &str = "03bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+const READY_CHANNEL: &str = "0123456789abcdef0123456789abcdef";
+const PENDING_CHANNEL: &str = "fedcba9876543210fedcba9876543210";
+const PAID_OUT: &str = "1111111111111111111111111111111111111111111111111111111111111111";
+const PAID_IN: &str = "2222222222222222222222222222222222222222222222222222222222222222";
+const PENDING_OFFER: &str = "3333333333333333333333333333333333333333333333333333333333333333";
+const FAILED_KEYSEND: &str = "4444444444444444444444444444444444444444444444444444444444444444";
+const ONCHAIN: &str = "5555555555555555555555555555555555555555555555555555555555555555";
+const FUTURE_KIND: &str = "6666666666666666666666666666666666666666666666666666666666666666";
+const HASH: &str = "7777777777777777777777777777777777777777777777777777777777777777";
+
+/// An invoice, offer and refund from `PEER_A`, in the mock's encoding.
+fn remote_invoice() -> String {
+	format!("lnmock1_{PEER_A}_{HASH}_1500000_{CLOCK}_3600")
+}
+
+fn remote_offer() -> String {
+	format!("lnomock1_{PEER_A}_{HASH}_any_none_none")
+}
+
+fn remote_refund() -> String {
+	format!("lnrmock1_{PEER_A}_{HASH}_75000_{}", CLOCK + 3600)
+}
+
+fn id<T: std::str::FromStr>(s: &str) -> T
+where
+	T::Err: std::fmt::Debug,
+{
+	s.parse().unwrap()
+}
+
+fn payment(
+	id_hex: &str, direction: PaymentDirection, status: PaymentStatus, kind: PaymentKind,
+	amount_msat: Option<u64>, fee_paid_msat: Option<u64>, kind_details: PaymentKindDetails,
+) -> PaymentDetailsDto {
+	PaymentDetailsDto {
+		id: id(id_hex),
+		direction,
+		status,
+		amount_msat,
+		kind,
+		fee_paid_msat,
+		kind_details: Some(kind_details),
+		created_at: None,
+	}
+}
+
+/// A node with two peers, a ready and a pending channel, one payment of every shape and two
+/// queued events.
+fn fixture() -> MockState {
+	let mut s = MockState::default();
+	s.clock = Some(CLOCK);
+	s.best_block_height = 812_345;
+	s.onchain_balance_sats = 123_456_789;
+	s.peers = vec![
+		PeerDetailsDto {
+			node_id: id(PEER_A),
+			address: "10.0.0.1:9735".into(),
+			is_persisted: true,
+			is_connected: true,
+		},
+		PeerDetailsDto {
+			node_id: id(PEER_B),
+			address: "10.0.0.2:9735".into(),
+			is_persisted: false,
+			is_connected: true,
+		},
+	];
+	s.channels = vec![
+		ChannelDetailsExtendedDto {
+			channel_id: id(&PAID_OUT.replace('1', "a")),
+			user_channel_id: id(READY_CHANNEL),
+			counterparty_node_id: id(PEER_A),
+			channel_point: Some(format!("{}:1", ONCHAIN.replace('5', "c"))),
+			channel_value_sats: 1_234_567,
+			outbound_capacity_msat: 1_000_000_000,
+			inbound_capacity_msat: 200_000_000,
+			is_channel_ready: true,
+			is_usable: true,
+			is_announced: true,
+			rgb_balance: Some(RgbChannelBalanceDto {
+				asset_id: id(&PAID_OUT.replace('1', "e")),
+				local_amount: 900,
+				remote_amount: 100,
+			}),
+		},
+		ChannelDetailsExtendedDto {
+			channel_id: id(&PAID_OUT.replace('1', "b")),
+			user_channel_id: id(PENDING_CHANNEL),
+			counterparty_node_id: id(PEER_B),
+			channel_point: None,
+			channel_value_sats: 100_000,
+			outbound_capacity_msat: 100_000_000,
+			inbound_capacity_msat: 0,
+			is_channel_ready: false,
+			is_usable: false,
+			is_announced: false,
+			rgb_balance: None,
+		},
+	];
+	s.payments = vec![
+		payment(
+			PAID_OUT,
+			PaymentDirection::Outbound,
+			PaymentStatus::Succeeded,
+			PaymentKind::Bolt11,
+			Some(1_500_000),
+			Some(1_234),
+			PaymentKindDetails::Bolt11 {
+				payment_hash: PAID_OUT.into(),
+				preimage: Some(HASH.into()),
+				secret: None,
+			},
+		),
+		payment(
+			PAID_IN,
+			PaymentDirection::Inbound,
+			PaymentStatus::Succeeded,
+			PaymentKind::Bolt11,
+			Some(21_000_000),
+			None,
+			PaymentKindDetails::Bolt11 {
+				payment_hash: PAID_IN.into(),
+				preimage: Some(HASH.into()),
+				secret: Some(HASH.into()),
+			},
+		),
+		payment(
+			PENDING_OFFER,
+			PaymentDirection::Outbound,
+			PaymentStatus::Pending,
+			PaymentKind::Bolt12Offer,
+			Some(250_000),
+			None,
+			PaymentKindDetails::Bolt12Offer {
+				payment_hash: None,
+				preimage: None,
+				secret: None,
+				offer_id: HASH.into(),
+				payer_note: Some("thanks".into()),
+				quantity: Some(2),
+			},
+		),
+		payment(
+			FAILED_KEYSEND,
+			PaymentDirection::Outbound,
+			PaymentStatus::Failed,
+			PaymentKind::Spontaneous,
+			Some(1_000),
+			None,
+			PaymentKindDetails::Spontaneous { payment_hash: FAILED_KEYSEND.into(), preimage: None },
+		),
+		payment(
+			ONCHAIN,
+			PaymentDirection::Inbound,
+			PaymentStatus::Succeeded,
+			PaymentKind::Onchain,
+			Some(250_000_000_000),
+			None,
+			PaymentKindDetails::Onchain { txid: ONCHAIN.into() },
+		),
+		payment(
+			FUTURE_KIND,
+			PaymentDirection::Outbound,
+			PaymentStatus::Unknown("Retrying".into()),
+			PaymentKind::Unknown("Bolt13".into()),
+			None,
+			None,
+			PaymentKindDetails::Unknown(serde_json::json!({ "type": "Bolt13", "hops": 3 })),
+		),
+	];
+	s.events = VecDeque::from([
+		EventDto::PaymentReceived { payment_id: Some(id(PAID_IN)), amount_msat: 21_000_000 },
+		EventDto::ChannelReady { user_channel_id: id(READY_CHANNEL) },
+	]);
+	s
+}
+
+/// Stamps the fixture's payments an hour apart, the oldest first; the payment from a newer
+/// daemon has no timestamp.
+fn timestamped(s: &mut MockState) {
+	for (n, p) in s.payments.iter_mut().filter(|p| p.id.as_str() != FUTURE_KIND).enumerate() {
+		p.created_at = Some(CLOCK - 3600 * (5 - n as u64));
+	}
+}
+
+const CONFIG: &str = "current = \"node-a\"\n\n\
+	[contexts.node-a]\nurl = \"http://127.0.0.1:8501\"\n\n\
+	[contexts.node-b]\nurl = \"http://127.0.0.1:8502\"\n";
+
+struct Case {
+	name: &'static str,
+	args: Vec<String>,
+	setup: fn(&mut MockState),
+}
+
+fn case(name: &'static str, args: &[&str]) -> Case {
+	Case { name, args: args.iter().map(|a| a.to_string()).collect(), setup: |_| {} }
+}
+
+fn case_with(name: &'static str, args: &[&str], setup: fn(&mut MockState)) -> Case {
+	Case { setup, ..case(name, args) }
+}
+
+fn cases() -> Vec<Case> {
+	let invoice = remote_invoice();
+	let offer = remote_offer();
+	let refund = remote_refund();
+	vec![
+		case("ctx_ls", &["ctx", "ls"]),
+		case("ctx_show", &["ctx", "show"]),
+		case("ctx_add", &["ctx", "add", "node-c", "--url", "http://127.0.0.1:8503"]),
+		case("ctx_use", &["ctx", "use", "node-b"]),
+		case("ctx_rm", &["ctx", "rm", "node-a"]),
+		case("node_health", &["node", "health"]),
+		case("node_ready", &["node", "ready"]),
+		case_with("node_ready_not_ready", &["node", "ready"], |s| s.ready = false),
+		case("node_status", &["node", "status"]),
+		case_with("node_status_server_error", &["node", "status"], |s| {
+			s.faults.push(Fault::new("status", FaultAction::error(500, "node is stopping")));
+		}),
+		case("node_id", &["node", "id"]),
+		case("node_listen", &["node", "listen"]),
+		case("node_version", &["node", "version"]),
+		case("wallet_balance", &["wallet", "balance"]),
+		case("wallet_balance_sats", &["wallet", "balance", "--sats"]),
+		case("wallet_balance_unit_msat", &["--unit", "msat", "wallet", "balance"]),
+		case("wallet_balance_sats_and_unit", &["--unit", "btc", "wallet", "balance", "--sats"]),
+		case("wallet_balance_unit_after_sats", &["wallet", "balance", "--sats", "--unit", "btc"]),
+		case("wallet_address", &["wallet", "address"]),
+		case("wallet_sync", &["wallet", "sync"]),
+		case("peer_ls", &["peer", "ls"]),
+		case("peer_ls_no_truncate", &["--no-truncate", "peer", "ls"]),
+		case("peer_connect", &["peer", "connect", PEER_B, "10.0.0.2:9735", "--persist"]),
+		case("peer_disconnect", &["peer", "disconnect", PEER_B]),
+		case("channel_ls", &["channel", "ls"]),
+		case("channel_ls_unit_btc", &["--unit", "btc", "channel", "ls"]),
+		case(
+			"channel_open",
+			&[
+				"channel",
+				"open",
+				"--node-id",
+				PEER_B,
+				"--addr",
+				"10.0.0.2:9735",
+				"--amount-sats",
+				"250k sat",
+				"--push-msat",
+				"1000",
+				"--private",
+			],
+		),
+		case(
+			"channel_close",
+			&[
+				"channel",
+				"close",
+				"--user-channel-id",
+				READY_CHANNEL,
+				"--counterparty-node-id",
+				PEER_A,
+			],
+		),
+		case(
+			"channel_force_close",
+			&[
+				"--yes",
+				"channel",
+				"force-close",
+				"--user-channel-id",
+				PENDING_CHANNEL,
+				"--counterparty-node-id",
+				PEER_B,
+			],
+		),
+		case(
+			"pay_invoice_create",
+			&["pay", "invoice", "create", "--desc", "coffee", "--amount-msat", "21k sat"],
+		),
+		case("pay_invoice_create_var", &["pay", "invoice", "create", "--desc", "tip jar"]),
+		case("pay_invoice_pay", &["pay", "invoice", "pay", "--invoice", &invoice]),
+		case_with(
+			"pay_invoice_pay_failed",
+			&["pay", "invoice", "pay", "--invoice", &invoice],
+			|s| s.settings.payment_outcome = PaymentOutcome::Fail,
+		),
+		case(
+			"pay_offer_create",
+			&[
+				"pay",
+				"offer",
+				"create",
+				"--desc",
+				"sticker",
+				"--amount-msat",
+				"5000",
+				"--quantity",
+				"3",
+			],
+		),
+		case(
+			"pay_offer_create_var",
+			&["pay", "offer", "create", "--desc", "donations", "--no-expiry"],
+		),
+		case("pay_offer_decode", &["pay", "offer", "decode", &offer]),
+		case(
+			"pay_offer_pay",
+			&[
+				"pay",
+				"offer",
+				"pay",
+				"--offer",
+				&offer,
+				"--amount-msat",
+				"12345",
+				"--payer-note",
+				"hi",
+			],
+		),
+		case(
+			"pay_refund_initiate",
+			&["pay", "refund", "initiate", "--amount-msat", "75000", "--payer-note", "return"],
+		),
+		case("pay_refund_decode", &["pay", "refund", "decode", &refund]),
+		case("pay_refund_request_payment", &["pay", "refund", "request-payment", &refund]),
+		case(
+			"pay_keysend_send",
+			&[
+				"pay",
+				"keysend",
+				"send",
+				"--node-id",
+				PEER_A,
+				"--amount-msat",
+				"1 sat",
+				"--tlv",
+				"65537:cafe",
+			],
+		),
+		case("pay_ls", &["pay", "ls"]),
+		case("pay_ls_filtered", &["pay", "ls", "--status", "Succeeded", "--direction", "Inbound"]),
+		case("pay_ls_bad_status", &["pay", "ls", "--status", "paid"]),
+		case_with("pay_ls_page", &["pay", "ls", "--limit", "2"], timestamped),
+		case_with(
+			"pay_ls_next_page",
+			&["pay", "ls", "--limit", "2", "--cursor", ONCHAIN],
+			timestamped,
+		),
+		case_with(
+			"pay_ls_all",
+			&["pay", "ls", "--all", "--limit", "4", "--kind", "bolt11"],
+			timestamped,
+		),
+		case_with("pay_ls_since", &["pay", "ls", "--since", "1749992800"], timestamped),
+		case_with(
+			"pay_ls_page_without_list_route",
+			&["pay", "ls", "--limit", "2", "--status", "succeeded"],
+			|s| {
+				timestamped(s);
+				s.faults.push(Fault::new("payments_list", FaultAction::error(404, "not found")));
+			},
+		),
+		case("pay_ls_unit_sat", &["--unit", "sat", "pay", "ls"]),
+		case("pay_wait", &["pay", "wait", PAID_OUT]),
+		case("pay_wait_timeout", &["pay", "wait", PENDING_OFFER, "--timeout-secs", "1"]),
+		case("pay_abandon", &["pay", "abandon", PENDING_OFFER]),
+		case("pay_get", &["pay", "get", PAID_OUT]),
+		case("pay_get_offer", &["pay", "get", PENDING_OFFER]),
+		case("pay_get_onchain_btc", &["--unit", "btc", "pay", "get", ONCHAIN]),
+		case("pay_get_unknown_kind", &["pay", "get", FUTURE_KIND]),
+		case("pay_get_not_found", &["pay", "get", HASH]),
+		case("events_next", &["events", "next"]),
+		case_with("events_watch_channels", &["events", "watch", "--count", "2"], |s| {
+			s.events = VecDeque::from([
+				EventDto::ChannelPending {
+					funding_txo: OutPointDto { txid: ONCHAIN.into(), vout: 1 },
+				},
+				EventDto::ChannelClosed {
+					channel_id: id(&PAID_OUT.replace('1', "a")),
+					user_channel_id: id(READY_CHANNEL),
+					counterparty_node_id: Some(id(PEER_A)),
+					reason: Some("CounterpartyForceClosed".into()),
+				},
+			]);
+		}),
+		case("events_handled", &["events", "handled"]),
+		case("events_watch", &["events", "watch", "--count", "2"]),
+		case_with("events_watch_payments", &["events", "watch", "--count", "3"], |s| {
+			s.events = VecDeque::from([
+				EventDto::PaymentSuccessful {
+					payment_id: Some(id(PAID_OUT)),
+					fee_paid_msat: Some(1_234),
+				},
+				EventDto::PaymentFailed { payment_id: Some(id(FAILED_KEYSEND)) },
+				EventDto::Other {
+					kind: "Other".into(),
+					data: serde_json::json!({ "kind": "SpliceLocked" }),
+				},
+			]);
+		}),
+		case_with("events_watch_unknown_kind", &["events", "watch", "--count", "2"], |s| {
+			s.events = VecDeque::from([
+				EventDto::Other {
+					kind: "SpliceLocked".into(),
+					data: serde_json::json!({
+						"channel_id": PAID_OUT.replace('1', "a"),
+						"new_funding_txo": { "txid": ONCHAIN, "vout": 0 },
+						"splice_id": 7,
+					}),
+				},
+				EventDto::Other { kind: "Heartbeat".into(), data: serde_json::Value::Null },
+			]);
+		}),
+	]
+}
+
+fn snapshot_dir() -> PathBuf {
+	Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots")
+}
+
+fn cassette_dir() -> PathBuf {
+	Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/cassettes")
+}
+
+/// The daemon URL the cassettes are replayed with, and shown in place of the recording one.
+const DAEMON_URL: &str = "http://mock";
+
+/// Runs `rgbldk` with `args` in a fresh home holding [`CONFIG`].
+fn rgbldk(home: &Path, url: &str, args: &[String]) -> Output {
+	let config = home.join("config/rgbldk/config.toml");
+	std::fs::create_dir_all(config.parent().unwrap()).unwrap();
+	std::fs::write(&config, CONFIG).unwrap();
+	Command::new(env!("CARGO_BIN_EXE_rgbldk"))
+		.args(args)
+		.env("RGBLDK_CONNECT", url)
+		.env("XDG_CONFIG_HOME", home.join("config"))
+		.env("XDG_CACHE_HOME", home.join("cache"))
+		.env_remove("RGBLDK_URL")
+		.env_remove("RGBLDK_CTX")
+		.output()
+		.unwrap()
+}
+
+/// Records one run of `case` against a fresh mock into `cassette`.
+async fn record(case: &Case, home: &Path, cassette: &Path, args: &[String]) {
+	let mut state = fixture();
+	(case.setup)(&mut state);
+	let server = MockServer::bind(([127, 0, 0, 1], 0).into(), state).await.unwrap();
+	let _ = std::fs::remove_dir_all(cassette);
+	std::fs::create_dir_all(cassette).unwrap();
+	let flag = ["--record".to_string(), cassette.display().to_string()];
+	rgbldk(home, &server.url(), &[&flag[..], args].concat());
+	server.shutdown().await.unwrap();
+	let _ = std::fs::remove_dir_all(home);
+	// A command that never reached the daemon leaves nothing to replay.
+	let _ = std::fs::remove_dir(cassette);
+}
+
+/// Runs one case in both output modes and renders the snapshot text.
+async fn render(case: &Case, recording: bool) -> String {
+	let mut out = String::new();
+	for output in ["text", "json"] {
+		let home = std::env::temp_dir().join(format!(
+			"rgbldk-snapshots-{}-{}-{output}",
+			std::process::id(),
+			case.name
+		));
+		let cassette = cassette_dir().join(case.name).join(output);
+		let mut args =
+			vec!["--output".to_string(), output.into(), "--color".into(), "never".into()];
+		args.extend(case.args.iter().cloned());
+		if recording {
+			record(case, &home, &cassette, &args).await;
+		}
+
+		let replay = ["--replay".to_string(), cassette.display().to_string()];
+		let run = rgbldk(&home, DAEMON_URL, &[&replay[..], &args].concat());
+
+		let shown: Vec<String> = args
+			.iter()
+			.map(|a| if a.contains(' ') { format!("'{a}'") } else { a.clone() })
+			.collect();
+		let _ = writeln!(out, "$ rgbldk {}", shown.join(" "));
+		out.push_str(&String::from_utf8_lossy(&run.stdout));
+		let stderr = String::from_utf8_lossy(&run.stderr);
+		if !stderr.is_empty() {
+			let _ = write!(out, "[stderr]\n{stderr}");
+		}
+		let code = run.status.code().unwrap_or(-1);
+		if code != 0 {
+			let _ = writeln!(out, "[exit {code}]");
+		}
+		out.push('\n');
+		let _ = std::fs::remove_dir_all(&home);
+	}
+	out
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cli_output_matches_snapshots() {
+	let dir = snapshot_dir();
+	let recording = std::env::var_os("RECORD_CASSETTES").is_some();
+	let update = recording || std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+	let cases = cases();
+	let mut stale = Vec::new();
+	for case in &cases {
+		let path = dir.join(format!("{}.txt", case.name));
+		let actual = render(case, recording).await;
+		if update {
+			std::fs::create_dir_all(&dir).unwrap();
+			std::fs::write(&path, &actual).unwrap();
+		} else if std::fs::read_to_string(&path).ok().as_deref() != Some(actual.as_str()) {
+			eprintln!("--- {} (actual)\n{actual}", path.display());
+			stale.push(case.name);
+		}
+	}
+
+	let known: BTreeSet<String> = cases.iter().map(|c| format!("{}.txt", c.name)).collect();
+	let orphans: Vec<String> = std::fs::read_dir(&dir)
+		.into_iter()
+		.flatten()
+		.filter_map(|e| e.ok()?.file_name().into_string().ok())
+		.filter(|name| !known.contains(name))
+		.collect();
+	for orphan in &orphans {
+		if update {
+			std::fs::remove_file(dir.join(orphan)).unwrap();
+		}
+	}
+	assert!(
+		stale.is_empty() && (update || orphans.is_empty()),
+		"snapshots differ: {stale:?}, without a case: {orphans:?}; review the output above and \
+		 regenerate with `UPDATE_SNAPSHOTS=1 cargo test -p rgbldk-cli --test snapshots`"
+	);
+}
@@ -0,0 +1,117 @@
+//! Scripted failure injection.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// A scripted failure, matched against `/api/v1` requests by operation id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fault {
+	/// Operation id to match (see `rgbldk_api::routes::ROUTES`); `None` matches every route.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub operation: Option<String>,
+	/// What happens to a matching request.
+	pub action: FaultAction,
+	/// Number of matching requests to affect; `None` keeps the fault until it is cleared.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub times: Option<u32>,
+}
+
+/// Effect of a [`Fault`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FaultAction {
+	/// Answer with `status` and `{ "error": message }` instead of running the handler.
+	Error {
+		/// HTTP status code.
+		status: u16,
+		/// Error message.
+		message: String,
+	},
+	/// Hold the request for `ms` milliseconds, then handle it (or apply the next fault).
+	Delay {
+		/// Delay in milliseconds.
+		ms: u64,
+	},
+	/// Answer `200` with a body that is not valid JSON.
+	Garbage,
+}
+
+impl Fault {
+	/// A fault that applies to every request for `operation` until cleared.
+	pub fn new(operation: impl Into<String>, action: FaultAction) -> Self {
+		Self { operation: Some(operation.into()), action, times: None }
+	}
+
+	/// Limits the fault to the next `n` matching requests.
+	pub fn times(mut self, n: u32) -> Self {
+		self.times = Some(n);
+		self
+	}
+
+	pub(crate) fn matches(&self, operation: &str) -> bool {
+		self.operation.as_deref().is_none_or(|op| op == operation)
+	}
+}
+
+impl FaultAction {
+	/// An error answer with the given status and message.
+	pub fn error(status: u16, message: impl Into<String>) -> Self {
+		FaultAction::Error { status, message: message.into() }
+	}
+}
+
+/// Parses `[OPERATION=]ACTION[*TIMES]`, where `ACTION` is `STATUS[:MESSAGE]`, `delay:MS` or
+/// `garbage`; e.g. `bolt11_pay=500:route not found*2` or `delay:250`.
+impl FromStr for Fault {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (operation, rest) = match s.split_once('=') {
+			Some((op, rest)) => (Some(op.trim().to_string()), rest),
+			None => (None, s),
+		};
+		let (action, times) = match rest.rsplit_once('*') {
+			Some((action, n)) => {
+				let n = n.trim().parse().map_err(|_| format!("invalid repeat count {n:?}"))?;
+				(action, Some(n))
+			},
+			None => (rest, None),
+		};
+		let action = action.trim();
+		let action = if action == "garbage" {
+			FaultAction::Garbage
+		} else if let Some(ms) = action.strip_prefix("delay:") {
+			let ms = ms.trim().parse().map_err(|_| format!("invalid delay {ms:?}"))?;
+			FaultAction::Delay { ms }
+		} else {
+			let (status, message) = action.split_once(':').unwrap_or((action, "injected fault"));
+			let status: u16 = status
+				.trim()
+				.parse()
+				.ok()
+				.filter(|s| (100..600).contains(s))
+				.ok_or_else(|| format!("invalid fault action {action:?}"))?;
+			FaultAction::error(status, message.trim())
+		};
+		Ok(Fault { operation, action, times })
+	}
+}
+
+impl fmt::Display for Fault {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if let Some(op) = &self.operation {
+			write!(f, "{op}=")?;
+		}
+		match &self.action {
+			FaultAction::Error { status, message } => write!(f, "{status}:{message}")?,
+			FaultAction::Delay { ms } => write!(f, "delay:{ms}")?,
+			FaultAction::Garbage => f.write_str("garbage")?,
+		}
+		if let Some(n) = self.times {
+			write!(f, "*{n}")?;
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,116 @@
+#![deny(missing_docs)]
+
+//! In-process mock of the `rgbldkd` HTTP API, for running `rgbldk` and API clients end to end
+//! without bitcoind, esplora or a Lightning node.
+//!
+//! [`MockServer`] serves every route in [`rgbldk_api::routes::ROUTES`] from an in-memory
+//! [`MockState`]: peers, channels, payments and the event queue, with the daemon's ACK model
+//! (`/events/wait_next` returns the head, `/events/handled` removes it). Scenarios are scripted
+//! either in-process ([`MockServer::update`]) or over HTTP through the `/mock/*` control routes,
+//! and [`Fault`]s make chosen routes fail, stall or answer garbage.
+
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use rgbldk_api::http::EventDto;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// Scripted failure injection.
+pub mod fault;
+
+mod server;
+
+/// In-memory node state behind the mock routes.
+pub mod state;
+
+pub use fault::{Fault, FaultAction};
+pub use state::{MockState, PaymentOutcome, Rejection, Settings};
+
+use server::Shared;
+
+/// A running mock daemon. Dropping it stops the server.
+pub struct MockServer {
+	addr: SocketAddr,
+	shared: Arc<Shared>,
+	task: Option<JoinHandle<io::Result<()>>>,
+}
+
+impl MockServer {
+	/// Starts a mock with default state on a free loopback port.
+	pub async fn start() -> io::Result<Self> {
+		Self::bind(SocketAddr::from(([127, 0, 0, 1], 0)), MockState::default()).await
+	}
+
+	/// Starts a mock with `state` on `addr`.
+	pub async fn bind(addr: SocketAddr, state: MockState) -> io::Result<Self> {
+		let listener = TcpListener::bind(addr).await?;
+		let addr = listener.local_addr()?;
+		let shared = Arc::new(Shared::new(state));
+		let app = server::router(shared.clone());
+		let signal = shared.clone();
+		let task = tokio::spawn(async move {
+			axum::serve(listener, app)
+				.with_graceful_shutdown(async move { signal.closed().await })
+				.await
+		});
+		Ok(Self { addr, shared, task: Some(task) })
+	}
+
+	/// Address the mock listens on.
+	pub fn addr(&self) -> SocketAddr {
+		self.addr
+	}
+
+	/// Base URL to pass as `--connect` or to `RgbLdkClient::new`.
+	pub fn url(&self) -> String {
+		format!("http://{}", self.addr)
+	}
+
+	/// Reads the state.
+	pub fn read<R>(&self, f: impl FnOnce(&MockState) -> R) -> R {
+		self.shared.read(f)
+	}
+
+	/// Edits the state; pending long-polls (`wait_next`, payment waits) see the change.
+	pub fn update<R>(&self, f: impl FnOnce(&mut MockState) -> R) -> R {
+		self.shared.update(f)
+	}
+
+	/// Appends an event to the queue.
+	pub fn push_event(&self, event: EventDto) {
+		self.update(|s| s.push_event(event));
+	}
+
+	/// Adds a fault after the existing ones.
+	pub fn inject(&self, fault: Fault) {
+		self.update(|s| s.faults.push(fault));
+	}
+
+	/// Removes every fault.
+	pub fn clear_faults(&self) {
+		self.update(|s| s.faults.clear());
+	}
+
+	/// Mines `blocks` blocks, confirming pending channels (see [`MockState::mine`]).
+	pub fn mine(&self, blocks: u32) {
+		self.update(|s| s.mine(blocks));
+	}
+
+	/// Stops accepting connections, answers pending long-polls with `503` and waits for the
+	/// server to finish.
+	pub async fn shutdown(mut self) -> io::Result<()> {
+		self.shared.close();
+		match self.task.take() {
+			Some(task) => task.await.map_err(io::Error::other)?,
+			None => Ok(()),
+		}
+	}
+}
+
+impl Drop for MockServer {
+	fn drop(&mut self) {
+		self.shared.close();
+	}
+}
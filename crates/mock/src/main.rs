@@ -0,0 +1,55 @@
+use std::net::SocketAddr;
+
+use clap::Parser;
+use rgbldk_api::ids::NodeId;
+use rgbldk_mock::{Fault, MockServer, MockState};
+
+#[derive(Parser, Debug)]
+#[command(
+	name = "rgbldkd-mock",
+	about = "In-memory stand-in for the rgbldkd HTTP API (no bitcoind or Lightning node needed)"
+)]
+struct Args {
+	/// Address to listen on.
+	#[arg(long, default_value = "127.0.0.1:8500")]
+	listen: SocketAddr,
+
+	/// Node id to report (default: a fixed mock id).
+	#[arg(long)]
+	node_id: Option<NodeId>,
+
+	/// Start with `/readyz` answering 503 (flip it with `POST /mock/ready`).
+	#[arg(long)]
+	not_ready: bool,
+
+	/// Inject a fault: `[OPERATION=]STATUS[:MESSAGE]|delay:MS|garbage[*TIMES]` (repeatable),
+	/// e.g. `--fault 'bolt11_pay=500:no route*1'`.
+	#[arg(long = "fault", value_name = "SPEC")]
+	faults: Vec<Fault>,
+}
+
+#[tokio::main]
+async fn main() {
+	let args = Args::parse();
+	let mut state = match args.node_id {
+		Some(node_id) => MockState::new(node_id),
+		None => MockState::default(),
+	};
+	state.ready = !args.not_ready;
+	state.faults = args.faults;
+
+	let server = match MockServer::bind(args.listen, state).await {
+		Ok(server) => server,
+		Err(e) => {
+			eprintln!("cannot listen on {}: {e}", args.listen);
+			std::process::exit(1);
+		},
+	};
+	println!("rgbldkd-mock listening on {}", server.url());
+
+	let _ = tokio::signal::ctrl_c().await;
+	if let Err(e) = server.shutdown().await {
+		eprintln!("shutdown failed: {e}");
+		std::process::exit(1);
+	}
+}
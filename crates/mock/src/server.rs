@@ -0,0 +1,654 @@
+//! Axum routes: the `/api/v1` surface plus the `/mock` control surface.
+
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::body::Bytes;
+use axum::extract::{FromRequest, MatchedPath, Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::sse::{Event as SseEvent, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures_util::stream::{self, Stream};
+use rgbldk_api::http::*;
+use rgbldk_api::ids::PaymentId;
+use rgbldk_api::routes::ROUTES;
+use rgbldk_api::API_VERSION;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+use crate::fault::{Fault, FaultAction};
+use crate::state::{MockState, Rejection, Settings};
+
+/// Default `/payment/{id}/wait` timeout, as in the daemon.
+const DEFAULT_WAIT_SECS: u32 = 60;
+
+/// State shared between the handlers and [`crate::MockServer`].
+pub(crate) struct Shared {
+	state: Mutex<MockState>,
+	/// Bumped after every mutation; long-polls re-check their condition when it changes.
+	changed: watch::Sender<u64>,
+	closed: AtomicBool,
+}
+
+impl Shared {
+	pub(crate) fn new(state: MockState) -> Self {
+		Self {
+			state: Mutex::new(state),
+			changed: watch::Sender::new(0),
+			closed: AtomicBool::new(false),
+		}
+	}
+
+	pub(crate) fn read<R>(&self, f: impl FnOnce(&MockState) -> R) -> R {
+		f(&self.state.lock().unwrap_or_else(|e| e.into_inner()))
+	}
+
+	pub(crate) fn update<R>(&self, f: impl FnOnce(&mut MockState) -> R) -> R {
+		let out = f(&mut self.state.lock().unwrap_or_else(|e| e.into_inner()));
+		self.changed.send_modify(|n| *n = n.wrapping_add(1));
+		out
+	}
+
+	/// Wakes every long-poll with a shutdown answer so graceful shutdown can finish.
+	pub(crate) fn close(&self) {
+		self.closed.store(true, Ordering::SeqCst);
+		self.changed.send_modify(|n| *n = n.wrapping_add(1));
+	}
+
+	fn is_closed(&self) -> bool {
+		self.closed.load(Ordering::SeqCst)
+	}
+
+	pub(crate) async fn closed(&self) {
+		let mut rx = self.changed.subscribe();
+		while !self.is_closed() {
+			if rx.changed().await.is_err() {
+				return;
+			}
+		}
+	}
+
+	/// Re-evaluates `f` after every state change until it yields, the deadline passes or the
+	/// server shuts down.
+	async fn wait_for<R>(
+		&self, timeout: Option<Duration>, mut f: impl FnMut(&MockState) -> Option<R>,
+	) -> Result<R, ApiErr> {
+		let mut rx = self.changed.subscribe();
+		let deadline = timeout.map(|t| Instant::now() + t);
+		loop {
+			if let Some(out) = self.read(&mut f) {
+				return Ok(out);
+			}
+			if self.is_closed() {
+				return Err(ApiErr::new(StatusCode::SERVICE_UNAVAILABLE, "shutting down"));
+			}
+			let changed = match deadline {
+				Some(deadline) => tokio::time::timeout_at(deadline, rx.changed()).await,
+				None => Ok(rx.changed().await),
+			};
+			match changed {
+				Ok(Ok(())) => {},
+				Ok(Err(_)) => {
+					return Err(ApiErr::new(StatusCode::SERVICE_UNAVAILABLE, "shutting down"));
+				},
+				Err(_) => return Err(ApiErr::bad_request("timed out")),
+			}
+		}
+	}
+}
+
+type AppState = State<Arc<Shared>>;
+
+/// Error answer in the daemon's `{ "error": "..." }` shape (plus extra fields where the daemon
+/// adds them).
+pub(crate) struct ApiErr {
+	status: StatusCode,
+	body: Value,
+}
+
+impl ApiErr {
+	fn new(status: StatusCode, msg: impl Into<String>) -> Self {
+		Self { status, body: json!({ "error": msg.into() }) }
+	}
+
+	fn bad_request(msg: impl Into<String>) -> Self {
+		Self::new(StatusCode::BAD_REQUEST, msg)
+	}
+}
+
+impl From<Rejection> for ApiErr {
+	fn from(r: Rejection) -> Self {
+		match r {
+			Rejection::BadRequest(msg) => ApiErr::bad_request(msg),
+			Rejection::NotFound => ApiErr::new(StatusCode::NOT_FOUND, "not found"),
+			Rejection::Conflict(msg) => ApiErr::new(StatusCode::CONFLICT, msg),
+		}
+	}
+}
+
+impl IntoResponse for ApiErr {
+	fn into_response(self) -> Response {
+		(self.status, Json(self.body)).into_response()
+	}
+}
+
+type ApiResult<T> = Result<Json<T>, ApiErr>;
+
+/// JSON request body; malformed bodies get a `400` in the daemon's error shape. An empty body
+/// reads as `{}`.
+struct Body<T>(T);
+
+impl<S: Send + Sync, T: DeserializeOwned> FromRequest<S> for Body<T> {
+	type Rejection = ApiErr;
+
+	async fn from_request(req: Request, state: &S) -> Result<Self, ApiErr> {
+		let bytes = Bytes::from_request(req, state)
+			.await
+			.map_err(|e| ApiErr::bad_request(e.body_text()))?;
+		let bytes: &[u8] = if bytes.is_empty() { b"{}" } else { &bytes };
+		serde_json::from_slice(bytes)
+			.map(Body)
+			.map_err(|e| ApiErr::bad_request(format!("invalid request body: {e}")))
+	}
+}
+
+fn parse_payment_id(id: &str) -> Result<PaymentId, ApiErr> {
+	id.parse().map_err(|e: rgbldk_api::ids::IdParseError| ApiErr::bad_request(e.to_string()))
+}
+
+fn ok() -> Json<OkResponse> {
+	Json(OkResponse { ok: true, checks: vec![] })
+}
+
+pub(crate) fn router(shared: Arc<Shared>) -> Router {
+	let api = Router::new()
+		.route("/healthz", get(healthz))
+		.route("/readyz", get(readyz))
+		.route("/version", get(version))
+		.route("/status", get(status))
+		.route("/node_id", get(node_id))
+		.route("/listening_addresses", get(listening_addresses))
+		.route("/wallet/new_address", post(wallet_new_address))
+		.route("/wallet/sync", post(wallet_sync))
+		.route("/balances", get(balances))
+		.route("/peers", get(peers))
+		.route("/peers/connect", post(peer_connect))
+		.route("/peers/disconnect", post(peer_disconnect))
+		.route("/channels", get(channels))
+		.route("/channel/open", post(channel_open))
+		.route("/channel/close", post(channel_close))
+		.route("/channel/force_close", post(channel_force_close))
+		.route("/bolt11/decode", post(bolt11_decode))
+		.route("/bolt11/receive", post(bolt11_receive))
+		.route("/bolt11/receive_var", post(bolt11_receive_var))
+		.route("/bolt11/send", post(bolt11_send))
+		.route("/bolt11/send_using_amount", post(bolt11_send_using_amount))
+		.route("/bolt11/pay", post(bolt11_pay))
+		.route("/bolt12/offer/receive", post(bolt12_offer_receive))
+		.route("/bolt12/offer/receive_var", post(bolt12_offer_receive_var))
+		.route("/bolt12/offer/decode", post(bolt12_offer_decode))
+		.route("/bolt12/offer/send", post(bolt12_offer_send))
+		.route("/bolt12/refund/initiate", post(bolt12_refund_initiate))
+		.route("/bolt12/refund/decode", post(bolt12_refund_decode))
+		.route("/bolt12/refund/request_payment", post(bolt12_refund_request_payment))
+		.route("/spontaneous/send", post(spontaneous_send))
+		.route("/payments", get(payments))
+		.route("/payments/list", post(payments_list))
+		.route("/payment/{payment_id}", get(payment))
+		.route("/payment/{payment_id}/wait", post(payment_wait))
+		.route("/payment/{payment_id}/abandon", post(payment_abandon))
+		.route("/events/wait_next", post(events_wait_next))
+		.route("/events/handled", post(events_handled))
+		.route("/events/stream", get(events_stream))
+		.route_layer(middleware::from_fn_with_state(shared.clone(), inject_faults));
+
+	let control = Router::new()
+		.route("/state", get(mock_state))
+		.route("/events", post(mock_push_event))
+		.route("/faults", post(mock_add_fault).delete(mock_clear_faults))
+		.route("/settings", post(mock_settings))
+		.route("/ready", post(mock_ready))
+		.route("/mine", post(mock_mine))
+		.route("/receive", post(mock_receive))
+		.route("/payments/{payment_id}/resolve", post(mock_resolve_payment));
+
+	Router::new()
+		.nest(&format!("/api/{API_VERSION}"), api)
+		.nest("/mock", control)
+		.fallback(|| async { ApiErr::new(StatusCode::NOT_FOUND, "not found") })
+		.with_state(shared)
+}
+
+/// Applies the scripted faults of the matched route before its handler runs.
+async fn inject_faults(
+	State(shared): AppState, matched: MatchedPath, req: Request, next: Next,
+) -> Response {
+	let prefix = format!("/api/{API_VERSION}");
+	let path = matched.as_str().strip_prefix(&prefix).unwrap_or(matched.as_str());
+	let method = req.method().as_str();
+	let Some(route) = ROUTES.iter().find(|r| r.path == path && r.method.as_str() == method) else {
+		return next.run(req).await;
+	};
+	for action in shared.update(|s| s.take_faults(route.operation_id)) {
+		match action {
+			FaultAction::Delay { ms } => tokio::time::sleep(Duration::from_millis(ms)).await,
+			FaultAction::Error { status, message } => {
+				let status =
+					StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+				return ApiErr::new(status, message).into_response();
+			},
+			FaultAction::Garbage => {
+				return ([(header::CONTENT_TYPE, "application/json")], "{\"truncated\":")
+					.into_response();
+			},
+		}
+	}
+	next.run(req).await
+}
+
+// ---- node ----
+
+async fn healthz() -> Json<OkResponse> {
+	ok()
+}
+
+async fn readyz(State(shared): AppState) -> (StatusCode, Json<OkResponse>) {
+	let ready = shared.read(|s| s.ready);
+	let check = HealthCheckDto {
+		name: "node_is_running".into(),
+		ok: ready,
+		detail: (!ready).then(|| "node is starting".into()),
+		hint: None,
+	};
+	let status = if ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+	(status, Json(OkResponse { ok: ready, checks: vec![check] }))
+}
+
+async fn version(State(shared): AppState) -> Json<VersionResponse> {
+	Json(shared.read(|s| s.version.clone()))
+}
+
+async fn status(State(shared): AppState) -> Json<StatusDto> {
+	Json(shared.read(MockState::status))
+}
+
+async fn node_id(State(shared): AppState) -> Json<NodeIdResponse> {
+	Json(NodeIdResponse { node_id: shared.read(|s| s.node_id.clone()) })
+}
+
+async fn listening_addresses(State(shared): AppState) -> Json<ListeningAddressesResponse> {
+	Json(ListeningAddressesResponse { addresses: shared.read(|s| s.listening_addresses.clone()) })
+}
+
+// ---- wallet ----
+
+async fn wallet_new_address(State(shared): AppState) -> Json<WalletNewAddressResponse> {
+	Json(WalletNewAddressResponse { address: shared.update(MockState::new_address) })
+}
+
+async fn wallet_sync() -> Json<OkResponse> {
+	ok()
+}
+
+async fn balances(State(shared): AppState) -> Json<BalancesDto> {
+	Json(shared.read(MockState::balances))
+}
+
+// ---- peers ----
+
+async fn peers(State(shared): AppState) -> Json<Vec<PeerDetailsDto>> {
+	Json(shared.read(|s| s.peers.clone()))
+}
+
+async fn peer_connect(
+	State(shared): AppState, Body(req): Body<PeerConnectRequest>,
+) -> ApiResult<OkResponse> {
+	shared.update(|s| s.connect_peer(req))?;
+	Ok(ok())
+}
+
+async fn peer_disconnect(
+	State(shared): AppState, Body(req): Body<PeerDisconnectRequest>,
+) -> ApiResult<OkResponse> {
+	shared.update(|s| s.disconnect_peer(&req.node_id))?;
+	Ok(ok())
+}
+
+// ---- channels ----
+
+async fn channels(State(shared): AppState) -> Json<Vec<ChannelDetailsExtendedDto>> {
+	Json(shared.read(|s| s.channels.clone()))
+}
+
+async fn channel_open(
+	State(shared): AppState, Body(req): Body<OpenChannelRequest>,
+) -> ApiResult<OpenChannelResponse> {
+	let user_channel_id = shared.update(|s| s.open_channel(req))?;
+	Ok(Json(OpenChannelResponse { user_channel_id }))
+}
+
+async fn channel_close(
+	State(shared): AppState, Body(req): Body<CloseChannelRequest>,
+) -> ApiResult<OkResponse> {
+	shared.update(|s| s.close_channel(&req, false))?;
+	Ok(ok())
+}
+
+async fn channel_force_close(
+	State(shared): AppState, Body(req): Body<CloseChannelRequest>,
+) -> ApiResult<OkResponse> {
+	shared.update(|s| s.close_channel(&req, true))?;
+	Ok(ok())
+}
+
+// ---- BOLT11 ----
+
+async fn bolt11_decode(
+	State(shared): AppState, Body(req): Body<Bolt11DecodeRequest>,
+) -> ApiResult<Bolt11DecodeResponse> {
+	Ok(Json(shared.read(|s| s.decode_invoice(&req.invoice))?))
+}
+
+async fn bolt11_receive(
+	State(shared): AppState, Body(req): Body<Bolt11ReceiveRequest>,
+) -> ApiResult<Bolt11ReceiveResponse> {
+	let invoice = shared
+		.update(|s| s.create_invoice(Some(req.amount_msat), req.description, req.expiry_secs))?;
+	Ok(Json(Bolt11ReceiveResponse { invoice }))
+}
+
+async fn bolt11_receive_var(
+	State(shared): AppState, Body(req): Body<Bolt11ReceiveVarRequest>,
+) -> ApiResult<Bolt11ReceiveResponse> {
+	let invoice = shared.update(|s| s.create_invoice(None, req.description, req.expiry_secs))?;
+	Ok(Json(Bolt11ReceiveResponse { invoice }))
+}
+
+async fn bolt11_send(
+	State(shared): AppState, Body(req): Body<Bolt11SendRequest>,
+) -> ApiResult<SendResponse> {
+	let payment_id = shared.update(|s| s.pay_invoice(&req.invoice, None))?;
+	Ok(Json(SendResponse { payment_id }))
+}
+
+async fn bolt11_send_using_amount(
+	State(shared): AppState, Body(req): Body<Bolt11SendUsingAmountRequest>,
+) -> ApiResult<SendResponse> {
+	let payment_id = shared.update(|s| s.pay_invoice(&req.invoice, Some(req.amount_msat)))?;
+	Ok(Json(SendResponse { payment_id }))
+}
+
+async fn bolt11_pay(
+	State(shared): AppState, Body(req): Body<Bolt11PayRequest>,
+) -> ApiResult<Bolt11PayResponse> {
+	let destination = shared.read(|s| s.decode_invoice(&req.invoice))?.destination;
+	let payment_id = shared.update(|s| s.pay_invoice(&req.invoice, req.amount_msat))?;
+	let payment = wait_terminal(&shared, &payment_id, DEFAULT_WAIT_SECS).await?;
+	if payment.status != PaymentStatus::Succeeded {
+		return Err(ApiErr::bad_request(format!("payment {payment_id} failed")));
+	}
+	let preimage = payment.kind_details.as_ref().and_then(|d| d.preimage()).unwrap_or_default();
+	Ok(Json(Bolt11PayResponse {
+		preimage: preimage.to_string(),
+		amount_sats: payment.amount_msat.unwrap_or(0) / 1000,
+		destination,
+		fee_paid_msat: payment.fee_paid_msat,
+		payment_id,
+	}))
+}
+
+// ---- BOLT12 ----
+
+async fn bolt12_offer_receive(
+	State(shared): AppState, Body(req): Body<Bolt12OfferReceiveRequest>,
+) -> ApiResult<Bolt12OfferResponse> {
+	let offer = shared.update(|s| {
+		s.create_offer(Some(req.amount_msat), req.description, req.expiry_secs, req.quantity)
+	})?;
+	Ok(Json(Bolt12OfferResponse { offer }))
+}
+
+async fn bolt12_offer_receive_var(
+	State(shared): AppState, Body(req): Body<Bolt12OfferReceiveVarRequest>,
+) -> ApiResult<Bolt12OfferResponse> {
+	let offer = shared.update(|s| s.create_offer(None, req.description, req.expiry_secs, None))?;
+	Ok(Json(Bolt12OfferResponse { offer }))
+}
+
+async fn bolt12_offer_decode(
+	State(shared): AppState, Body(req): Body<Bolt12OfferDecodeRequest>,
+) -> ApiResult<Bolt12OfferDecodeResponse> {
+	Ok(Json(shared.read(|s| s.decode_offer(&req.offer))?))
+}
+
+async fn bolt12_offer_send(
+	State(shared): AppState, Body(req): Body<Bolt12OfferSendRequest>,
+) -> ApiResult<SendResponse> {
+	let payment_id = shared.update(|s| s.pay_offer(req))?;
+	Ok(Json(SendResponse { payment_id }))
+}
+
+async fn bolt12_refund_initiate(
+	State(shared): AppState, Body(req): Body<Bolt12RefundInitiateRequest>,
+) -> ApiResult<Bolt12RefundInitiateResponse> {
+	Ok(Json(shared.update(|s| s.initiate_refund(req))?))
+}
+
+async fn bolt12_refund_decode(
+	State(shared): AppState, Body(req): Body<Bolt12RefundDecodeRequest>,
+) -> ApiResult<Bolt12RefundDecodeResponse> {
+	Ok(Json(shared.read(|s| s.decode_refund(&req.refund))?))
+}
+
+async fn bolt12_refund_request_payment(
+	State(shared): AppState, Body(req): Body<Bolt12RefundRequestPaymentRequest>,
+) -> ApiResult<Bolt12RefundRequestPaymentResponse> {
+	Ok(Json(shared.update(|s| s.request_refund_payment(&req.refund))?))
+}
+
+async fn spontaneous_send(
+	State(shared): AppState, Body(req): Body<SpontaneousSendRequest>,
+) -> ApiResult<SendResponse> {
+	let payment_id = shared.update(|s| s.keysend(&req))?;
+	Ok(Json(SendResponse { payment_id }))
+}
+
+// ---- payments ----
+
+async fn payments(State(shared): AppState) -> Json<Vec<PaymentDetailsDto>> {
+	Json(shared.read(|s| s.payments.clone()))
+}
+
+async fn payments_list(
+	State(shared): AppState, Body(req): Body<PaymentsListRequest>,
+) -> ApiResult<PaymentsPage> {
+	Ok(Json(shared.read(|s| s.list_payments(&req))?))
+}
+
+async fn payment(
+	State(shared): AppState, Path(payment_id): Path<String>,
+) -> ApiResult<PaymentDetailsDto> {
+	let id = parse_payment_id(&payment_id)?;
+	Ok(Json(shared.read(|s| s.payment(&id).cloned())?))
+}
+
+async fn wait_terminal(
+	shared: &Shared, id: &PaymentId, timeout_secs: u32,
+) -> Result<PaymentDetailsDto, ApiErr> {
+	shared.read(|s| s.payment(id).map(drop))?;
+	let timeout = Duration::from_secs(timeout_secs.into());
+	shared
+		.wait_for(Some(timeout), |s| {
+			s.payment(id).ok().filter(|p| p.status != PaymentStatus::Pending).cloned()
+		})
+		.await
+}
+
+async fn payment_wait(
+	State(shared): AppState, Path(payment_id): Path<String>, Body(req): Body<PaymentWaitRequest>,
+) -> ApiResult<PaymentWaitResponse> {
+	let id = parse_payment_id(&payment_id)?;
+	let timeout_secs = req.timeout_secs.unwrap_or(DEFAULT_WAIT_SECS);
+	match wait_terminal(&shared, &id, timeout_secs).await {
+		Ok(payment) => {
+			let ok = payment.status == PaymentStatus::Succeeded;
+			let check = HealthCheckDto {
+				name: "payment_status".into(),
+				ok,
+				detail: Some(payment.status.to_string()),
+				hint: None,
+			};
+			Ok(Json(PaymentWaitResponse { ok, payment, checks: vec![check] }))
+		},
+		Err(e) if e.status == StatusCode::BAD_REQUEST => {
+			let payment = shared.read(|s| s.payment(&id).cloned())?;
+			let check = HealthCheckDto {
+				name: "payment_status".into(),
+				ok: false,
+				detail: Some(format!("still {} after {timeout_secs}s", payment.status)),
+				hint: Some("retry with a longer --timeout-secs".into()),
+			};
+			Err(ApiErr {
+				status: StatusCode::BAD_REQUEST,
+				body: json!({
+					"error": "timed out waiting for payment",
+					"payment": payment,
+					"checks": [check],
+				}),
+			})
+		},
+		Err(e) => Err(e),
+	}
+}
+
+async fn payment_abandon(
+	State(shared): AppState, Path(payment_id): Path<String>,
+) -> ApiResult<OkResponse> {
+	let id = parse_payment_id(&payment_id)?;
+	shared.update(|s| s.abandon_payment(&id))?;
+	Ok(ok())
+}
+
+// ---- events ----
+
+async fn events_wait_next(State(shared): AppState) -> ApiResult<QueuedEvent> {
+	let head = shared.wait_for(None, |s| s.sequenced_events().next()).await?;
+	Ok(Json(QueuedEvent { id: Some(head.seq), event: head.event }))
+}
+
+/// Removes the head of the queue; like the daemon, an ACK with nothing queued is a no-op. With an
+/// `id`, only that event is removed (see [`MockState::ack_event_seq`]).
+async fn events_handled(
+	State(shared): AppState, Body(req): Body<EventAckRequest>,
+) -> ApiResult<OkResponse> {
+	match req.id {
+		Some(id) => shared.update(|s| s.ack_event_seq(id))?,
+		None => shared.update(|s| s.ack_event()),
+	}
+	Ok(ok())
+}
+
+/// Pushes every queued event from the head on, then each new one as it is queued. Nothing is
+/// removed until it is acknowledged, so a new stream starts over at the head.
+async fn events_stream(
+	State(shared): AppState,
+) -> Sse<impl Stream<Item = Result<SseEvent, Infallible>>> {
+	let events = stream::unfold((shared, 0), |(shared, sent)| async move {
+		let next =
+			shared.wait_for(None, |s| s.sequenced_events().find(|e| e.seq > sent)).await.ok()?;
+		let data = serde_json::to_string(&next).expect("events serialize");
+		let event = SseEvent::default().id(next.seq.to_string()).data(data);
+		Some((Ok(event), (shared, next.seq)))
+	});
+	Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+// ---- /mock control surface ----
+
+async fn mock_state(State(shared): AppState) -> Json<MockState> {
+	Json(shared.read(MockState::clone))
+}
+
+async fn mock_push_event(State(shared): AppState, Body(event): Body<EventDto>) -> Json<OkResponse> {
+	shared.update(|s| s.push_event(event));
+	ok()
+}
+
+async fn mock_add_fault(State(shared): AppState, Body(fault): Body<Fault>) -> Json<OkResponse> {
+	shared.update(|s| s.faults.push(fault));
+	ok()
+}
+
+async fn mock_clear_faults(State(shared): AppState) -> Json<OkResponse> {
+	shared.update(|s| s.faults.clear());
+	ok()
+}
+
+async fn mock_settings(
+	State(shared): AppState, Body(settings): Body<Settings>,
+) -> Json<OkResponse> {
+	shared.update(|s| s.settings = settings);
+	ok()
+}
+
+#[derive(Deserialize)]
+struct ReadyRequest {
+	ready: bool,
+}
+
+async fn mock_ready(State(shared): AppState, Body(req): Body<ReadyRequest>) -> Json<OkResponse> {
+	shared.update(|s| s.ready = req.ready);
+	ok()
+}
+
+#[derive(Deserialize)]
+struct MineRequest {
+	#[serde(default = "one")]
+	blocks: u32,
+}
+
+fn one() -> u32 {
+	1
+}
+
+async fn mock_mine(State(shared): AppState, Body(req): Body<MineRequest>) -> Json<StatusDto> {
+	Json(shared.update(|s| {
+		s.mine(req.blocks);
+		s.status()
+	}))
+}
+
+#[derive(Deserialize)]
+struct ReceiveRequest {
+	/// Invoice or offer created by this node.
+	request: String,
+	amount_msat: Option<u64>,
+}
+
+async fn mock_receive(
+	State(shared): AppState, Body(req): Body<ReceiveRequest>,
+) -> ApiResult<SendResponse> {
+	let payment_id = shared.update(|s| s.receive(&req.request, req.amount_msat))?;
+	Ok(Json(SendResponse { payment_id }))
+}
+
+#[derive(Deserialize)]
+struct ResolveRequest {
+	status: PaymentStatus,
+}
+
+async fn mock_resolve_payment(
+	State(shared): AppState, Path(payment_id): Path<String>, Body(req): Body<ResolveRequest>,
+) -> ApiResult<OkResponse> {
+	let id = parse_payment_id(&payment_id)?;
+	shared.update(|s| s.resolve_payment(&id, req.status))?;
+	Ok(ok())
+}
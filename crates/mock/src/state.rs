@@ -0,0 +1,947 @@
+//! In-memory node state behind the mock routes.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rgbldk_api::http::*;
+use rgbldk_api::ids::{ChannelId, NodeId, PaymentId, UserChannelId};
+use rgbldk_api::routes::ROUTES;
+use rgbldk_api::{API_VERSION, CRATE_VERSION};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::fault::{Fault, FaultAction};
+
+/// Chain hash of regtest, as reported by the BOLT12 decoders.
+const REGTEST_CHAIN_HASH: &str = "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206";
+
+/// Node id the mock answers with unless configured otherwise.
+pub const DEFAULT_NODE_ID: &str =
+	"026d6f636b00000000000000000000000000000000000000000000000000000001";
+
+/// Why the mock refused a request; maps to the daemon's `400`/`404`/`409` error answers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rejection {
+	/// `400` with this message.
+	BadRequest(String),
+	/// `404 not found`.
+	NotFound,
+	/// `409` with this message.
+	Conflict(String),
+}
+
+impl fmt::Display for Rejection {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Rejection::BadRequest(msg) => f.write_str(msg),
+			Rejection::NotFound => f.write_str("not found"),
+			Rejection::Conflict(msg) => f.write_str(msg),
+		}
+	}
+}
+
+impl std::error::Error for Rejection {}
+
+type Result<T> = std::result::Result<T, Rejection>;
+
+fn bad_request<T>(msg: impl Into<String>) -> Result<T> {
+	Err(Rejection::BadRequest(msg.into()))
+}
+
+/// How payments the mock initiates end.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaymentOutcome {
+	/// Complete immediately with `Succeeded`.
+	#[default]
+	Succeed,
+	/// Complete immediately with `Failed`.
+	Fail,
+	/// Stay `Pending` until resolved through [`MockState::resolve_payment`] or abandoned.
+	Pending,
+}
+
+/// Knobs for the simulated network.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+	/// Outcome of outbound payments and of refund payment requests.
+	pub payment_outcome: PaymentOutcome,
+	/// Routing fee reported for successful outbound payments.
+	pub fee_msat: u64,
+}
+
+/// State of the mocked node.
+///
+/// The public fields can be edited freely (through `MockServer::update`) to seed a scenario;
+/// the route handlers keep them consistent the way the daemon would.
+#[derive(Debug, Clone, Serialize)]
+pub struct MockState {
+	/// Answer of `/node_id`.
+	pub node_id: NodeId,
+	/// Answer of `/listening_addresses`.
+	pub listening_addresses: Vec<String>,
+	/// Answer of `/version`; `capabilities` lists every route by default.
+	pub version: VersionResponse,
+	/// Whether `/readyz` reports ready.
+	pub ready: bool,
+	/// Chain tip; advanced by [`MockState::mine`].
+	pub best_block_height: u32,
+	/// On-chain wallet balance; channel opens spend from it and closes pay back into it.
+	pub onchain_balance_sats: u64,
+	/// Known peers.
+	pub peers: Vec<PeerDetailsDto>,
+	/// Open channels.
+	pub channels: Vec<ChannelDetailsExtendedDto>,
+	/// Payment store, in creation order.
+	pub payments: Vec<PaymentDetailsDto>,
+	/// Event queue; `/events/wait_next` returns the head and `/events/handled` removes it.
+	pub events: VecDeque<EventDto>,
+	/// Events acknowledged so far; the head of `events` has sequence number `acked_events + 1`.
+	pub acked_events: u64,
+	/// Simulated network behavior.
+	pub settings: Settings,
+	/// Active faults, applied in order.
+	pub faults: Vec<Fault>,
+	/// Fixed Unix time for invoice, offer and refund timestamps; `None` follows the system clock.
+	pub clock: Option<u64>,
+	#[serde(skip)]
+	receivables: HashMap<String, Receivable>,
+	#[serde(skip)]
+	counter: u64,
+}
+
+/// What the node remembers about invoices and offers it created.
+#[derive(Debug, Clone)]
+struct Receivable {
+	description: String,
+	preimage: Option<String>,
+}
+
+impl Default for MockState {
+	fn default() -> Self {
+		Self::new(DEFAULT_NODE_ID.parse().expect("default node id is valid"))
+	}
+}
+
+impl MockState {
+	/// A funded, ready node without peers, channels, payments or events.
+	pub fn new(node_id: NodeId) -> Self {
+		Self {
+			node_id,
+			listening_addresses: vec!["127.0.0.1:9735".into()],
+			version: VersionResponse {
+				api_version: API_VERSION.into(),
+				api_crate_version: CRATE_VERSION.into(),
+				core_crate_version: "mock".into(),
+				capabilities: ROUTES.iter().map(|r| r.operation_id.to_string()).collect(),
+			},
+			ready: true,
+			best_block_height: 100,
+			onchain_balance_sats: 10_000_000,
+			peers: Vec::new(),
+			channels: Vec::new(),
+			payments: Vec::new(),
+			events: VecDeque::new(),
+			acked_events: 0,
+			settings: Settings::default(),
+			faults: Vec::new(),
+			clock: None,
+			receivables: HashMap::new(),
+			counter: 0,
+		}
+	}
+
+	fn now(&self) -> u64 {
+		self.clock.unwrap_or_else(now_secs)
+	}
+
+	/// Deterministic pseudo-random hex, so runs against a fresh mock produce the same ids.
+	fn next_hex(&mut self, label: &str, bytes: usize) -> String {
+		self.counter += 1;
+		let digest = Sha256::digest(format!("{label}/{}", self.counter));
+		let mut out = hex(&digest);
+		out.truncate(bytes * 2);
+		out
+	}
+
+	fn next_payment_id(&mut self) -> PaymentId {
+		self.next_hex("payment", 32).parse().expect("generated ids are valid")
+	}
+
+	/// Appends an event to the queue.
+	pub fn push_event(&mut self, event: EventDto) {
+		self.events.push_back(event);
+	}
+
+	/// Queued events with their sequence numbers, head first.
+	pub fn sequenced_events(&self) -> impl Iterator<Item = EventEnvelope> + '_ {
+		(self.acked_events + 1..)
+			.zip(&self.events)
+			.map(|(seq, event)| EventEnvelope { seq, event: event.clone() })
+	}
+
+	/// Removes the head event, if there is one.
+	pub fn ack_event(&mut self) {
+		if self.events.pop_front().is_some() {
+			self.acked_events += 1;
+		}
+	}
+
+	/// Removes the head event if its sequence number is `seq`. Events that are already gone are
+	/// acknowledged again without effect; anything else is a conflict.
+	pub fn ack_event_seq(&mut self, seq: u64) -> Result<()> {
+		let head = self.acked_events + 1;
+		if seq < head {
+			return Ok(());
+		}
+		if seq > head || self.events.is_empty() {
+			return Err(Rejection::Conflict(format!(
+				"event {seq} is not the head of the queue (head is {head})"
+			)));
+		}
+		self.ack_event();
+		Ok(())
+	}
+
+	/// Returns the next matching faults: any delays, up to and including the first answer.
+	pub(crate) fn take_faults(&mut self, operation: &str) -> Vec<FaultAction> {
+		let mut actions = Vec::new();
+		for fault in self.faults.iter_mut().filter(|f| f.matches(operation)) {
+			if fault.times == Some(0) {
+				continue;
+			}
+			if let Some(n) = &mut fault.times {
+				*n -= 1;
+			}
+			actions.push(fault.action.clone());
+			if !matches!(fault.action, FaultAction::Delay { .. }) {
+				break;
+			}
+		}
+		self.faults.retain(|f| f.times != Some(0));
+		actions
+	}
+
+	// ---- node ----
+
+	pub(crate) fn status(&self) -> StatusDto {
+		StatusDto {
+			is_running: true,
+			is_listening: !self.listening_addresses.is_empty(),
+			best_block_height: self.best_block_height,
+		}
+	}
+
+	pub(crate) fn new_address(&mut self) -> String {
+		format!("bcrt1q{}", self.next_hex("address", 20))
+	}
+
+	pub(crate) fn balances(&self) -> BalancesDto {
+		BalancesDto {
+			total_onchain_balance_sats: self.onchain_balance_sats,
+			spendable_onchain_balance_sats: self.onchain_balance_sats,
+			total_anchor_channels_reserve_sats: 0,
+			total_lightning_balance_sats: self
+				.channels
+				.iter()
+				.map(|c| c.outbound_capacity_msat / 1000)
+				.sum(),
+		}
+	}
+
+	/// Mines `blocks` blocks; every pending channel confirms and becomes ready.
+	pub fn mine(&mut self, blocks: u32) {
+		self.best_block_height += blocks;
+		if blocks == 0 {
+			return;
+		}
+		let mut ready = Vec::new();
+		for channel in self.channels.iter_mut().filter(|c| !c.is_channel_ready) {
+			channel.is_channel_ready = true;
+			channel.is_usable = true;
+			ready.push(channel.user_channel_id.clone());
+		}
+		for user_channel_id in ready {
+			self.push_event(EventDto::ChannelReady { user_channel_id });
+		}
+	}
+
+	// ---- peers ----
+
+	pub(crate) fn connect_peer(&mut self, req: PeerConnectRequest) -> Result<()> {
+		if req.address.trim().is_empty() {
+			return bad_request("peer address must not be empty");
+		}
+		match self.peers.iter_mut().find(|p| p.node_id == req.node_id) {
+			Some(peer) => {
+				peer.address = req.address;
+				peer.is_persisted |= req.persist;
+				peer.is_connected = true;
+			},
+			None => self.peers.push(PeerDetailsDto {
+				node_id: req.node_id,
+				address: req.address,
+				is_persisted: req.persist,
+				is_connected: true,
+			}),
+		}
+		Ok(())
+	}
+
+	pub(crate) fn disconnect_peer(&mut self, node_id: &NodeId) -> Result<()> {
+		let Some(peer) = self.peers.iter_mut().find(|p| &p.node_id == node_id) else {
+			return bad_request(format!("peer {node_id} is not connected"));
+		};
+		peer.is_connected = false;
+		for channel in self.channels.iter_mut().filter(|c| &c.counterparty_node_id == node_id) {
+			channel.is_usable = false;
+		}
+		self.peers.retain(|p| p.is_connected || p.is_persisted);
+		Ok(())
+	}
+
+	// ---- channels ----
+
+	pub(crate) fn open_channel(&mut self, req: OpenChannelRequest) -> Result<UserChannelId> {
+		let push_msat = req.push_to_counterparty_msat.unwrap_or(0);
+		if req.channel_amount_sats == 0 {
+			return bad_request("channel amount must be greater than zero");
+		}
+		if req.channel_amount_sats > self.onchain_balance_sats {
+			return bad_request(format!(
+				"insufficient on-chain funds: {} sats available",
+				self.onchain_balance_sats
+			));
+		}
+		if push_msat > req.channel_amount_sats * 1000 {
+			return bad_request("push amount exceeds the channel amount");
+		}
+		self.connect_peer(PeerConnectRequest {
+			node_id: req.node_id.clone(),
+			address: req.address,
+			persist: true,
+		})?;
+		self.onchain_balance_sats -= req.channel_amount_sats;
+
+		let channel_id: ChannelId =
+			self.next_hex("channel", ChannelId::LEN).parse().expect("generated ids are valid");
+		let user_channel_id: UserChannelId = self
+			.next_hex("user_channel", UserChannelId::LEN)
+			.parse()
+			.expect("generated ids are valid");
+		let txid = self.next_hex("txid", 32);
+		self.channels.push(ChannelDetailsExtendedDto {
+			channel_id,
+			user_channel_id: user_channel_id.clone(),
+			counterparty_node_id: req.node_id,
+			channel_point: Some(format!("{txid}:0")),
+			channel_value_sats: req.channel_amount_sats,
+			outbound_capacity_msat: req.channel_amount_sats * 1000 - push_msat,
+			inbound_capacity_msat: push_msat,
+			is_channel_ready: false,
+			is_usable: false,
+			is_announced: req.announce.unwrap_or(true),
+			rgb_balance: None,
+		});
+		self.push_event(EventDto::ChannelPending { funding_txo: OutPointDto { txid, vout: 0 } });
+		Ok(user_channel_id)
+	}
+
+	pub(crate) fn close_channel(&mut self, req: &CloseChannelRequest, force: bool) -> Result<()> {
+		let Some(pos) = self.channels.iter().position(|c| {
+			c.user_channel_id == req.user_channel_id
+				&& c.counterparty_node_id == req.counterparty_node_id
+		}) else {
+			return bad_request(format!("channel {} not found", req.user_channel_id));
+		};
+		let channel = self.channels.remove(pos);
+		self.onchain_balance_sats += channel.outbound_capacity_msat / 1000;
+		let reason = if force { "HolderForceClosed" } else { "LocallyInitiatedCooperativeClosure" };
+		self.push_event(EventDto::ChannelClosed {
+			channel_id: channel.channel_id,
+			user_channel_id: channel.user_channel_id,
+			counterparty_node_id: Some(channel.counterparty_node_id),
+			reason: Some(reason.into()),
+		});
+		Ok(())
+	}
+
+	// ---- payments ----
+
+	pub(crate) fn payment(&self, id: &PaymentId) -> Result<&PaymentDetailsDto> {
+		self.payments.iter().find(|p| &p.id == id).ok_or(Rejection::NotFound)
+	}
+
+	/// One page of [`Self::payments`], newest first (see [`PaymentsPage::select`]).
+	pub(crate) fn list_payments(&self, req: &PaymentsListRequest) -> Result<PaymentsPage> {
+		PaymentsPage::select(&self.payments, req).map_err(Rejection::BadRequest)
+	}
+
+	fn payment_mut(&mut self, id: &PaymentId) -> Result<&mut PaymentDetailsDto> {
+		self.payments.iter_mut().find(|p| &p.id == id).ok_or(Rejection::NotFound)
+	}
+
+	fn insert_payment(&mut self, mut payment: PaymentDetailsDto) -> Result<()> {
+		payment.created_at = Some(self.now());
+		match self.payments.iter().position(|p| p.id == payment.id) {
+			Some(pos) if self.payments[pos].status == PaymentStatus::Failed => {
+				self.payments[pos] = payment;
+			},
+			Some(_) => return bad_request(format!("duplicate payment {}", payment.id)),
+			None => self.payments.push(payment),
+		}
+		Ok(())
+	}
+
+	/// Records a payment the node initiated and settles it according to
+	/// [`Settings::payment_outcome`].
+	fn start_payment(
+		&mut self, id: PaymentId, direction: PaymentDirection, kind: PaymentKind,
+		amount_msat: Option<u64>, details: PaymentKindDetails,
+	) -> Result<PaymentId> {
+		self.insert_payment(PaymentDetailsDto {
+			id: id.clone(),
+			direction,
+			status: PaymentStatus::Pending,
+			amount_msat,
+			kind,
+			fee_paid_msat: None,
+			kind_details: Some(details),
+			created_at: None,
+		})?;
+		match self.settings.payment_outcome {
+			PaymentOutcome::Succeed => self.resolve_payment(&id, PaymentStatus::Succeeded)?,
+			PaymentOutcome::Fail => self.resolve_payment(&id, PaymentStatus::Failed)?,
+			PaymentOutcome::Pending => {},
+		}
+		Ok(id)
+	}
+
+	/// Moves a pending payment to `status` and queues the matching event.
+	pub fn resolve_payment(&mut self, id: &PaymentId, status: PaymentStatus) -> Result<()> {
+		let fee_msat = self.settings.fee_msat;
+		let hash = self.payment(id)?.kind_details.as_ref().and_then(|d| d.payment_hash());
+		let hash = hash.map_or_else(|| sha256_hex(&format!("hash/{id}")), str::to_string);
+		let preimage = self.preimage_for(&hash);
+		let payment = self.payment_mut(id)?;
+		if payment.status != PaymentStatus::Pending {
+			return bad_request(format!("payment {id} is already {}", payment.status));
+		}
+		let outbound = payment.direction == PaymentDirection::Outbound;
+		let event = match status {
+			PaymentStatus::Succeeded => {
+				if outbound {
+					payment.fee_paid_msat = Some(fee_msat);
+				}
+				if let Some(details) = &mut payment.kind_details {
+					settle_details(details, hash, preimage);
+				}
+				if outbound {
+					EventDto::PaymentSuccessful {
+						payment_id: Some(id.clone()),
+						fee_paid_msat: Some(fee_msat),
+					}
+				} else {
+					EventDto::PaymentReceived {
+						payment_id: Some(id.clone()),
+						amount_msat: payment.amount_msat.unwrap_or(0),
+					}
+				}
+			},
+			PaymentStatus::Failed => EventDto::PaymentFailed { payment_id: Some(id.clone()) },
+			other => return bad_request(format!("cannot resolve a payment to {other}")),
+		};
+		payment.status = status;
+		self.push_event(event);
+		Ok(())
+	}
+
+	pub(crate) fn abandon_payment(&mut self, id: &PaymentId) -> Result<()> {
+		let payment = self.payment(id)?;
+		if payment.direction != PaymentDirection::Outbound
+			|| payment.status != PaymentStatus::Pending
+		{
+			return bad_request(format!("payment {id} is not a pending outbound payment"));
+		}
+		self.resolve_payment(id, PaymentStatus::Failed)
+	}
+
+	/// Simulates a remote payer paying an invoice or offer this node created.
+	pub fn receive(&mut self, request: &str, amount_msat: Option<u64>) -> Result<PaymentId> {
+		if let Ok(invoice) = MockInvoice::decode(request) {
+			if invoice.payee != self.node_id {
+				return bad_request("invoice was not created by this node");
+			}
+			if invoice.is_expired(self.now()) {
+				return bad_request("invoice expired");
+			}
+			let id: PaymentId = invoice.payment_hash.parse().expect("payment hashes are 32 bytes");
+			let amount = invoice.amount_msat.or(amount_msat);
+			let payment = self.payment_mut(&id)?;
+			if payment.amount_msat.is_none() {
+				payment.amount_msat = Some(amount.ok_or_else(|| {
+					Rejection::BadRequest("amount required for variable-amount invoice".into())
+				})?);
+			}
+			self.resolve_payment(&id, PaymentStatus::Succeeded)?;
+			return Ok(id);
+		}
+		let offer = MockOffer::decode(request)?;
+		if offer.payee != self.node_id {
+			return bad_request("offer was not created by this node");
+		}
+		let Some(amount) = amount_msat.or(offer.amount_msat) else {
+			return bad_request("amount required for variable-amount offer");
+		};
+		let preimage = self.next_hex("preimage", 32);
+		let id = self.next_payment_id();
+		self.insert_payment(PaymentDetailsDto {
+			id: id.clone(),
+			direction: PaymentDirection::Inbound,
+			status: PaymentStatus::Pending,
+			amount_msat: Some(amount),
+			kind: PaymentKind::Bolt12Offer,
+			fee_paid_msat: None,
+			kind_details: Some(PaymentKindDetails::Bolt12Offer {
+				payment_hash: Some(sha256_hex(&preimage)),
+				preimage: Some(preimage),
+				secret: None,
+				offer_id: offer.offer_id,
+				payer_note: None,
+				quantity: offer.quantity,
+			}),
+			created_at: None,
+		})?;
+		self.resolve_payment(&id, PaymentStatus::Succeeded)?;
+		Ok(id)
+	}
+
+	// ---- BOLT11 ----
+
+	pub(crate) fn create_invoice(
+		&mut self, amount_msat: Option<u64>, description: String, expiry_secs: u32,
+	) -> Result<String> {
+		if amount_msat == Some(0) {
+			return bad_request("amount must be greater than zero");
+		}
+		let preimage = self.next_hex("preimage", 32);
+		let invoice = MockInvoice {
+			payee: self.node_id.clone(),
+			payment_hash: sha256_hex(&preimage),
+			amount_msat,
+			created_at: self.now(),
+			expiry_secs: expiry_secs.into(),
+		};
+		let id: PaymentId = invoice.payment_hash.parse().expect("payment hashes are 32 bytes");
+		self.insert_payment(PaymentDetailsDto {
+			id,
+			direction: PaymentDirection::Inbound,
+			status: PaymentStatus::Pending,
+			amount_msat,
+			kind: PaymentKind::Bolt11,
+			fee_paid_msat: None,
+			kind_details: Some(PaymentKindDetails::Bolt11 {
+				payment_hash: invoice.payment_hash.clone(),
+				preimage: None,
+				secret: None,
+			}),
+			created_at: None,
+		})?;
+		self.receivables.insert(
+			invoice.payment_hash.clone(),
+			Receivable { description, preimage: Some(preimage) },
+		);
+		Ok(invoice.encode())
+	}
+
+	pub(crate) fn decode_invoice(&self, invoice: &str) -> Result<Bolt11DecodeResponse> {
+		let invoice = MockInvoice::decode(invoice)?;
+		Ok(Bolt11DecodeResponse {
+			payment_hash: invoice.payment_hash,
+			destination: invoice.payee,
+			amount_msat: invoice.amount_msat,
+			expiry_secs: invoice.expiry_secs,
+		})
+	}
+
+	pub(crate) fn pay_invoice(
+		&mut self, invoice: &str, amount_msat: Option<u64>,
+	) -> Result<PaymentId> {
+		let invoice = MockInvoice::decode(invoice)?;
+		if invoice.payee == self.node_id {
+			return bad_request("cannot pay an invoice created by this node");
+		}
+		if invoice.is_expired(self.now()) {
+			return bad_request("invoice expired");
+		}
+		let amount = match (invoice.amount_msat, amount_msat) {
+			(Some(fixed), Some(given)) if given < fixed => {
+				return bad_request(format!("amount below the invoice amount of {fixed} msat"));
+			},
+			(_, Some(given)) => given,
+			(Some(fixed), None) => fixed,
+			(None, None) => return bad_request("amount required for variable-amount invoice"),
+		};
+		let id = invoice.payment_hash.parse().expect("payment hashes are 32 bytes");
+		self.start_payment(
+			id,
+			PaymentDirection::Outbound,
+			PaymentKind::Bolt11,
+			Some(amount),
+			PaymentKindDetails::Bolt11 {
+				payment_hash: invoice.payment_hash,
+				preimage: None,
+				secret: None,
+			},
+		)
+	}
+
+	// ---- BOLT12 ----
+
+	pub(crate) fn create_offer(
+		&mut self, amount_msat: Option<u64>, description: String, expiry_secs: Option<u32>,
+		quantity: Option<u64>,
+	) -> Result<String> {
+		if amount_msat == Some(0) {
+			return bad_request("amount must be greater than zero");
+		}
+		let offer = MockOffer {
+			payee: self.node_id.clone(),
+			offer_id: self.next_hex("offer", 32),
+			amount_msat,
+			absolute_expiry: expiry_secs.map(|s| self.now() + u64::from(s)),
+			quantity,
+		};
+		self.receivables.insert(offer.offer_id.clone(), Receivable { description, preimage: None });
+		Ok(offer.encode())
+	}
+
+	pub(crate) fn decode_offer(&self, offer: &str) -> Result<Bolt12OfferDecodeResponse> {
+		let offer = MockOffer::decode(offer)?;
+		Ok(Bolt12OfferDecodeResponse {
+			description: self.receivables.get(&offer.offer_id).map(|r| r.description.clone()),
+			offer_id: offer.offer_id,
+			signing_pubkey: Some(offer.payee.to_string()),
+			issuer: None,
+			amount_msat: offer.amount_msat,
+			absolute_expiry_unix_secs: offer.absolute_expiry,
+			chain_hashes: vec![REGTEST_CHAIN_HASH.into()],
+			paths_count: 0,
+			expects_quantity: offer.quantity.is_some(),
+		})
+	}
+
+	pub(crate) fn pay_offer(&mut self, req: Bolt12OfferSendRequest) -> Result<PaymentId> {
+		let offer = MockOffer::decode(&req.offer)?;
+		if offer.payee == self.node_id {
+			return bad_request("cannot pay an offer created by this node");
+		}
+		if offer.absolute_expiry.is_some_and(|t| t <= self.now()) {
+			return bad_request("offer expired");
+		}
+		let Some(amount) = req.amount_msat.or(offer.amount_msat) else {
+			return bad_request("amount required for zero-amount offer");
+		};
+		let id = self.next_payment_id();
+		let payment_hash = self.next_hex("payment_hash", 32);
+		self.start_payment(
+			id,
+			PaymentDirection::Outbound,
+			PaymentKind::Bolt12Offer,
+			Some(amount),
+			PaymentKindDetails::Bolt12Offer {
+				payment_hash: Some(payment_hash),
+				preimage: None,
+				secret: None,
+				offer_id: offer.offer_id,
+				payer_note: req.payer_note,
+				quantity: req.quantity,
+			},
+		)
+	}
+
+	pub(crate) fn initiate_refund(
+		&mut self, req: Bolt12RefundInitiateRequest,
+	) -> Result<Bolt12RefundInitiateResponse> {
+		if req.amount_msat == 0 {
+			return bad_request("amount must be greater than zero");
+		}
+		let refund = MockRefund {
+			payer: self.node_id.clone(),
+			payment_id: self.next_payment_id(),
+			amount_msat: req.amount_msat,
+			absolute_expiry: self.now() + u64::from(req.expiry_secs),
+		};
+		// The payer waits for the payee's invoice, so this stays pending until resolved.
+		self.insert_payment(PaymentDetailsDto {
+			id: refund.payment_id.clone(),
+			direction: PaymentDirection::Outbound,
+			status: PaymentStatus::Pending,
+			amount_msat: Some(req.amount_msat),
+			kind: PaymentKind::Bolt12Refund,
+			fee_paid_msat: None,
+			kind_details: Some(PaymentKindDetails::Bolt12Refund {
+				payment_hash: None,
+				preimage: None,
+				secret: None,
+				payer_note: req.payer_note.clone(),
+				quantity: req.quantity,
+			}),
+			created_at: None,
+		})?;
+		self.receivables.insert(
+			refund.payment_id.to_string(),
+			Receivable { description: req.payer_note.unwrap_or_default(), preimage: None },
+		);
+		Ok(Bolt12RefundInitiateResponse { refund: refund.encode(), payment_id: refund.payment_id })
+	}
+
+	pub(crate) fn decode_refund(&self, refund: &str) -> Result<Bolt12RefundDecodeResponse> {
+		let refund = MockRefund::decode(refund)?;
+		let known = self.receivables.get(refund.payment_id.as_str());
+		Ok(Bolt12RefundDecodeResponse {
+			description: known.map(|r| r.description.clone()).unwrap_or_default(),
+			issuer: None,
+			amount_msat: refund.amount_msat,
+			absolute_expiry_unix_secs: Some(refund.absolute_expiry),
+			chain_hash: REGTEST_CHAIN_HASH.into(),
+			payer_signing_pubkey: refund.payer.to_string(),
+			payer_note: None,
+			quantity: None,
+			paths_count: 0,
+		})
+	}
+
+	pub(crate) fn request_refund_payment(
+		&mut self, refund: &str,
+	) -> Result<Bolt12RefundRequestPaymentResponse> {
+		let refund = MockRefund::decode(refund)?;
+		if refund.payer == self.node_id {
+			return bad_request("cannot request payment for a refund created by this node");
+		}
+		if refund.absolute_expiry <= self.now() {
+			return bad_request("refund expired");
+		}
+		let preimage = self.next_hex("preimage", 32);
+		let payment_hash = sha256_hex(&preimage);
+		let id = self.next_payment_id();
+		let payment_id = self.start_payment(
+			id,
+			PaymentDirection::Inbound,
+			PaymentKind::Bolt12Refund,
+			Some(refund.amount_msat),
+			PaymentKindDetails::Bolt12Refund {
+				payment_hash: Some(payment_hash.clone()),
+				preimage: None,
+				secret: None,
+				payer_note: None,
+				quantity: None,
+			},
+		)?;
+		self.receivables.insert(
+			payment_hash.clone(),
+			Receivable { description: String::new(), preimage: Some(preimage) },
+		);
+		Ok(Bolt12RefundRequestPaymentResponse {
+			invoice: format!("lnimock1_{}_{payment_hash}", self.node_id),
+			invoice_hex: payment_hash,
+			payment_id,
+		})
+	}
+
+	// ---- keysend ----
+
+	pub(crate) fn keysend(&mut self, req: &SpontaneousSendRequest) -> Result<PaymentId> {
+		if req.counterparty_node_id == self.node_id {
+			return bad_request("cannot send a payment to this node");
+		}
+		if req.amount_msat == 0 {
+			return bad_request("amount must be greater than zero");
+		}
+		let preimage = self.next_hex("preimage", 32);
+		let payment_hash = sha256_hex(&preimage);
+		let id = payment_hash.parse().expect("payment hashes are 32 bytes");
+		self.receivables.insert(
+			payment_hash.clone(),
+			Receivable { description: String::new(), preimage: Some(preimage) },
+		);
+		self.start_payment(
+			id,
+			PaymentDirection::Outbound,
+			PaymentKind::Spontaneous,
+			Some(req.amount_msat),
+			PaymentKindDetails::Spontaneous { payment_hash, preimage: None },
+		)
+	}
+
+	/// Preimage revealed when a payment with this hash settles.
+	fn preimage_for(&self, payment_hash: &str) -> String {
+		match self.receivables.get(payment_hash).and_then(|r| r.preimage.clone()) {
+			Some(preimage) => preimage,
+			// Someone else's invoice: the mock cannot know the real preimage.
+			None => sha256_hex(&format!("preimage/{payment_hash}")),
+		}
+	}
+}
+
+/// Fills in what a settled payment reveals: the preimage and, for BOLT12, the hash.
+fn settle_details(details: &mut PaymentKindDetails, hash: String, revealed: String) {
+	match details {
+		PaymentKindDetails::Bolt11 { preimage, .. }
+		| PaymentKindDetails::Bolt11Jit { preimage, .. }
+		| PaymentKindDetails::Spontaneous { preimage, .. } => {
+			preimage.get_or_insert(revealed);
+		},
+		PaymentKindDetails::Bolt12Offer { payment_hash, preimage, .. }
+		| PaymentKindDetails::Bolt12Refund { payment_hash, preimage, .. } => {
+			payment_hash.get_or_insert(hash);
+			preimage.get_or_insert(revealed);
+		},
+		PaymentKindDetails::Onchain { .. } | PaymentKindDetails::Unknown(_) => {},
+	}
+}
+
+fn now_secs() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn hex(bytes: &[u8]) -> String {
+	bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn sha256_hex(s: &str) -> String {
+	hex(&Sha256::digest(s))
+}
+
+fn invalid(what: &str) -> Rejection {
+	Rejection::BadRequest(format!("invalid {what}"))
+}
+
+fn parse_amount(s: &str) -> Option<Option<u64>> {
+	if s == "any" {
+		Some(None)
+	} else {
+		s.parse().ok().map(Some)
+	}
+}
+
+fn format_amount(amount: Option<u64>) -> String {
+	amount.map_or_else(|| "any".into(), |a| a.to_string())
+}
+
+/// BOLT11 stand-in: `lnmock1_<payee>_<payment hash>_<amount msat|any>_<created>_<expiry secs>`.
+struct MockInvoice {
+	payee: NodeId,
+	payment_hash: String,
+	amount_msat: Option<u64>,
+	created_at: u64,
+	expiry_secs: u64,
+}
+
+impl MockInvoice {
+	fn encode(&self) -> String {
+		format!(
+			"lnmock1_{}_{}_{}_{}_{}",
+			self.payee,
+			self.payment_hash,
+			format_amount(self.amount_msat),
+			self.created_at,
+			self.expiry_secs
+		)
+	}
+
+	fn decode(s: &str) -> Result<Self> {
+		let fields: Vec<&str> = s.trim().split('_').collect();
+		let ["lnmock1", payee, hash, amount, created, expiry] = fields.as_slice() else {
+			return Err(invalid("invoice"));
+		};
+		let hash: PaymentId = hash.parse().map_err(|_| invalid("invoice"))?;
+		Ok(Self {
+			payee: payee.parse().map_err(|_| invalid("invoice"))?,
+			payment_hash: hash.to_string(),
+			amount_msat: parse_amount(amount).ok_or_else(|| invalid("invoice"))?,
+			created_at: created.parse().map_err(|_| invalid("invoice"))?,
+			expiry_secs: expiry.parse().map_err(|_| invalid("invoice"))?,
+		})
+	}
+
+	fn is_expired(&self, now: u64) -> bool {
+		self.created_at + self.expiry_secs <= now
+	}
+}
+
+/// BOLT12 offer stand-in: `lnomock1_<payee>_<offer id>_<amount msat|any>_<expiry|none>_<quantity|none>`.
+struct MockOffer {
+	payee: NodeId,
+	offer_id: String,
+	amount_msat: Option<u64>,
+	absolute_expiry: Option<u64>,
+	quantity: Option<u64>,
+}
+
+impl MockOffer {
+	fn encode(&self) -> String {
+		let opt = |v: Option<u64>| v.map_or_else(|| "none".into(), |v| v.to_string());
+		format!(
+			"lnomock1_{}_{}_{}_{}_{}",
+			self.payee,
+			self.offer_id,
+			format_amount(self.amount_msat),
+			opt(self.absolute_expiry),
+			opt(self.quantity)
+		)
+	}
+
+	fn decode(s: &str) -> Result<Self> {
+		let fields: Vec<&str> = s.trim().split('_').collect();
+		let ["lnomock1", payee, offer_id, amount, expiry, quantity] = fields.as_slice() else {
+			return Err(invalid("offer"));
+		};
+		let opt = |v: &str| -> Result<Option<u64>> {
+			if v == "none" {
+				Ok(None)
+			} else {
+				v.parse().map(Some).map_err(|_| invalid("offer"))
+			}
+		};
+		let offer_id: PaymentId = offer_id.parse().map_err(|_| invalid("offer"))?;
+		Ok(Self {
+			payee: payee.parse().map_err(|_| invalid("offer"))?,
+			offer_id: offer_id.to_string(),
+			amount_msat: parse_amount(amount).ok_or_else(|| invalid("offer"))?,
+			absolute_expiry: opt(expiry)?,
+			quantity: opt(quantity)?,
+		})
+	}
+}
+
+/// BOLT12 refund stand-in: `lnrmock1_<payer>_<payment id>_<amount msat>_<expiry>`.
+struct MockRefund {
+	payer: NodeId,
+	payment_id: PaymentId,
+	amount_msat: u64,
+	absolute_expiry: u64,
+}
+
+impl MockRefund {
+	fn encode(&self) -> String {
+		format!(
+			"lnrmock1_{}_{}_{}_{}",
+			self.payer, self.payment_id, self.amount_msat, self.absolute_expiry
+		)
+	}
+
+	fn decode(s: &str) -> Result<Self> {
+		let fields: Vec<&str> = s.trim().split('_').collect();
+		let ["lnrmock1", payer, payment_id, amount, expiry] = fields.as_slice() else {
+			return Err(invalid("refund"));
+		};
+		Ok(Self {
+			payer: payer.parse().map_err(|_| invalid("refund"))?,
+			payment_id: payment_id.parse().map_err(|_| invalid("refund"))?,
+			amount_msat: amount.parse().map_err(|_| invalid("refund"))?,
+			absolute_expiry: expiry.parse().map_err(|_| invalid("refund"))?,
+		})
+	}
+}
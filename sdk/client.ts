@@ -1,4 +1,8 @@
-import {
+// Generated from the rgbldk-api crate; do not edit by hand.
+// Regenerate with `UPDATE_SDK=1 cargo test -p rgbldk-api --features schema --test sdk`.
+
+import { HttpTransport, type ClientOptions, type RequestOptions } from "./http.js";
+import type {
   BalancesDto,
   Bolt11DecodeRequest,
   Bolt11DecodeResponse,
@@ -23,355 +27,226 @@ import {
   Bolt12RefundRequestPaymentResponse,
   ChannelDetailsExtendedDto,
   CloseChannelRequest,
-  EventDto,
+  EventAckRequest,
+  EventEnvelope,
   ListeningAddressesResponse,
   NodeIdResponse,
   OkResponse,
   OpenChannelRequest,
   OpenChannelResponse,
   PaymentDetailsDto,
+  PaymentId,
   PaymentWaitRequest,
   PaymentWaitResponse,
+  PaymentsListRequest,
+  PaymentsPage,
   PeerConnectRequest,
   PeerDetailsDto,
   PeerDisconnectRequest,
+  QueuedEvent,
   SendResponse,
   SpontaneousSendRequest,
   StatusDto,
+  VersionResponse,
+  WalletNewAddressResponse,
 } from "./types.js";
-import { parse, parseNumberAndBigInt } from "lossless-json";
 
-export interface RequestOptions {
-  timeoutMs?: number;
-  headers?: Record<string, string>;
-  signal?: AbortSignal;
-}
+/** Typed client for the `/api/v1` routes, mirroring the Rust `RgbLdkClient`. */
+export class RgbLdkClient {
+  readonly http: HttpTransport;
 
-export class HttpError extends Error {
-  public readonly status: number;
-  public readonly body: unknown;
-  constructor(message: string, status: number, body: unknown) {
-    super(message);
-    this.status = status;
-    this.body = body;
+  /** `baseUrl` is the daemon root, e.g. `http://127.0.0.1:8500`. */
+  constructor(baseUrl: string, opts?: ClientOptions) {
+    this.http = new HttpTransport(`${baseUrl.replace(/\/+$/, "")}/api/v1`, opts);
+  }
+
+  /** Liveness probe. (`GET /api/v1/healthz`) */
+  healthz(options?: RequestOptions): Promise<OkResponse> {
+    return this.http.request("GET", "/healthz", undefined, options);
   }
-}
 
-export type FetchLike = (input: RequestInfo | URL, init?: RequestInit) => Promise<Response>;
-
-export class NodeHttpClient {
-  private readonly baseUrl: string;
-  private readonly fetchFn: FetchLike;
-  private readonly defaultHeaders: Record<string, string>;
-
-  constructor(baseUrl: string, opts?: { fetch?: FetchLike; headers?: Record<string, string> }) {
-    if (!baseUrl) throw new Error("baseUrl is required");
-    this.baseUrl = baseUrl.replace(/\/$/, "");
-    const globalFetch: any = (globalThis as any).fetch;
-    this.fetchFn = opts?.fetch ?? (globalFetch?.bind(globalThis) as FetchLike);
-    if (!this.fetchFn) {
-      throw new Error("No fetch implementation found. Provide opts.fetch or a global fetch");
-    }
-    this.defaultHeaders = { "Content-Type": "application/json", ...(opts?.headers ?? {}) };
-  }
-
-  private async request<T>(
-    method: string,
-    path: string,
-    body?: unknown,
-    options?: RequestOptions & { returnNullOn404?: boolean },
-  ): Promise<T | null> {
-    const url = `${this.baseUrl}${path}`;
-    const headers = { ...this.defaultHeaders, ...(options?.headers ?? {}) };
-    const controller = new AbortController();
-    const timeout = options?.timeoutMs && options.timeoutMs > 0
-      ? setTimeout(() => controller.abort(), options.timeoutMs)
-      : undefined;
-    if (options?.signal) {
-      if (options.signal.aborted) controller.abort();
-      else options.signal.addEventListener("abort", () => controller.abort());
-    }
-
-    try {
-      const resp = await this.fetchFn(url, {
-        method,
-        headers,
-        body: body !== undefined ? JSON.stringify(body, jsonBigIntReplacer) : undefined,
-        signal: controller.signal,
-      });
-      const text = await resp.text();
-      const json = text ? safeJsonParse(text) : undefined;
-      if (!resp.ok) {
-        if (resp.status === 404 && options?.returnNullOn404) return null;
-        const msg = (json as any)?.error || `HTTP ${resp.status}`;
-        throw new HttpError(msg, resp.status, json ?? text);
-      }
-      return (json as T) ?? ({} as T);
-    } catch (e: any) {
-      if (e?.name === "AbortError") {
-        throw new Error("Request aborted");
-      }
-      throw e;
-    } finally {
-      if (timeout) clearTimeout(timeout);
-    }
-  }
-
-  // GET /status
+  /** Readiness probe; answers 503 with the same body while the node is not ready. (`GET /api/v1/readyz`) */
+  readyz(options?: RequestOptions): Promise<OkResponse> {
+    return this.http.request("GET", "/readyz", undefined, options, [503]);
+  }
+
+  /** Daemon and API versions. (`GET /api/v1/version`) */
+  version(options?: RequestOptions): Promise<VersionResponse> {
+    return this.http.request("GET", "/version", undefined, options);
+  }
+
+  /** Node runtime status. (`GET /api/v1/status`) */
   status(options?: RequestOptions): Promise<StatusDto> {
-    return this.request<StatusDto>("GET", "/status", undefined, options) as Promise<StatusDto>;
+    return this.http.request("GET", "/status", undefined, options);
   }
 
-  // GET /node_id
+  /** The node's own id. (`GET /api/v1/node_id`) */
   nodeId(options?: RequestOptions): Promise<NodeIdResponse> {
-    return this.request<NodeIdResponse>("GET", "/node_id", undefined, options) as Promise<NodeIdResponse>;
+    return this.http.request("GET", "/node_id", undefined, options);
   }
 
-  // GET /listening_addresses
+  /** P2P listening addresses. (`GET /api/v1/listening_addresses`) */
   listeningAddresses(options?: RequestOptions): Promise<ListeningAddressesResponse> {
-    return this.request<ListeningAddressesResponse>("GET", "/listening_addresses", undefined, options) as Promise<ListeningAddressesResponse>;
+    return this.http.request("GET", "/listening_addresses", undefined, options);
   }
 
-  // POST /wallet/new_address
-  walletNewAddress(options?: RequestOptions): Promise<{ address: string }> {
-    return this.request<{ address: string }>("POST", "/wallet/new_address", {}, options) as Promise<{ address: string }>;
+  /** Fresh on-chain address. (`POST /api/v1/wallet/new_address`) */
+  walletNewAddress(options?: RequestOptions): Promise<WalletNewAddressResponse> {
+    return this.http.request("POST", "/wallet/new_address", undefined, options);
   }
 
-  // POST /wallet/sync
+  /** Sync the on-chain wallet. (`POST /api/v1/wallet/sync`) */
   walletSync(options?: RequestOptions): Promise<OkResponse> {
-    return this.request<OkResponse>("POST", "/wallet/sync", {}, options) as Promise<OkResponse>;
+    return this.http.request("POST", "/wallet/sync", undefined, options);
   }
 
-  // GET /balances
+  /** Wallet and channel balances. (`GET /api/v1/balances`) */
   balances(options?: RequestOptions): Promise<BalancesDto> {
-    return this.request<BalancesDto>("GET", "/balances", undefined, options) as Promise<BalancesDto>;
+    return this.http.request("GET", "/balances", undefined, options);
   }
 
-  // GET /peers
+  /** Known peers. (`GET /api/v1/peers`) */
   peers(options?: RequestOptions): Promise<PeerDetailsDto[]> {
-    return this.request<PeerDetailsDto[]>("GET", "/peers", undefined, options) as Promise<PeerDetailsDto[]>;
+    return this.http.request("GET", "/peers", undefined, options);
   }
 
-  // POST /peers/connect
-  peersConnect(req: PeerConnectRequest, options?: RequestOptions): Promise<OkResponse> {
-    return this.request<OkResponse>("POST", "/peers/connect", req, options) as Promise<OkResponse>;
+  /** Connect to a peer. (`POST /api/v1/peers/connect`) */
+  peerConnect(req: PeerConnectRequest, options?: RequestOptions): Promise<OkResponse> {
+    return this.http.request("POST", "/peers/connect", req, options);
   }
 
-  // POST /peers/disconnect
-  peersDisconnect(req: PeerDisconnectRequest, options?: RequestOptions): Promise<OkResponse> {
-    return this.request<OkResponse>("POST", "/peers/disconnect", req, options) as Promise<OkResponse>;
+  /** Disconnect a peer. (`POST /api/v1/peers/disconnect`) */
+  peerDisconnect(req: PeerDisconnectRequest, options?: RequestOptions): Promise<OkResponse> {
+    return this.http.request("POST", "/peers/disconnect", req, options);
   }
 
-  // GET /channels
+  /** Channels with extended details. (`GET /api/v1/channels`) */
   channels(options?: RequestOptions): Promise<ChannelDetailsExtendedDto[]> {
-    return this.request<ChannelDetailsExtendedDto[]>("GET", "/channels", undefined, options) as Promise<
-      ChannelDetailsExtendedDto[]
-    >;
+    return this.http.request("GET", "/channels", undefined, options);
   }
 
-  // POST /channel/open
+  /** Open a channel. (`POST /api/v1/channel/open`) */
   channelOpen(req: OpenChannelRequest, options?: RequestOptions): Promise<OpenChannelResponse> {
-    return this.request<OpenChannelResponse>("POST", "/channel/open", req, options) as Promise<OpenChannelResponse>;
+    return this.http.request("POST", "/channel/open", req, options);
   }
 
-  // POST /channel/close
+  /** Cooperatively close a channel. (`POST /api/v1/channel/close`) */
   channelClose(req: CloseChannelRequest, options?: RequestOptions): Promise<OkResponse> {
-    return this.request<OkResponse>("POST", "/channel/close", req, options) as Promise<OkResponse>;
+    return this.http.request("POST", "/channel/close", req, options);
   }
 
-  // POST /channel/force_close
+  /** Force-close a channel. (`POST /api/v1/channel/force_close`) */
   channelForceClose(req: CloseChannelRequest, options?: RequestOptions): Promise<OkResponse> {
-    return this.request<OkResponse>("POST", "/channel/force_close", req, options) as Promise<OkResponse>;
+    return this.http.request("POST", "/channel/force_close", req, options);
   }
 
-  // POST /bolt11/receive
-  bolt11Receive(req: Bolt11ReceiveRequest, options?: RequestOptions): Promise<Bolt11ReceiveResponse> {
-    return this.request<Bolt11ReceiveResponse>("POST", "/bolt11/receive", req, options) as Promise<Bolt11ReceiveResponse>;
+  /** Decode a BOLT11 invoice. (`POST /api/v1/bolt11/decode`) */
+  bolt11Decode(req: Bolt11DecodeRequest, options?: RequestOptions): Promise<Bolt11DecodeResponse> {
+    return this.http.request("POST", "/bolt11/decode", req, options);
   }
 
-  // POST /bolt11/receive_var
-  bolt11ReceiveVar(req: Bolt11ReceiveVarRequest, options?: RequestOptions): Promise<Bolt11ReceiveResponse> {
-    return this.request<Bolt11ReceiveResponse>("POST", "/bolt11/receive_var", req, options) as Promise<Bolt11ReceiveResponse>;
+  /** Create a fixed-amount BOLT11 invoice. (`POST /api/v1/bolt11/receive`) */
+  bolt11Receive(req: Bolt11ReceiveRequest, options?: RequestOptions): Promise<Bolt11ReceiveResponse> {
+    return this.http.request("POST", "/bolt11/receive", req, options);
   }
 
-  // POST /bolt11/decode
-  bolt11Decode(req: Bolt11DecodeRequest, options?: RequestOptions): Promise<Bolt11DecodeResponse> {
-    return this.request<Bolt11DecodeResponse>("POST", "/bolt11/decode", req, options) as Promise<Bolt11DecodeResponse>;
+  /** Create a variable-amount BOLT11 invoice. (`POST /api/v1/bolt11/receive_var`) */
+  bolt11ReceiveVar(req: Bolt11ReceiveVarRequest, options?: RequestOptions): Promise<Bolt11ReceiveResponse> {
+    return this.http.request("POST", "/bolt11/receive_var", req, options);
   }
 
-  // POST /bolt11/send
+  /** Pay a BOLT11 invoice without waiting. (`POST /api/v1/bolt11/send`) */
   bolt11Send(req: Bolt11SendRequest, options?: RequestOptions): Promise<SendResponse> {
-    return this.request<SendResponse>("POST", "/bolt11/send", req, options) as Promise<SendResponse>;
+    return this.http.request("POST", "/bolt11/send", req, options);
   }
 
-  // POST /bolt11/send_using_amount
+  /** Pay a variable-amount BOLT11 invoice without waiting. (`POST /api/v1/bolt11/send_using_amount`) */
   bolt11SendUsingAmount(req: Bolt11SendUsingAmountRequest, options?: RequestOptions): Promise<SendResponse> {
-    return this.request<SendResponse>("POST", "/bolt11/send_using_amount", req, options) as Promise<SendResponse>;
+    return this.http.request("POST", "/bolt11/send_using_amount", req, options);
   }
 
-  // POST /bolt11/pay (waits for completion)
+  /** Pay a BOLT11 invoice and wait for completion. (`POST /api/v1/bolt11/pay`) */
   bolt11Pay(req: Bolt11PayRequest, options?: RequestOptions): Promise<Bolt11PayResponse> {
-    return this.request<Bolt11PayResponse>("POST", "/bolt11/pay", req, options) as Promise<Bolt11PayResponse>;
+    return this.http.request("POST", "/bolt11/pay", req, options);
   }
 
-  // POST /spontaneous/send
-  spontaneousSend(req: SpontaneousSendRequest, options?: RequestOptions): Promise<SendResponse> {
-    // The Rust side uses r#type identifier but JSON key is "type".
-    const body = { ...req, custom_tlvs: req.custom_tlvs ?? [] };
-    return this.request<SendResponse>("POST", "/spontaneous/send", body, options) as Promise<SendResponse>;
-  }
-
-  // GET /payment/{paymentId}
-  getPayment(paymentIdHex: string, options?: RequestOptions): Promise<PaymentDetailsDto | null> {
-    if (!paymentIdHex) throw new Error("paymentIdHex is required");
-    return this.request<PaymentDetailsDto>(
-      "GET",
-      `/payment/${encodeURIComponent(paymentIdHex)}`,
-      undefined,
-      { ...options, returnNullOn404: true },
-    ) as Promise<PaymentDetailsDto | null>;
-  }
-
-  // GET /payments
-  payments(options?: RequestOptions): Promise<PaymentDetailsDto[]> {
-    return this.request<PaymentDetailsDto[]>("GET", "/payments", undefined, options) as Promise<
-      PaymentDetailsDto[]
-    >;
-  }
-
-  // POST /payment/{paymentId}/wait
-  paymentWait(paymentIdHex: string, req: PaymentWaitRequest = {}, options?: RequestOptions): Promise<PaymentWaitResponse> {
-    if (!paymentIdHex) throw new Error("paymentIdHex is required");
-    return this.request<PaymentWaitResponse>(
-      "POST",
-      `/payment/${encodeURIComponent(paymentIdHex)}/wait`,
-      req,
-      options,
-    ) as Promise<PaymentWaitResponse>;
-  }
-
-  // POST /payment/{paymentId}/abandon
-  paymentAbandon(paymentIdHex: string, options?: RequestOptions): Promise<OkResponse> {
-    if (!paymentIdHex) throw new Error("paymentIdHex is required");
-    return this.request<OkResponse>(
-      "POST",
-      `/payment/${encodeURIComponent(paymentIdHex)}/abandon`,
-      {},
-      options,
-    ) as Promise<OkResponse>;
-  }
-
-  // ---- BOLT12 offers ----
-
-  // POST /bolt12/offer/receive
+  /** Create a fixed-amount BOLT12 offer. (`POST /api/v1/bolt12/offer/receive`) */
   bolt12OfferReceive(req: Bolt12OfferReceiveRequest, options?: RequestOptions): Promise<Bolt12OfferResponse> {
-    return this.request<Bolt12OfferResponse>("POST", "/bolt12/offer/receive", req, options) as Promise<
-      Bolt12OfferResponse
-    >;
+    return this.http.request("POST", "/bolt12/offer/receive", req, options);
   }
 
-  // POST /bolt12/offer/receive_var
+  /** Create a variable-amount BOLT12 offer. (`POST /api/v1/bolt12/offer/receive_var`) */
   bolt12OfferReceiveVar(req: Bolt12OfferReceiveVarRequest, options?: RequestOptions): Promise<Bolt12OfferResponse> {
-    return this.request<Bolt12OfferResponse>(
-      "POST",
-      "/bolt12/offer/receive_var",
-      req,
-      options,
-    ) as Promise<Bolt12OfferResponse>;
+    return this.http.request("POST", "/bolt12/offer/receive_var", req, options);
   }
 
-  // POST /bolt12/offer/decode
+  /** Decode a BOLT12 offer. (`POST /api/v1/bolt12/offer/decode`) */
   bolt12OfferDecode(req: Bolt12OfferDecodeRequest, options?: RequestOptions): Promise<Bolt12OfferDecodeResponse> {
-    return this.request<Bolt12OfferDecodeResponse>("POST", "/bolt12/offer/decode", req, options) as Promise<
-      Bolt12OfferDecodeResponse
-    >;
+    return this.http.request("POST", "/bolt12/offer/decode", req, options);
   }
 
-  // POST /bolt12/offer/send
+  /** Pay a BOLT12 offer. (`POST /api/v1/bolt12/offer/send`) */
   bolt12OfferSend(req: Bolt12OfferSendRequest, options?: RequestOptions): Promise<SendResponse> {
-    return this.request<SendResponse>("POST", "/bolt12/offer/send", req, options) as Promise<SendResponse>;
+    return this.http.request("POST", "/bolt12/offer/send", req, options);
   }
 
-  // ---- BOLT12 refunds ----
-
-  // POST /bolt12/refund/initiate
+  /** Initiate a BOLT12 refund (payer side). (`POST /api/v1/bolt12/refund/initiate`) */
   bolt12RefundInitiate(req: Bolt12RefundInitiateRequest, options?: RequestOptions): Promise<Bolt12RefundInitiateResponse> {
-    return this.request<Bolt12RefundInitiateResponse>(
-      "POST",
-      "/bolt12/refund/initiate",
-      req,
-      options,
-    ) as Promise<Bolt12RefundInitiateResponse>;
+    return this.http.request("POST", "/bolt12/refund/initiate", req, options);
   }
 
-  // POST /bolt12/refund/decode
+  /** Decode a BOLT12 refund. (`POST /api/v1/bolt12/refund/decode`) */
   bolt12RefundDecode(req: Bolt12RefundDecodeRequest, options?: RequestOptions): Promise<Bolt12RefundDecodeResponse> {
-    return this.request<Bolt12RefundDecodeResponse>("POST", "/bolt12/refund/decode", req, options) as Promise<
-      Bolt12RefundDecodeResponse
-    >;
+    return this.http.request("POST", "/bolt12/refund/decode", req, options);
   }
 
-  // POST /bolt12/refund/request_payment
-  bolt12RefundRequestPayment(
-    req: Bolt12RefundRequestPaymentRequest,
-    options?: RequestOptions,
-  ): Promise<Bolt12RefundRequestPaymentResponse> {
-    return this.request<Bolt12RefundRequestPaymentResponse>(
-      "POST",
-      "/bolt12/refund/request_payment",
-      req,
-      options,
-    ) as Promise<Bolt12RefundRequestPaymentResponse>;
+  /** Request payment for a BOLT12 refund (payee side). (`POST /api/v1/bolt12/refund/request_payment`) */
+  bolt12RefundRequestPayment(req: Bolt12RefundRequestPaymentRequest, options?: RequestOptions): Promise<Bolt12RefundRequestPaymentResponse> {
+    return this.http.request("POST", "/bolt12/refund/request_payment", req, options);
   }
 
-  // POST /events/wait_next (long-poll)
-  eventsWaitNext(options?: RequestOptions): Promise<EventDto> {
-    return this.request<EventDto>("POST", "/events/wait_next", {}, options) as Promise<EventDto>;
+  /** Send a spontaneous (keysend) payment. (`POST /api/v1/spontaneous/send`) */
+  spontaneousSend(req: SpontaneousSendRequest, options?: RequestOptions): Promise<SendResponse> {
+    return this.http.request("POST", "/spontaneous/send", req, options);
   }
 
-  // POST /events/handled
-  eventsHandled(options?: RequestOptions): Promise<OkResponse> {
-    return this.request<OkResponse>("POST", "/events/handled", {}, options) as Promise<OkResponse>;
+  /** All payments. (`GET /api/v1/payments`) */
+  payments(options?: RequestOptions): Promise<PaymentDetailsDto[]> {
+    return this.http.request("GET", "/payments", undefined, options);
   }
-}
 
-function safeJsonParse(text: string): unknown {
-  try {
-    const value = parse(text, null, parseNumberAndBigInt);
-    return normalizeBigInts(value);
-  } catch {
-    return undefined;
+  /** One page of payments, newest first, with optional filters. (`POST /api/v1/payments/list`) */
+  paymentsList(req: PaymentsListRequest, options?: RequestOptions): Promise<PaymentsPage> {
+    return this.http.request("POST", "/payments/list", req, options);
   }
-}
 
-function jsonBigIntReplacer(_key: string, value: unknown): unknown {
-  if (typeof value === "bigint") {
-    const max = BigInt(Number.MAX_SAFE_INTEGER);
-    if (value <= max) return Number(value);
-    throw new Error("Request body contains a bigint larger than Number.MAX_SAFE_INTEGER");
+  /** A single payment. (`GET /api/v1/payment/{payment_id}`) */
+  payment(paymentId: PaymentId, options?: RequestOptions): Promise<PaymentDetailsDto> {
+    return this.http.request("GET", `/payment/${encodeURIComponent(paymentId)}`, undefined, options);
+  }
+
+  /** Wait for a payment to reach a terminal state. (`POST /api/v1/payment/{payment_id}/wait`) */
+  paymentWait(paymentId: PaymentId, req: PaymentWaitRequest, options?: RequestOptions): Promise<PaymentWaitResponse> {
+    return this.http.request("POST", `/payment/${encodeURIComponent(paymentId)}/wait`, req, options);
   }
-  return value;
-}
 
-function normalizeBigInts(value: unknown): unknown {
-  if (typeof value === "bigint") {
-    const max = BigInt(Number.MAX_SAFE_INTEGER);
-    const min = BigInt(Number.MIN_SAFE_INTEGER);
-    if (value <= max && value >= min) return Number(value);
-    return value;
+  /** Abandon an outbound payment. (`POST /api/v1/payment/{payment_id}/abandon`) */
+  paymentAbandon(paymentId: PaymentId, options?: RequestOptions): Promise<OkResponse> {
+    return this.http.request("POST", `/payment/${encodeURIComponent(paymentId)}/abandon`, undefined, options);
   }
 
-  if (Array.isArray(value)) {
-    return value.map(normalizeBigInts);
+  /** Long-poll for the next event (does not remove it). (`POST /api/v1/events/wait_next`) */
+  eventsWaitNext(options?: RequestOptions): Promise<QueuedEvent> {
+    return this.http.request("POST", "/events/wait_next", undefined, options);
   }
 
-  if (value && typeof value === "object") {
-    const obj = value as Record<string, unknown>;
-    for (const key of Object.keys(obj)) {
-      obj[key] = normalizeBigInts(obj[key]);
-    }
-    return obj;
+  /** Acknowledge the head event, optionally guarded by its id (the `seq` of a streamed event). (`POST /api/v1/events/handled`) */
+  eventsHandled(req: EventAckRequest, options?: RequestOptions): Promise<OkResponse> {
+    return this.http.request("POST", "/events/handled", req, options);
   }
 
-  return value;
+  /** Server-sent events: every queued event from the head on, then new ones as they arrive. (`GET /api/v1/events/stream`) */
+  eventsStream(options?: RequestOptions): AsyncGenerator<EventEnvelope> {
+    return this.http.stream("GET", "/events/stream", undefined, options);
+  }
 }
@@ -0,0 +1,68 @@
+import type { RgbLdkClient } from "./client.js";
+import type { EventDto, QueuedEvent } from "./types.js";
+
+export interface EventLoopOptions {
+  /** Stops the loop; an in-flight long-poll is aborted. */
+  signal?: AbortSignal;
+  /** Delay before retrying after a failed poll, handler or ACK (default 500 ms). */
+  retryDelayMs?: number;
+  /** Called for every failure; the loop keeps running. */
+  onError?: (err: unknown, event?: EventDto) => void;
+}
+
+/**
+ * Consumes the daemon's event queue with the ACK model from `docs/concepts/events-ack-model.md`:
+ * wait for the head event, run `onEvent`, and ACK (`/events/handled`) only once it resolved. The
+ * ACK names the event's `id`, so it fails with `409` instead of removing an event that another
+ * consumer put at the head in the meantime.
+ * If the handler throws, the event is not ACKed and is delivered again after `retryDelayMs`,
+ * so handlers must be idempotent. Resolves when `signal` is aborted.
+ */
+export async function eventLoop(
+  client: RgbLdkClient,
+  onEvent: (event: QueuedEvent) => Promise<void> | void,
+  opts: EventLoopOptions = {},
+): Promise<void> {
+  const { signal, retryDelayMs = 500, onError } = opts;
+  while (!signal?.aborted) {
+    let event: QueuedEvent;
+    try {
+      event = await client.eventsWaitNext({ signal });
+    } catch (e) {
+      if (signal?.aborted) return;
+      onError?.(e);
+      await sleep(retryDelayMs, signal);
+      continue;
+    }
+
+    try {
+      await onEvent(event);
+    } catch (e) {
+      onError?.(e, event);
+      await sleep(retryDelayMs, signal);
+      continue;
+    }
+
+    try {
+      await client.eventsHandled({ id: event.id }, { signal });
+    } catch (e) {
+      if (signal?.aborted) return;
+      // Not ACKed: the same event comes back from the next wait_next.
+      onError?.(e, event);
+      await sleep(retryDelayMs, signal);
+    }
+  }
+}
+
+function sleep(ms: number, signal?: AbortSignal): Promise<void> {
+  return new Promise((resolve) => {
+    if (signal?.aborted) return resolve();
+    const timer = setTimeout(done, ms);
+    signal?.addEventListener("abort", done, { once: true });
+    function done() {
+      clearTimeout(timer);
+      signal?.removeEventListener("abort", done);
+      resolve();
+    }
+  });
+}
@@ -0,0 +1,174 @@
+import { parse, parseNumberAndBigInt, stringify } from "lossless-json";
+import type { ErrorResponse } from "./types.js";
+
+export interface RequestOptions {
+  timeoutMs?: number;
+  headers?: Record<string, string>;
+  signal?: AbortSignal;
+}
+
+export interface ClientOptions {
+  fetch?: FetchLike;
+  headers?: Record<string, string>;
+}
+
+export type FetchLike = (input: RequestInfo | URL, init?: RequestInit) => Promise<Response>;
+
+/** Non-2xx answer from the daemon (mirrors `ApiError::Http` in the Rust client). */
+export class HttpError extends Error {
+  public readonly status: number;
+  /** Parsed `ErrorResponse`, when the body had that shape. */
+  public readonly error: ErrorResponse | undefined;
+  /** Parsed JSON body, or the raw text when it was not JSON. */
+  public readonly body: unknown;
+  constructor(message: string, status: number, error: ErrorResponse | undefined, body: unknown) {
+    super(message);
+    this.status = status;
+    this.error = error;
+    this.body = body;
+  }
+}
+
+/** JSON-over-fetch transport shared by the generated client. */
+export class HttpTransport {
+  private readonly baseUrl: string;
+  private readonly fetchFn: FetchLike;
+  private readonly defaultHeaders: Record<string, string>;
+
+  constructor(baseUrl: string, opts?: ClientOptions) {
+    if (!baseUrl) throw new Error("baseUrl is required");
+    this.baseUrl = baseUrl.replace(/\/$/, "");
+    const globalFetch: any = (globalThis as any).fetch;
+    this.fetchFn = opts?.fetch ?? (globalFetch?.bind(globalThis) as FetchLike);
+    if (!this.fetchFn) {
+      throw new Error("No fetch implementation found. Provide opts.fetch or a global fetch");
+    }
+    this.defaultHeaders = { "Content-Type": "application/json", ...(opts?.headers ?? {}) };
+  }
+
+  /**
+   * Sends a request and decodes the JSON answer. POSTs without a body send `{}`.
+   * Statuses in `alsoOk` are decoded like a 2xx (e.g. 503 from `/readyz`).
+   */
+  async request<T>(
+    method: "GET" | "POST",
+    path: string,
+    body?: unknown,
+    options?: RequestOptions,
+    alsoOk: number[] = [],
+  ): Promise<T> {
+    const url = `${this.baseUrl}${path}`;
+    const headers = { ...this.defaultHeaders, ...(options?.headers ?? {}) };
+    const controller = new AbortController();
+    const timeout = options?.timeoutMs && options.timeoutMs > 0
+      ? setTimeout(() => controller.abort(), options.timeoutMs)
+      : undefined;
+    if (options?.signal) {
+      if (options.signal.aborted) controller.abort();
+      else options.signal.addEventListener("abort", () => controller.abort());
+    }
+    const payload = body !== undefined ? stringify(body) : method === "POST" ? "{}" : undefined;
+
+    try {
+      const resp = await this.fetchFn(url, {
+        method,
+        headers,
+        body: payload,
+        signal: controller.signal,
+      });
+      const text = await resp.text();
+      const json = text ? safeJsonParse(text) : undefined;
+      if (!resp.ok && !alsoOk.includes(resp.status)) {
+        const error = typeof (json as any)?.error === "string" ? (json as ErrorResponse) : undefined;
+        throw new HttpError(error?.error ?? `HTTP ${resp.status}`, resp.status, error, json ?? text);
+      }
+      return (json as T) ?? ({} as T);
+    } catch (e: any) {
+      if (e?.name === "AbortError") {
+        throw new Error("Request aborted");
+      }
+      throw e;
+    } finally {
+      if (timeout) clearTimeout(timeout);
+    }
+  }
+
+  /**
+   * Opens a server-sent event stream and yields the JSON `data` of each event. Comment lines
+   * (keep-alives) are skipped. Ends when the daemon closes the stream or `options.signal` aborts.
+   */
+  async *stream<T>(
+    method: "GET" | "POST",
+    path: string,
+    body?: unknown,
+    options?: RequestOptions,
+  ): AsyncGenerator<T> {
+    const url = `${this.baseUrl}${path}`;
+    const headers = { ...this.defaultHeaders, Accept: "text/event-stream", ...(options?.headers ?? {}) };
+    const payload = body !== undefined ? stringify(body) : method === "POST" ? "{}" : undefined;
+    const resp = await this.fetchFn(url, { method, headers, body: payload, signal: options?.signal });
+    if (!resp.ok || !resp.body) {
+      const text = await resp.text();
+      const json = text ? safeJsonParse(text) : undefined;
+      const error = typeof (json as any)?.error === "string" ? (json as ErrorResponse) : undefined;
+      throw new HttpError(error?.error ?? `HTTP ${resp.status}`, resp.status, error, json ?? text);
+    }
+
+    const reader = resp.body.pipeThrough(new TextDecoderStream()).getReader();
+    let buffer = "";
+    let data: string[] = [];
+    try {
+      for (;;) {
+        const { value, done } = await reader.read();
+        if (done) return;
+        buffer += value;
+        let nl: number;
+        while ((nl = buffer.indexOf("\n")) >= 0) {
+          const line = buffer.slice(0, nl).replace(/\r$/, "");
+          buffer = buffer.slice(nl + 1);
+          if (line === "") {
+            if (data.length) yield safeJsonParse(data.join("\n")) as T;
+            data = [];
+          } else if (line.startsWith("data:")) {
+            data.push(line.slice(5).replace(/^ /, ""));
+          }
+        }
+      }
+    } finally {
+      reader.releaseLock();
+    }
+  }
+}
+
+function safeJsonParse(text: string): unknown {
+  try {
+    const value = parse(text, null, parseNumberAndBigInt);
+    return normalizeBigInts(value);
+  } catch {
+    return undefined;
+  }
+}
+
+/** Keeps integers as `number` when they are safe and as `bigint` otherwise (see `Int`). */
+function normalizeBigInts(value: unknown): unknown {
+  if (typeof value === "bigint") {
+    const max = BigInt(Number.MAX_SAFE_INTEGER);
+    const min = BigInt(Number.MIN_SAFE_INTEGER);
+    if (value <= max && value >= min) return Number(value);
+    return value;
+  }
+
+  if (Array.isArray(value)) {
+    return value.map(normalizeBigInts);
+  }
+
+  if (value && typeof value === "object") {
+    const obj = value as Record<string, unknown>;
+    for (const key of Object.keys(obj)) {
+      obj[key] = normalizeBigInts(obj[key]);
+    }
+    return obj;
+  }
+
+  return value;
+}
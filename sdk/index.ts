@@ -1,2 +1,4 @@
 export * from "./types.js";
+export * from "./http.js";
 export * from "./client.js";
+export * from "./events.js";
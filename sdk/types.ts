@@ -1,278 +1,666 @@
-// TypeScript DTOs mirroring src/http/dto.rs
+// Generated from the rgbldk-api crate; do not edit by hand.
+// Regenerate with `UPDATE_SDK=1 cargo test -p rgbldk-api --features schema --test sdk`.
 
+/** JSON integer that may exceed `Number.MAX_SAFE_INTEGER` (u64 on the Rust side). */
 export type Int = number | bigint;
 
-export interface StatusDto {
-  is_running: boolean;
-  is_listening: boolean;
-  best_block_height: number;
-}
+/** Hex-encoded asset id (32 bytes). */
+export type AssetId = string;
 
+/** Wallet and channel balance overview. */
 export interface BalancesDto {
-  total_onchain_balance_sats: Int;
+  /** Spendable on-chain balance in satoshis. */
   spendable_onchain_balance_sats: Int;
+  /** Sum of sats reserved for anchor channels. */
   total_anchor_channels_reserve_sats: Int;
+  /** Total claimable Lightning balance in satoshis. */
   total_lightning_balance_sats: Int;
+  /** Total confirmed on-chain balance in satoshis. */
+  total_onchain_balance_sats: Int;
 }
 
-export interface PeerDetailsDto {
-  node_id: string;
-  address: string;
-  is_persisted: boolean;
-  is_connected: boolean;
+/** Request to decode a Bolt11 invoice. */
+export interface Bolt11DecodeRequest {
+  /** The Bolt11 invoice string. */
+  invoice: string;
 }
 
-export interface PeerConnectRequest {
-  node_id: string;
-  address: string;
-  persist?: boolean;
+/** Decoded Bolt11 invoice summary. */
+export interface Bolt11DecodeResponse {
+  /** Amount in millisatoshis, if the invoice is fixed-amount. */
+  amount_msat?: Int | null;
+  /** Destination node id (hex pubkey). */
+  destination: NodeId;
+  /** Invoice expiry in seconds. */
+  expiry_secs: Int;
+  /** Payment hash (hex). */
+  payment_hash: string;
 }
 
-export interface PeerDisconnectRequest {
-  node_id: string;
+/** Request to pay a Bolt11 invoice and wait for completion. */
+export interface Bolt11PayRequest {
+  /** Amount in millisatoshis (required for variable-amount invoices). */
+  amount_msat?: Int | null;
+  /** The Bolt11 invoice string. */
+  invoice: string;
 }
 
-export type PaymentDirection = "Inbound" | "Outbound";
-export type PaymentStatus = "Pending" | "Succeeded" | "Failed";
-export type PaymentKind =
-  | "Bolt11"
-  | "Bolt11Jit"
-  | "Bolt12Offer"
-  | "Bolt12Refund"
-  | "Spontaneous"
-  | "Onchain";
+/** Response of a completed Bolt11 payment. */
+export interface Bolt11PayResponse {
+  /** Amount paid in satoshis. */
+  amount_sats: Int;
+  /** Destination node id (hex pubkey). */
+  destination: NodeId;
+  /** Fee paid in millisatoshis, if known. */
+  fee_paid_msat?: Int | null;
+  /** Payment id (hex-encoded 32 bytes). */
+  payment_id: PaymentId;
+  /** Payment preimage (hex-encoded 32 bytes). */
+  preimage: string;
+}
 
-export interface PaymentDetailsDto {
-  id: string;
-  direction: PaymentDirection;
-  status: PaymentStatus;
-  amount_msat: Int | null;
-  kind: PaymentKind;
-  fee_paid_msat: Int | null;
-  kind_details?: any;
+/** Request to create a Bolt11 invoice. */
+export interface Bolt11ReceiveRequest {
+  /** Amount in millisatoshis. */
+  amount_msat: Int;
+  /** Invoice description. */
+  description: string;
+  /** Invoice expiry in seconds. */
+  expiry_secs: number;
 }
 
-export interface ChannelDetailsDto {
-  channel_id: string;
-  counterparty_node_id: string;
-  is_channel_ready: boolean;
-  is_announced: boolean;
+/** Response containing a newly created Bolt11 invoice. */
+export interface Bolt11ReceiveResponse {
+  /** The invoice string. */
+  invoice: string;
 }
 
-export interface ChannelDetailsExtendedDto {
-  channel_id: string;
-  user_channel_id: string;
-  counterparty_node_id: string;
-  channel_point: string | null;
-  channel_value_sats: Int;
-  outbound_capacity_msat: Int;
-  inbound_capacity_msat: Int;
-  is_channel_ready: boolean;
-  is_usable: boolean;
-  is_announced: boolean;
+/** Request to create a variable-amount Bolt11 invoice. */
+export interface Bolt11ReceiveVarRequest {
+  /** Invoice description. */
+  description: string;
+  /** Invoice expiry in seconds. */
+  expiry_secs: number;
 }
 
-export interface OpenChannelRequest {
-  node_id: string;
-  address: string;
-  channel_amount_sats: number;
-  push_to_counterparty_msat?: number;
-  announce?: boolean;
+/** Request to pay a Bolt11 invoice. */
+export interface Bolt11SendRequest {
+  /** The Bolt11 invoice string. */
+  invoice: string;
 }
 
-export interface OpenChannelResponse {
-  user_channel_id: string;
+/** Request to pay a Bolt11 invoice using a specified amount. */
+export interface Bolt11SendUsingAmountRequest {
+  /** Amount in millisatoshis to pay. */
+  amount_msat: Int;
+  /** The Bolt11 invoice string. */
+  invoice: string;
 }
 
-export interface CloseChannelRequest {
-  user_channel_id: string;
-  counterparty_node_id: string;
+/** Request to decode a BOLT12 offer. */
+export interface Bolt12OfferDecodeRequest {
+  /** Bech32-encoded offer (HRP `lno`). */
+  offer: string;
 }
 
-export interface Bolt11ReceiveRequest {
-  amount_msat: number;
-  description: string;
-  expiry_secs: number;
+/** Decoded BOLT12 offer summary. */
+export interface Bolt12OfferDecodeResponse {
+  /** Seconds since Unix epoch. */
+  absolute_expiry_unix_secs?: Int | null;
+  /** Offer amount in millisatoshis, if fixed-amount. */
+  amount_msat?: Int | null;
+  /** Chain hashes this offer supports (hex). */
+  chain_hashes?: string[];
+  /** Offer description, if present. */
+  description?: string | null;
+  /** Whether the offer expects a quantity in the invoice request. */
+  expects_quantity: boolean;
+  /** Offer issuer, if present. */
+  issuer?: string | null;
+  /** Offer id (hex-encoded 32 bytes). */
+  offer_id: string;
+  /** Number of blinded message paths. */
+  paths_count: Int;
+  /** Issuer signing pubkey (hex), if present. */
+  signing_pubkey?: string | null;
 }
 
-export interface Bolt11ReceiveVarRequest {
+/** Request to create a fixed-amount BOLT12 offer. */
+export interface Bolt12OfferReceiveRequest {
+  /** Amount in millisatoshis. */
+  amount_msat: Int;
+  /** Offer description. */
   description: string;
-  expiry_secs: number;
+  /** Seconds from now; if omitted, offer does not expire. */
+  expiry_secs?: number | null;
+  /** Optional item quantity. */
+  quantity?: Int | null;
 }
 
-export interface Bolt11ReceiveResponse {
-  invoice: string;
+/** Request to create a variable-amount (zero-amount) BOLT12 offer. */
+export interface Bolt12OfferReceiveVarRequest {
+  /** Offer description. */
+  description: string;
+  /** Seconds from now; if omitted, offer does not expire. */
+  expiry_secs?: number | null;
 }
 
-export interface Bolt11SendRequest {
-  invoice: string;
+/** Response containing an encoded BOLT12 offer string. */
+export interface Bolt12OfferResponse {
+  /** Bech32-encoded offer (HRP `lno`). */
+  offer: string;
 }
 
-export interface Bolt11SendUsingAmountRequest {
-  invoice: string;
-  amount_msat: number;
+/** Request to pay a BOLT12 offer. */
+export interface Bolt12OfferSendRequest {
+  /** Required for zero-amount offers; may be used to overpay fixed-amount offers. */
+  amount_msat?: Int | null;
+  /** Bech32-encoded offer (HRP `lno`). */
+  offer: string;
+  /** Optional payer note to include in the invoice request. */
+  payer_note?: string | null;
+  /** Optional item quantity. */
+  quantity?: Int | null;
 }
 
-export interface SendResponse {
-  payment_id: string;
+/** Request to decode a BOLT12 refund. */
+export interface Bolt12RefundDecodeRequest {
+  /** Bech32-encoded refund (HRP `lnr`). */
+  refund: string;
 }
 
-export interface Bolt11DecodeRequest {
-  invoice: string;
+/** Decoded BOLT12 refund summary. */
+export interface Bolt12RefundDecodeResponse {
+  /** Seconds since Unix epoch. */
+  absolute_expiry_unix_secs?: Int | null;
+  /** Refund amount in millisatoshis. */
+  amount_msat: Int;
+  /** Chain hash the refund is valid for (hex). */
+  chain_hash: string;
+  /** Refund description. */
+  description: string;
+  /** Refund issuer, if present. */
+  issuer?: string | null;
+  /** Number of blinded message paths. */
+  paths_count: Int;
+  /** Optional payer note. */
+  payer_note?: string | null;
+  /** Payer signing pubkey (hex). */
+  payer_signing_pubkey: string;
+  /** Optional item quantity. */
+  quantity?: Int | null;
 }
 
-export interface Bolt11DecodeResponse {
-  payment_hash: string;
-  destination: string;
-  amount_msat: Int | null;
+/** Request to initiate a BOLT12 refund (payer-side), returning an encoded refund string. */
+export interface Bolt12RefundInitiateRequest {
+  /** Amount in millisatoshis. */
+  amount_msat: Int;
+  /** Refund expiry in seconds from now. */
   expiry_secs: number;
+  /** Optional payer note. */
+  payer_note?: string | null;
+  /** Optional item quantity. */
+  quantity?: Int | null;
 }
 
-export interface Bolt11PayRequest {
+/** Response of a successfully initiated refund. */
+export interface Bolt12RefundInitiateResponse {
+  /** Payment id associated with the refund flow (hex-encoded 32 bytes). */
+  payment_id: PaymentId;
+  /** Bech32-encoded refund (HRP `lnr`). */
+  refund: string;
+}
+
+/** Request to respond to a refund by creating and sending an invoice (payee-side). */
+export interface Bolt12RefundRequestPaymentRequest {
+  /** Bech32-encoded refund (HRP `lnr`). */
+  refund: string;
+}
+
+/** Response for a refund payment request. */
+export interface Bolt12RefundRequestPaymentResponse {
+  /** Informational only (bech32-encoded BOLT12 invoice, HRP `lni`). */
   invoice: string;
-  amount_msat?: number | null;
+  /** Informational only (raw TLV bytes hex-encoded). */
+  invoice_hex: string;
+  /** Payment id for tracking the inbound refund payment (hex-encoded 32 bytes). */
+  payment_id: PaymentId;
 }
 
-export interface Bolt11PayResponse {
-  payment_id: string;
-  preimage: string;
-  amount_sats: Int;
-  destination: string;
-  fee_paid_msat: Int | null;
+/** Channel details entry (extended for control-plane integrations). */
+export interface ChannelDetailsExtendedDto {
+  /** Channel id (32-byte hex). */
+  channel_id: ChannelId;
+  /** Funding outpoint formatted as `txid:vout` if known. */
+  channel_point?: string | null;
+  /** Total channel capacity in satoshis. */
+  channel_value_sats: Int;
+  /** Counterparty node id (hex pubkey). */
+  counterparty_node_id: NodeId;
+  /** Receivable capacity in millisatoshis. */
+  inbound_capacity_msat: Int;
+  /** Whether channel is announced. */
+  is_announced: boolean;
+  /** Whether channel is ready. */
+  is_channel_ready: boolean;
+  /** Whether channel is usable. */
+  is_usable: boolean;
+  /** Sendable capacity in millisatoshis. */
+  outbound_capacity_msat: Int;
+  /**
+   * RGB asset balance in this channel, if any.
+   * Only present for RGB-enabled channels.
+   */
+  rgb_balance?: RgbChannelBalanceDto | null;
+  /** User channel id (hex-encoded 16 bytes BIG-ENDIAN). */
+  user_channel_id: UserChannelId;
+}
+
+/** Hex-encoded channel id (32 bytes). */
+export type ChannelId = string;
+
+/** Request to close or force-close a channel. */
+export interface CloseChannelRequest {
+  /** Counterparty node id. */
+  counterparty_node_id: NodeId;
+  /** User channel id (hex-encoded 16 bytes BIG-ENDIAN). */
+  user_channel_id: UserChannelId;
 }
 
+/** Custom TLV record as passed to keysend. */
 export interface CustomTlvDto {
-  type: number; // r#type in Rust
+  /** TLV type number. */
+  type: Int;
+  /** Hex-encoded value bytes. */
   value_hex: string;
 }
 
-export interface SpontaneousSendRequest {
-  counterparty_node_id: string;
-  amount_msat: number;
-  custom_tlvs?: CustomTlvDto[];
+/** Generic `{ error: "..." }` error response. */
+export interface ErrorResponse {
+  /** Human-readable error message. */
+  error: string;
 }
 
-export interface OutPointDto {
-  txid: string;
-  vout: number;
+/** Request to acknowledge the head event. */
+export interface EventAckRequest {
+  /**
+   * `id` of the event; the daemon answers `409` if it is not the head, and acknowledging an
+   * event that is already gone is a no-op. Without it the head is acknowledged, whatever it is.
+   */
+  id?: Int | null;
 }
 
+/** An event from the daemon's queue. */
 export type EventDto =
-  | { type: "PaymentSuccessful"; data: { payment_id: string | null; fee_paid_msat: Int | null } }
-  | { type: "PaymentFailed"; data: { payment_id: string | null } }
-  | { type: "PaymentReceived"; data: { payment_id: string | null; amount_msat: Int } }
-  | { type: "ChannelPending"; data: { funding_txo: OutPointDto } }
-  | { type: "ChannelReady"; data: { user_channel_id: string } }
-  | { type: "ChannelClosed"; data: {} }
-  | { type: "Other"; data: { kind: string } };
-
+  | {
+    type: "PaymentSuccessful";
+    data: {
+      /** Total fee paid in millisatoshis, if provided. */
+      fee_paid_msat?: Int | null;
+      /** Optional payment id. */
+      payment_id?: PaymentId | null;
+    };
+  }
+  | {
+    type: "PaymentFailed";
+    data: {
+      /** Optional payment id. */
+      payment_id?: PaymentId | null;
+    };
+  }
+  | {
+    type: "PaymentReceived";
+    data: {
+      /** Amount received in millisatoshis. */
+      amount_msat: Int;
+      /** Optional payment id. */
+      payment_id?: PaymentId | null;
+    };
+  }
+  | {
+    type: "ChannelPending";
+    data: {
+      /** Funding outpoint of the channel transaction. */
+      funding_txo: OutPointDto;
+    };
+  }
+  | {
+    type: "ChannelReady";
+    data: {
+      /** User channel id (hex-encoded 16 bytes BIG-ENDIAN). */
+      user_channel_id: UserChannelId;
+    };
+  }
+  | {
+    type: "ChannelClosed";
+    data: {
+      /** Channel id (hex-encoded 32 bytes). */
+      channel_id: ChannelId;
+      /** Counterparty node id (hex pubkey), if known. */
+      counterparty_node_id?: NodeId | null;
+      /** Closure reason (Debug string), if provided by the node. */
+      reason?: string | null;
+      /** User channel id (hex-encoded 16 bytes BIG-ENDIAN). */
+      user_channel_id: UserChannelId;
+    };
+  };
+
+/** Event pushed on `/events/stream`, with its position in the queue. */
+export interface EventEnvelope {
+  /** The event. */
+  event: EventDto;
+  /**
+   * Sequence number; one higher for every queued event, never reused. Acknowledge the event
+   * with `POST /events/handled` and this number as `id`.
+   */
+  seq: Int;
+}
+
+/** A single health/readiness sub-check. */
 export interface HealthCheckDto {
-  name: string;
-  ok: boolean;
+  /** Optional short detail. */
   detail?: string | null;
+  /** Optional user-actionable hint. */
   hint?: string | null;
-}
-
-export interface OkResponse {
+  /** Machine-readable check name. */
+  name: string;
+  /** Whether the check passed. */
   ok: boolean;
-  checks?: HealthCheckDto[];
-}
-
-export interface NodeIdResponse {
-  node_id: string;
 }
 
+/** Response listing the P2P addresses the node listens on. */
 export interface ListeningAddressesResponse {
+  /** Socket addresses, e.g. `0.0.0.0:9735`. */
   addresses: string[];
 }
 
-// ---- BOLT12 (offers + refunds) ----
+/** Hex-encoded node id (33 bytes). */
+export type NodeId = string;
 
-export interface Bolt12OfferReceiveRequest {
-  amount_msat: number;
-  description: string;
-  expiry_secs?: number | null;
-  quantity?: number | null;
+/** Response containing the node's own id. */
+export interface NodeIdResponse {
+  /** Node id (hex pubkey). */
+  node_id: NodeId;
 }
 
-export interface Bolt12OfferReceiveVarRequest {
-  description: string;
-  expiry_secs?: number | null;
+/** Generic `{ ok: true }` response. */
+export interface OkResponse {
+  /** Optional list of sub-checks providing more details about how `ok` was determined. */
+  checks?: HealthCheckDto[];
+  /** Whether the operation succeeded. */
+  ok: boolean;
 }
 
-export interface Bolt12OfferResponse {
-  offer: string; // bech32 lno...
+/** Request to open a channel to a peer. */
+export interface OpenChannelRequest {
+  /** Socket address of the counterparty as advertised. */
+  address: string;
+  /** Whether to create an announced channel (default true). */
+  announce?: boolean | null;
+  /** Channel capacity in satoshis. */
+  channel_amount_sats: Int;
+  /** Counterparty node id (hex secp256k1 pubkey). */
+  node_id: NodeId;
+  /** Optional initial push to counterparty in msat. */
+  push_to_counterparty_msat?: Int | null;
 }
 
-export interface Bolt12OfferDecodeRequest {
-  offer: string;
+/** Response of a successful channel open request. */
+export interface OpenChannelResponse {
+  /** User channel id (hex-encoded 16 bytes BIG-ENDIAN). */
+  user_channel_id: UserChannelId;
 }
 
-export interface Bolt12OfferDecodeResponse {
-  offer_id: string;
-  signing_pubkey?: string | null;
-  description?: string | null;
-  issuer?: string | null;
-  amount_msat?: Int | null;
-  absolute_expiry_unix_secs?: Int | null;
-  chain_hashes: string[];
-  paths_count: number;
-  expects_quantity: boolean;
+/** Outpoint representation. */
+export interface OutPointDto {
+  /** Transaction id (hex string). */
+  txid: string;
+  /** Output index. */
+  vout: number;
 }
 
-export interface Bolt12OfferSendRequest {
-  offer: string;
-  amount_msat?: number | null;
-  quantity?: number | null;
-  payer_note?: string | null;
+/** Payment details entry. */
+export interface PaymentDetailsDto {
+  /** Amount in millisatoshis, if known. */
+  amount_msat?: Int | null;
+  /**
+   * Unix time (seconds) the daemon first recorded the payment; absent from daemons that do
+   * not track it.
+   */
+  created_at?: Int | null;
+  /** Direction, either Inbound or Outbound. */
+  direction: PaymentDirection;
+  /** Fee paid in millisatoshis, if known. */
+  fee_paid_msat?: Int | null;
+  /** Payment id (hex-encoded 32 bytes). */
+  id: PaymentId;
+  /** Payment kind label. */
+  kind: PaymentKind;
+  /** Kind-specific details (when available). */
+  kind_details?: PaymentKindDetails | null;
+  /** Status, one of Pending, Succeeded, Failed. */
+  status: PaymentStatus;
 }
 
-export interface Bolt12RefundInitiateRequest {
-  amount_msat: number;
-  expiry_secs: number;
-  quantity?: number | null;
-  payer_note?: string | null;
+export type PaymentDirection = "Inbound" | "Outbound" | (string & {});
+
+/** Hex-encoded payment id (32 bytes). */
+export type PaymentId = string;
+
+export type PaymentKind = "Bolt11" | "Bolt11Jit" | "Bolt12Offer" | "Bolt12Refund" | "Spontaneous" | "Onchain" | (string & {});
+
+/**
+ * Kind-specific payment details, tagged by `type`.
+ *
+ * Hashes, preimages, secrets, offer ids and txids are hex strings.
+ */
+export type PaymentKindDetails =
+  | {
+    type: "Bolt11";
+    /** Payment hash. */
+    payment_hash: string;
+    /** Payment preimage, once known. */
+    preimage?: string | null;
+    /** Payment secret, if known. */
+    secret?: string | null;
+  }
+  | {
+    type: "Bolt11Jit";
+    /** Payment hash. */
+    payment_hash: string;
+    /** Payment preimage, once known. */
+    preimage?: string | null;
+    /** Payment secret, if known. */
+    secret?: string | null;
+  }
+  | {
+    type: "Bolt12Offer";
+    /** Id of the offer this payment is for. */
+    offer_id: string;
+    /** Payer note from the invoice request. */
+    payer_note?: string | null;
+    /** Payment hash, once an invoice has been received. */
+    payment_hash?: string | null;
+    /** Payment preimage, once known. */
+    preimage?: string | null;
+    /** Item quantity from the invoice request. */
+    quantity?: Int | null;
+    /** Payment secret, if known. */
+    secret?: string | null;
+  }
+  | {
+    type: "Bolt12Refund";
+    /** Payer note from the refund. */
+    payer_note?: string | null;
+    /** Payment hash, once an invoice has been received. */
+    payment_hash?: string | null;
+    /** Payment preimage, once known. */
+    preimage?: string | null;
+    /** Item quantity from the refund. */
+    quantity?: Int | null;
+    /** Payment secret, if known. */
+    secret?: string | null;
+  }
+  | {
+    type: "Spontaneous";
+    /** Payment hash. */
+    payment_hash: string;
+    /** Payment preimage, once known. */
+    preimage?: string | null;
+  }
+  | {
+    type: "Onchain";
+    /** Transaction id. */
+    txid: string;
+  }
+  | { [key: string]: unknown };
+
+export type PaymentStatus = "Pending" | "Succeeded" | "Failed" | (string & {});
+
+/** Request to wait for a payment to reach a terminal state. */
+export interface PaymentWaitRequest {
+  /** Default: 60 seconds. */
+  timeout_secs?: number | null;
 }
 
-export interface Bolt12RefundInitiateResponse {
-  refund: string; // bech32 lnr...
-  payment_id: string;
+/** Response of a waited payment result. */
+export interface PaymentWaitResponse {
+  /** Explainability sub-checks for the wait flow. */
+  checks?: HealthCheckDto[];
+  /** Whether the wait succeeded (i.e., payment succeeded). */
+  ok: boolean;
+  /** The final payment details. */
+  payment: PaymentDetailsDto;
 }
 
-export interface Bolt12RefundDecodeRequest {
-  refund: string;
+/**
+ * Request for one page of payments, newest first.
+ *
+ * All filters are optional and combine with AND. A payment without `created_at` never matches
+ * `created_after` or `created_before`.
+ */
+export interface PaymentsListRequest {
+  /** Only payments created at or after this Unix time (seconds). */
+  created_after?: Int | null;
+  /** Only payments created before this Unix time (seconds). */
+  created_before?: Int | null;
+  /** `next_cursor` of the previous page; omit for the first page. */
+  cursor?: string | null;
+  /** Only payments in this direction. */
+  direction?: PaymentDirection | null;
+  /** Only payments of this kind. */
+  kind?: PaymentKind | null;
+  /**
+   * Page size, 1 to [`PaymentsListRequest::MAX_LIMIT`]. Default:
+   * [`PaymentsListRequest::DEFAULT_LIMIT`].
+   */
+  limit?: number | null;
+  /** Only payments with this status. */
+  status?: PaymentStatus | null;
+}
+
+/** One page of payments. */
+export interface PaymentsPage {
+  /** Payments on this page, newest first. */
+  items: PaymentDetailsDto[];
+  /** Cursor for the next page; `null` on the last page. */
+  next_cursor?: string | null;
+}
+
+/** Request to connect to a peer. */
+export interface PeerConnectRequest {
+  /** Socket address, e.g. `lnd1:9735`. */
+  address: string;
+  /** Peer node id in hex. */
+  node_id: NodeId;
+  /** Whether to persist the peer to disk. */
+  persist?: boolean;
 }
 
-export interface Bolt12RefundDecodeResponse {
-  description: string;
-  issuer?: string | null;
-  amount_msat: Int;
-  absolute_expiry_unix_secs?: Int | null;
-  chain_hash: string;
-  payer_signing_pubkey: string;
-  payer_note?: string | null;
-  quantity?: Int | null;
-  paths_count: number;
+/** Peer details entry. */
+export interface PeerDetailsDto {
+  /** Selected address for the peer. */
+  address: string;
+  /** Whether peer is currently connected. */
+  is_connected: boolean;
+  /** Whether peer is persisted to peer store. */
+  is_persisted: boolean;
+  /** Peer node id in hex. */
+  node_id: NodeId;
 }
 
-export interface Bolt12RefundRequestPaymentRequest {
-  refund: string;
+/** Request to disconnect a peer. */
+export interface PeerDisconnectRequest {
+  /** Peer node id in hex. */
+  node_id: NodeId;
+}
+
+/** Event returned by `/events/wait_next`: the event's `type` and `data`, plus its sequence id. */
+export type QueuedEvent = EventDto & {
+  /**
+   * Sequence id; one higher for every queued event, never reused, and the same as the `seq`
+   * of the event on `/events/stream`. Absent from daemons that predate event ids.
+   */
+  id?: Int;
+};
+
+/** RGB asset balance in a channel. */
+export interface RgbChannelBalanceDto {
+  /** Asset ID (hex-encoded 32 bytes). */
+  asset_id: AssetId;
+  /** Local (our) RGB balance in this channel. */
+  local_amount: Int;
+  /** Remote (counterparty) RGB balance in this channel. */
+  remote_amount: Int;
+}
+
+/** Response containing the id of a sent payment. */
+export interface SendResponse {
+  /** Payment id (hex-encoded 32 bytes). */
+  payment_id: PaymentId;
 }
 
-export interface Bolt12RefundRequestPaymentResponse {
-  invoice: string;
-  invoice_hex: string;
-  payment_id: string;
+/** Request to send a spontaneous (keysend) payment. */
+export interface SpontaneousSendRequest {
+  /** Amount in millisatoshis. */
+  amount_msat: Int;
+  /** Counterparty node id (hex pubkey). */
+  counterparty_node_id: NodeId;
+  /** Optional custom TLV list. */
+  custom_tlvs?: CustomTlvDto[];
 }
 
-// ---- Payments (unified) ----
-
-export interface PaymentWaitRequest {
-  timeout_secs?: number | null;
+/** Node status response. */
+export interface StatusDto {
+  /** Current best block height as seen by the node. */
+  best_block_height: number;
+  /** Whether the node is currently listening for inbound peers. */
+  is_listening: boolean;
+  /** Whether the node runtime is running. */
+  is_running: boolean;
 }
 
-export interface PaymentWaitResponse {
-  ok: boolean;
-  payment: PaymentDetailsDto;
-  checks?: HealthCheckDto[];
+/** Hex-encoded user channel id (16 bytes). */
+export type UserChannelId = string;
+
+/** Daemon version information. */
+export interface VersionResponse {
+  /** Version of the `rgbldk-api` crate the daemon was built with. */
+  api_crate_version: string;
+  /** HTTP API version, e.g. `v1`. */
+  api_version: string;
+  /**
+   * Operation ids of the routes this daemon serves (see `rgbldk_api::routes::ROUTES`).
+   *
+   * Empty when the daemon predates capability lists.
+   */
+  capabilities?: string[];
+  /** Version of the node core crate. */
+  core_crate_version: string;
+}
+
+/** Response containing a fresh on-chain wallet address. */
+export interface WalletNewAddressResponse {
+  /** Bitcoin address. */
+  address: string;
 }